@@ -1,3 +1,6 @@
+use std::collections::{BTreeSet, HashSet};
+use std::ops::Bound;
+
 use anyhow::Result;
 use surrealdb_types::SurrealValue;
 use surrealism::surrealism;
@@ -19,7 +22,8 @@ use surrealism::surrealism;
 //     }
 // }
 
-#[surrealism]
+/// Whether someone of the given age is old enough to drive.
+#[surrealism(pure)]
 fn can_drive(age: i64) -> bool {
 	age >= 18
 
@@ -48,6 +52,14 @@ fn can_drive_bla(age: i64) -> bool {
 	age >= 18
 }
 
+/// Double a number.
+///
+/// Declared in the `math` namespace.
+#[surrealism(namespace = "math", name = "other", pure)]
+fn math_double(value: i64) -> i64 {
+	value * 2
+}
+
 #[surrealism(default)]
 fn def(age: i64) -> bool {
 	age >= 18
@@ -144,12 +156,29 @@ fn test_kv() -> Result<()> {
 	assert_eq!(values, vec![10, 30, 50], "values after del_batch");
 
 	// range delete
-	surrealism::kv::del_rng(.."test4".to_string()).expect("del_rng_to_4");
+	let deleted = surrealism::kv::del_rng(.."test4".to_string()).expect("del_rng_to_4");
+	assert_eq!(deleted, 2, "deleted by del_rng_to_4");
 	let values: Vec<i64> = surrealism::kv::values(..).expect("values after del_rng_to_4");
 	assert_eq!(values, vec![50], "values after del_rng_to_4");
-	surrealism::kv::del_rng(..).expect("del_rng");
+	let deleted = surrealism::kv::del_rng(..).expect("del_rng");
+	assert_eq!(deleted, 1, "deleted by del_rng");
 	let count = surrealism::kv::count(..).expect("count after del_rng");
 	assert_eq!(count, 0, "count after del_rng");
+	let deleted = surrealism::kv::del_rng(..).expect("del_rng empty");
+	assert_eq!(deleted, 0, "deleted by del_rng empty");
+
+	// Range deletes respect the end bound
+	surrealism::kv::set_batch([("a", 1), ("b", 2), ("c", 3), ("d", 4)]).expect("set a..d");
+	let deleted = surrealism::kv::del_rng("a".to_string().."c".to_string()).expect("del_rng a..c");
+	assert_eq!(deleted, 2, "deleted by del_rng a..c");
+	assert!(surrealism::kv::exists("c").expect("exists c"), "c kept by del_rng a..c");
+	let deleted =
+		surrealism::kv::del_rng("a".to_string()..="c".to_string()).expect("del_rng a..=c");
+	assert_eq!(deleted, 1, "deleted by del_rng a..=c");
+	assert!(!surrealism::kv::exists("c").expect("exists c"), "c deleted by del_rng a..=c");
+	let keys = surrealism::kv::keys(..).expect("keys after del_rng a..=c");
+	assert_eq!(keys, vec!["d"], "keys after del_rng a..=c");
+	surrealism::kv::del("d").expect("del d");
 
 	// Additional range examples
 	surrealism::kv::set("a", 1).expect("set a");
@@ -174,11 +203,108 @@ fn test_kv() -> Result<()> {
 		surrealism::kv::values(..="test".to_string()).expect("values ..=test");
 	assert_eq!(values_to_teste, vec![1, 2, 3, 10], "values ..=test");
 
+	// Exclusive start bounds, which have no range syntax
+	let keys_after_b = surrealism::kv::keys((Bound::Excluded("b".to_string()), Bound::Unbounded))
+		.expect("keys (b, ..)");
+	assert_eq!(keys_after_b, vec!["c", "test", "z"], "keys (b, ..)");
+	let count_between =
+		surrealism::kv::count((Bound::Excluded("a".to_string()), Bound::Excluded("z".to_string())))
+			.expect("count (a, z)");
+	assert_eq!(count_between, 3, "count (a, z)");
+	let count_all = surrealism::kv::count(..).expect("count ..");
+	assert_eq!(count_all, 5, "count ..");
+
+	// Paged scans
+	surrealism::kv::set_batch((0..1000).map(|i| (format!("scan:{i:04}"), i)))
+		.expect("set_batch scan");
+	let range = "scan:".to_string().."scan;".to_string();
+	let mut scanned = Vec::new();
+	let mut pages = 0;
+	let mut after = None;
+	loop {
+		let (page, next) =
+			surrealism::kv::scan::<_, i64>(range.clone(), 64, after).expect("scan page");
+		assert!(page.len() <= 64, "scan page size");
+		scanned.extend(page.into_iter().map(|(_, value)| value));
+		pages += 1;
+		match next {
+			Some(next) => after = Some(next),
+			None => break,
+		}
+	}
+	assert_eq!(scanned, (0..1000).collect::<Vec<i64>>(), "scan values");
+	assert_eq!(pages, 16, "scan pages");
+	let (page, next) =
+		surrealism::kv::scan::<_, i64>(range.clone(), 2, Some("scan:0997".to_string()))
+			.expect("scan after");
+	assert_eq!(page, vec![("scan:0998".to_string(), 998), ("scan:0999".to_string(), 999)]);
+	assert_eq!(next, None, "scan after next");
+	let deleted = surrealism::kv::del_rng(range).expect("del_rng scan");
+	assert_eq!(deleted, 1000, "deleted by del_rng scan");
+
 	println!("kv test passed");
 
 	Ok(())
 }
 
+/// Increment a counter kept in the KV store, returning its new value.
+#[surrealism]
+fn test_kv_counter() -> Result<i64> {
+	let count = surrealism::kv::get::<_, i64>("counter")?.unwrap_or(0) + 1;
+	surrealism::kv::set("counter", count)?;
+	Ok(count)
+}
+
+/// Read a value cached in the KV store, storing the given value if none is cached yet.
+#[surrealism]
+fn test_kv_cached(value: i64) -> Result<i64> {
+	surrealism::kv::get_or_insert_with("cached", || value)
+}
+
+/// Store null and none values, then read them back along with a key which was never set,
+/// showing that a key holding null or none is not reported as absent.
+#[surrealism]
+fn test_kv_null() -> Result<Vec<String>> {
+	use surrealdb_types::ToSql;
+	surrealism::kv::set("null", surrealdb_types::Value::Null)?;
+	surrealism::kv::set("none", surrealdb_types::Value::None)?;
+	["null", "none", "missing"]
+		.into_iter()
+		.map(|key| {
+			Ok(match surrealism::kv::get::<_, surrealdb_types::Value>(key)? {
+				Some(value) => format!("{key}: {}", value.to_sql()),
+				None => format!("{key}: absent"),
+			})
+		})
+		.collect()
+}
+
+/// Count a visit in the KV store and record it in the database, returning the visit count.
+#[surrealism]
+fn test_visit(page: String) -> Result<i64> {
+	let key = format!("visits:{page}");
+	let count = surrealism::kv::get::<_, i64>(key.as_str())?.unwrap_or(0) + 1;
+	surrealism::kv::set(key, count)?;
+	surrealism::sql_with_vars::<_, _, surrealdb_types::Value>(
+		"CREATE visit SET page = $page",
+		[("page".to_string(), page.into_value())],
+	)?;
+	Ok(count)
+}
+
+#[surrealism]
+fn test_unit(should_fail: bool) -> Result<()> {
+	if should_fail {
+		anyhow::bail!("Failed as requested");
+	}
+	Ok(())
+}
+
+#[surrealism]
+fn test_unit_plain(value: i64) {
+	println!("Received {value}");
+}
+
 #[surrealism]
 fn test_io() -> Result<String> {
 	println!("This is a test message to stdout");
@@ -190,3 +316,829 @@ fn test_io() -> Result<String> {
 fn test_none_value() -> Result<Vec<surrealdb_types::Value>> {
 	Ok(vec![surrealdb_types::Value::None])
 }
+
+#[surrealism]
+fn test_record_id(id: surrealdb_types::RecordId) -> surrealdb_types::RecordId {
+	id
+}
+
+/// The array or object key of a record id, read back inside the module.
+#[surrealism]
+fn test_record_key(id: surrealdb_types::RecordId) -> Result<surrealism::types::Value> {
+	match id.key {
+		surrealdb_types::RecordIdKey::Array(key) => Ok(key.into_value()),
+		surrealdb_types::RecordIdKey::Object(key) => Ok(key.into_value()),
+		key => anyhow::bail!("Expected an array or object key, found {key:?}"),
+	}
+}
+
+#[surrealism]
+fn test_datetime_add(
+	at: chrono::DateTime<chrono::Utc>,
+	by: std::time::Duration,
+) -> Result<chrono::DateTime<chrono::Utc>> {
+	Ok(at + chrono::Duration::from_std(by)?)
+}
+
+#[surrealism]
+fn test_env() -> Option<String> {
+	std::env::var("SURREALISM_SECRET").ok()
+}
+
+#[surrealism]
+fn test_random() -> Result<Vec<i64>> {
+	(0..3).map(|_| Ok(surrealism::rng::next_u64()? as i64)).collect()
+}
+
+/// Fills a buffer of `len` random bytes, and returns how many of them are not zero.
+#[surrealism]
+fn test_random_fill(len: i64) -> Result<i64> {
+	let mut buf = vec![0; usize::try_from(len)?];
+	surrealism::rng::fill_bytes(&mut buf)?;
+	Ok(buf.iter().filter(|byte| **byte != 0).count() as i64)
+}
+
+#[surrealism]
+fn test_now() -> Result<surrealdb_types::Datetime> {
+	surrealism::time::now()
+}
+
+/// Generate up to `count` tokens, taking 10ms each, stopping early with the tokens generated
+/// so far when the deadline is too close to generate another.
+#[surrealism]
+fn test_generate(count: i64) -> Result<Vec<i64>> {
+	let mut tokens = Vec::new();
+	for token in 0..count {
+		if let Some(remaining) = surrealism::time::deadline_remaining()?
+			&& remaining < std::time::Duration::from_millis(10)
+		{
+			break;
+		}
+		std::thread::sleep(std::time::Duration::from_millis(10));
+		tokens.push(token);
+	}
+	Ok(tokens)
+}
+
+#[surrealism]
+fn test_datetime_max() -> Result<surrealdb_types::Datetime> {
+	let max = chrono::DateTime::parse_from_rfc3339("9999-12-31T23:59:59.999999999Z")?;
+	Ok(surrealdb_types::Datetime::from(max.to_utc()))
+}
+
+#[surrealism]
+fn test_datetime_shift(
+	value: surrealdb_types::Datetime,
+	days: i64,
+) -> Result<surrealdb_types::Datetime> {
+	let shifted = chrono::TimeDelta::try_days(days)
+		.and_then(|delta| value.checked_add_signed(delta))
+		.ok_or_else(|| anyhow::anyhow!("{days} days from the given datetime is out of range"))?;
+	Ok(surrealdb_types::Datetime::from(shifted))
+}
+
+#[surrealism]
+fn test_datetime_invalid() -> Result<String> {
+	use surrealism::types::serialize::{Serializable, Serialized};
+
+	// Encode a datetime, then replace its seconds with a timestamp chrono cannot represent
+	let datetime = |seconds: i64| {
+		chrono::DateTime::from_timestamp(seconds, 0)
+			.map(|datetime| surrealdb_types::Value::Datetime(datetime.into()))
+			.ok_or_else(|| anyhow::anyhow!("Invalid timestamp {seconds}"))
+	};
+	let seconds = 1_000_000_000i64;
+	let bytes = surrealdb_types::encode(&datetime(seconds)?)?;
+	let with_seconds = |offset: usize, replacement: i64| {
+		let mut bytes = bytes.clone();
+		bytes[offset..offset + 8].copy_from_slice(&replacement.to_le_bytes());
+		surrealdb_types::Value::deserialize(Serialized(bytes.into()))
+	};
+	// The seconds are the bytes which, incremented, decode as the datetime a second later
+	let next = datetime(seconds + 1)?;
+	let offset = (0..bytes.len().saturating_sub(7))
+		.find(|&offset| with_seconds(offset, seconds + 1).is_ok_and(|value| value == next))
+		.ok_or_else(|| anyhow::anyhow!("Encoded seconds not found"))?;
+	match with_seconds(offset, i64::MAX) {
+		Ok(value) => anyhow::bail!("Decoded an impossible datetime: {value:?}"),
+		Err(e) => Ok(e.to_string()),
+	}
+}
+
+#[surrealism]
+fn test_context() -> Result<String> {
+	let user: Option<String> = surrealism::context::get("user")?;
+	Ok(match user {
+		Some(user) => format!("Signed in as {user}"),
+		None => "Not signed in".to_string(),
+	})
+}
+
+#[surrealism]
+fn test_context_numbers() -> Result<i64> {
+	let numbers: Option<Option<Vec<i64>>> = surrealism::context::get("numbers")?;
+	Ok(numbers.flatten().unwrap_or_default().iter().sum())
+}
+
+#[surrealism]
+fn test_log(name: String) -> Result<String> {
+	surrealism::log::info(format!("Greeting {name}"))?;
+	if name.is_empty() {
+		surrealism::log::warn("No name was provided")?;
+	}
+	Ok(format!("Hello, {name}!"))
+}
+
+#[surrealism]
+fn test_result_option(value: i64) -> Result<Option<i64>> {
+	if value < 0 {
+		anyhow::bail!("Value must not be negative");
+	}
+	Ok((value > 0).then_some(value))
+}
+
+#[surrealism]
+fn test_result_option_string(value: i64) -> Result<Option<i64>, String> {
+	if value < 0 {
+		return Err(format!("{value} is negative"));
+	}
+	Ok((value > 0).then_some(value))
+}
+
+#[surrealism]
+fn test_option_result(value: i64) -> Option<Result<i64>> {
+	if value < 0 {
+		return Some(Err(anyhow::anyhow!("Value must not be negative")));
+	}
+	(value > 0).then_some(Ok(value))
+}
+
+#[surrealism]
+fn test_bytes() -> bytes::Bytes {
+	vec![1u8, 2, 3].into()
+}
+
+#[surrealism]
+fn test_vec_u8() -> Vec<u8> {
+	vec![1u8, 2, 3]
+}
+
+#[surrealism]
+fn test_vec_u8_reverse(mut data: Vec<u8>) -> Vec<u8> {
+	data.reverse();
+	data
+}
+
+/// The SHA-256 digest of a string.
+#[surrealism]
+fn test_sha256(input: String) -> bytes::Bytes {
+	use sha2::Digest;
+	sha2::Sha256::digest(input.as_bytes()).to_vec().into()
+}
+
+#[surrealism]
+fn test_bytes_slice(data: bytes::Bytes, start: i64, end: i64) -> Result<bytes::Bytes> {
+	let (start, end) = (usize::try_from(start)?, usize::try_from(end)?);
+	if start > end || end > data.len() {
+		anyhow::bail!("Range {start}..{end} is out of bounds for {} bytes", data.len());
+	}
+	Ok(data.slice(start..end))
+}
+
+/// Returns the bytes it is given, so that transferring a large `Bytes` into the module and
+/// back out to the host can be timed by the host.
+#[surrealism]
+fn test_bytes_echo(data: bytes::Bytes) -> bytes::Bytes {
+	data
+}
+
+/// Whether a SHA-256 digest is the digest of "surrealism".
+#[surrealism]
+fn test_verify(hash: [u8; 32]) -> bool {
+	use sha2::Digest;
+	hash == <[u8; 32]>::from(sha2::Sha256::digest(b"surrealism"))
+}
+
+#[surrealism]
+fn test_initial(name: &str) -> Result<char> {
+	let initial = name.chars().next().ok_or_else(|| anyhow::anyhow!("Name is empty"))?;
+	Ok(initial.to_ascii_uppercase())
+}
+
+#[surrealism]
+fn test_repeat_char(c: char, times: i64) -> Result<String> {
+	Ok(std::iter::repeat_n(c, usize::try_from(times)?).collect())
+}
+
+/// The contents of a text file, read through the host.
+#[surrealism]
+fn test_fs_read(path: String) -> Result<String> {
+	let contents = surrealism::fs::read(path)?;
+	Ok(String::from_utf8(contents.to_vec())?)
+}
+
+/// Doubles each number, or returns `NONE` when there are none.
+#[surrealism]
+fn test_option_vec(values: Option<Vec<i64>>) -> Option<Vec<i64>> {
+	values.map(|values| values.into_iter().map(|value| value * 2).collect())
+}
+
+/// Doubles each number, keeping missing ones missing.
+#[surrealism]
+fn test_vec_option(values: Vec<Option<i64>>) -> Vec<Option<i64>> {
+	values.into_iter().map(|value| value.map(|value| value * 2)).collect()
+}
+
+/// The characters as they are, or `NONE` if any of them is missing.
+#[surrealism]
+fn test_vec_option_char(chars: Vec<Option<char>>) -> Option<Vec<char>> {
+	chars.into_iter().collect()
+}
+
+/// Count the calls made to this instance of the module, kept in a guest global.
+#[surrealism]
+fn test_counter() -> i64 {
+	static CALLS: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(0);
+	CALLS.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1
+}
+
+#[surrealism]
+fn test_greet() -> &'static str {
+	"Hello from Surrealism"
+}
+
+/// The name without surrounding whitespace, returned as is when it has none.
+#[surrealism]
+fn test_trim(name: std::borrow::Cow<'static, str>) -> std::borrow::Cow<'static, str> {
+	if name.trim().len() == name.len() {
+		name
+	} else {
+		name.trim().to_string().into()
+	}
+}
+
+/// Yields the squares of the first `count` numbers, pulled from the module one at a time.
+#[surrealism(stream)]
+fn test_squares(count: i64) -> impl Iterator<Item = i64> {
+	(1..=count).map(|n| n * n)
+}
+
+/// Yields a page of numbers at a time, failing before the stream is opened on an empty page.
+#[surrealism(stream)]
+fn test_pages(pages: i64, size: i64) -> Result<impl Iterator<Item = Vec<i64>>> {
+	if size <= 0 {
+		anyhow::bail!("Page size must be positive");
+	}
+	Ok((0..pages).map(move |page| (page * size..(page + 1) * size).collect()))
+}
+
+/// The values produced by `test_counted` streams, and the streams released, in this instance.
+static COUNTED_PRODUCED: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(0);
+static COUNTED_RELEASED: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(0);
+
+/// Counts a `test_counted` stream as released when its iterator is dropped.
+struct CountedRelease;
+
+impl Drop for CountedRelease {
+	fn drop(&mut self) {
+		COUNTED_RELEASED.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+	}
+}
+
+/// Yields the numbers up to `count`, counting each value as it is produced.
+#[surrealism(stream)]
+fn test_counted(count: i64) -> impl Iterator<Item = i64> {
+	let release = CountedRelease;
+	(1..=count).map(move |n| {
+		let _ = &release;
+		COUNTED_PRODUCED.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+		n
+	})
+}
+
+/// The values produced by `test_counted` streams, and the streams released, so far.
+#[surrealism]
+fn test_counted_stats() -> (i64, i64) {
+	(
+		COUNTED_PRODUCED.load(std::sync::atomic::Ordering::Relaxed),
+		COUNTED_RELEASED.load(std::sync::atomic::Ordering::Relaxed),
+	)
+}
+
+#[surrealism]
+fn test_rgb(color: [i64; 3]) -> [i64; 3] {
+	color.map(|channel| 255 - channel)
+}
+
+#[surrealism]
+fn test_u128_max() -> u128 {
+	u128::MAX
+}
+
+#[surrealism]
+fn test_u128_echo(value: u128) -> u128 {
+	value
+}
+
+#[surrealism]
+fn test_i128_negate(value: i128) -> Result<i128> {
+	value.checked_neg().ok_or_else(|| anyhow::anyhow!("Cannot negate {value}"))
+}
+
+#[surrealism]
+fn test_set(values: Vec<i64>) -> surrealism::types::set::SetOf<i64> {
+	values.into_iter().collect()
+}
+
+#[surrealism]
+fn test_unique(values: Vec<i64>) -> BTreeSet<i64> {
+	values.into_iter().collect()
+}
+
+#[surrealism]
+fn test_set_union(a: HashSet<String>, b: BTreeSet<String>) -> Result<HashSet<String>> {
+	if a.is_empty() && b.is_empty() {
+		anyhow::bail!("Both sets are empty");
+	}
+	Ok(a.into_iter().chain(b).collect())
+}
+
+#[surrealism]
+fn test_either(value: surrealdb_types::Either2<i64, String>) -> String {
+	match value {
+		surrealdb_types::Either2::A(number) => format!("number: {number}"),
+		surrealdb_types::Either2::B(text) => format!("string: {text}"),
+	}
+}
+
+#[surrealism]
+fn tax(amount: surrealdb_types::Decimal) -> surrealdb_types::Decimal {
+	amount * surrealdb_types::Decimal::new(20, 2)
+}
+
+#[surrealism]
+fn test_half(value: f64) -> f64 {
+	value / 2.0
+}
+
+#[surrealism]
+fn test_meta() -> Result<String> {
+	Ok(surrealism::meta()?.version)
+}
+
+#[surrealism]
+fn test_sql_many() -> Result<surrealdb_types::Value> {
+	let mut results =
+		surrealism::sql_many("DEFINE TABLE demo_module_data; SELECT * FROM demo_module_data;")?;
+	if results.len() != 2 {
+		anyhow::bail!("Expected 2 statement results, received {}", results.len());
+	}
+	Ok(results.remove(1))
+}
+
+/// Run a single `SELECT` as a script, which has one result however many rows it returns.
+#[surrealism]
+fn test_sql_many_single() -> Result<i64> {
+	Ok(surrealism::sql_many("SELECT * FROM user")?.len() as i64)
+}
+
+/// Run a two-statement script, describing the status and result of each statement.
+#[surrealism]
+fn test_sql_results() -> Result<Vec<String>> {
+	use surrealdb_types::ToSql;
+	let results = surrealism::sql_results("SELECT * FROM user; SELECT * FROM missing;")?;
+	Ok(results
+		.into_iter()
+		.map(|result| match result.into_result() {
+			Ok(value) => format!("OK: {}", value.to_sql()),
+			Err(e) => format!("ERR: {e}"),
+		})
+		.collect())
+}
+
+#[surrealism]
+fn test_sql_binds(id: String) -> Result<surrealdb_types::Value> {
+	surrealism::sql_with_binds("SELECT * FROM type::thing('user', $_1)", [id.into_value()])
+}
+
+#[surrealism]
+fn test_sql_rows() -> Result<Vec<String>> {
+	let users: Vec<User> = surrealism::sql("SELECT name, age, enabled FROM user")?;
+	Ok(users.into_iter().map(|user| user.name).collect())
+}
+
+/// Describe a user from two fields of an object, without declaring a struct for it.
+#[surrealism]
+fn test_object_fields(user: surrealdb_types::Object) -> Result<String> {
+	use surrealism::types::object::ObjectExt;
+	let name: String = user.get_typed("name")?.unwrap_or_else(|| "Anonymous".to_string());
+	match user.get_typed::<i64>("age")? {
+		Some(age) => Ok(format!("{name} is {age}")),
+		None => Ok(format!("{name} has no age")),
+	}
+}
+
+#[surrealism]
+fn test_sql_typed() -> Result<Vec<String>> {
+	let users = surrealism::sql_typed::<_, User>("SELECT name, age, enabled FROM user")?;
+	Ok(users.into_iter().map(|user| user.name).collect())
+}
+
+#[surrealism]
+fn test_sql_duplicate_vars() -> Result<String> {
+	let vars = vec![
+		("name".to_string(), surrealdb_types::Value::String("first".to_string())),
+		("name".to_string(), surrealdb_types::Value::String("second".to_string())),
+	];
+	surrealism::sql_with_vars("RETURN $name", vars)
+}
+
+#[surrealism]
+fn test_sql_query(query: String) -> Result<surrealdb_types::Value> {
+	surrealism::sql(query)
+}
+
+#[surrealism]
+fn test_sql_error() -> Result<String> {
+	match surrealism::sql::<_, surrealdb_types::Value>("CREATE user:tobie") {
+		Ok(_) => Ok("created".to_string()),
+		Err(e) => match e.downcast_ref::<surrealism::SqlError>() {
+			Some(e) if e.kind() == surrealism::SqlErrorKind::AlreadyExists => {
+				Ok("already exists".to_string())
+			}
+			_ => Err(e),
+		},
+	}
+}
+
+/// Define the schema twice, returning the definitions created by each call. The second call
+/// finds every definition already exists, so creates nothing.
+#[surrealism]
+fn test_define_schema() -> Result<(Vec<String>, Vec<String>)> {
+	let schema = ["DEFINE TABLE user SCHEMAFULL", "DEFINE FIELD name ON user TYPE string"];
+	let first = surrealism::schema::define(schema)?;
+	let second = surrealism::schema::define(schema)?;
+	anyhow::ensure!(second.is_empty(), "Defining the schema again created {second:?}");
+	Ok((first, second))
+}
+
+/// Define a schema statement which hides whether it created anything.
+#[surrealism]
+fn test_define_schema_overwrite() -> Result<Vec<String>> {
+	surrealism::schema::define(["DEFINE TABLE OVERWRITE user SCHEMAFULL"])
+}
+
+/// Define two schema statements passed as one.
+#[surrealism]
+fn test_define_schema_script() -> Result<Vec<String>> {
+	surrealism::schema::define(["DEFINE TABLE user SCHEMAFULL; DEFINE TABLE post"])
+}
+
+/// Run a flaky function, making up to `attempts` attempts.
+#[surrealism]
+fn test_run_retry(attempts: i64) -> Result<String> {
+	let policy = surrealism::RetryPolicy::new(u32::try_from(attempts)?)
+		.with_backoff(std::time::Duration::from_millis(10));
+	surrealism::run_with_retry("fn::flaky", None, (), policy)
+}
+
+#[surrealism(aliases("is_adult", "of_age"))]
+fn test_aliases(age: i64) -> bool {
+	age >= 18
+}
+
+#[surrealism(deprecated = "use test_aliases instead")]
+fn test_deprecated(age: i64) -> bool {
+	age >= 18
+}
+
+struct UserTable;
+
+impl surrealism::types::record::TableName for UserTable {
+	const NAME: &'static str = "user";
+}
+
+#[surrealism]
+fn test_grid(grid: Vec<Vec<i64>>) -> Vec<Vec<i64>> {
+	// Transpose the grid, so rows become columns
+	let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+	(0..width).map(|x| grid.iter().filter_map(|row| row.get(x).copied()).collect()).collect()
+}
+
+#[surrealism]
+fn test_named_args((first, last): (String, String), _unused: bool, mut age: i64) -> String {
+	age += 1;
+	format!("{first} {last} turns {age}")
+}
+
+#[surrealism]
+fn test_pair(name: String) -> (String, i64) {
+	let len = name.len() as i64;
+	(name, len)
+}
+
+#[surrealism]
+fn test_single(value: (i64,)) -> (i64,) {
+	(value.0 + 1,)
+}
+
+#[surrealism]
+fn test_sum(values: &[i64]) -> i64 {
+	values.iter().sum()
+}
+
+#[surrealism]
+fn test_shout(text: &str, user: &User, tags: &BTreeSet<String>) -> String {
+	let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+	format!("{}, {}! [{}]", text.to_uppercase(), user.name, tags.join(", "))
+}
+
+#[surrealism]
+fn test_destructured((name, age): (String, i64)) -> String {
+	format!("{name} is {age}")
+}
+
+#[surrealism]
+fn test_destructured_mixed(
+	(name, age): (String, i64),
+	mut greeting: String,
+	User {
+		enabled,
+		..
+	}: User,
+	_: i64,
+) -> String {
+	greeting.push_str(if enabled {
+		", enabled"
+	} else {
+		", disabled"
+	});
+	format!("{greeting} {name} ({age})")
+}
+
+/// Adds two numbers, and a third when it is passed, which can be omitted by the caller.
+#[surrealism]
+fn test_optional_arg(a: i64, b: i64, c: Option<i64>) -> i64 {
+	a + b + c.unwrap_or(0)
+}
+
+/// Returns its argument unchanged, for comparing how arguments are parsed.
+#[surrealism]
+fn test_identity(value: surrealism::Value) -> surrealism::Value {
+	value
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Order {
+	id: String,
+	customer: Customer,
+	lines: Vec<OrderLine>,
+	note: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Customer {
+	name: String,
+	tags: Vec<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct OrderLine {
+	sku: String,
+	quantity: u32,
+	price: f64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct OrderSummary {
+	order: Order,
+	total: f64,
+}
+
+/// Totals an order, received and returned as `serde` types rather than `SurrealValue` ones.
+#[surrealism(serde)]
+fn test_serde(order: Order) -> Result<OrderSummary> {
+	if order.lines.is_empty() {
+		anyhow::bail!("Order {} has no lines", order.id);
+	}
+	let total = order.lines.iter().map(|line| f64::from(line.quantity) * line.price).sum();
+	Ok(OrderSummary {
+		order,
+		total,
+	})
+}
+
+/// Returns a map keyed by pairs, which has no JSON form, so the call fails.
+#[surrealism(serde)]
+fn test_serde_unserializable() -> std::collections::BTreeMap<(i64, i64), i64> {
+	std::collections::BTreeMap::from([((1, 2), 3)])
+}
+
+#[surrealism]
+fn test_number(value: surrealism::types::Number) -> surrealism::types::Number {
+	match value {
+		surrealism::types::Number::Int(v) => surrealism::types::Number::Int(v * 2),
+		surrealism::types::Number::Float(v) => surrealism::types::Number::Float(v * 2.0),
+		surrealism::types::Number::Decimal(v) => {
+			surrealism::types::Number::Decimal(v * surrealdb_types::Decimal::from(2))
+		}
+	}
+}
+
+/// Half of an even number, or a description of an odd one.
+#[surrealism]
+fn test_dynamic(x: i64) -> surrealism::types::Value {
+	if x % 2 == 0 {
+		(x / 2).into_value()
+	} else {
+		format!("{x} is odd").into_value()
+	}
+}
+
+#[surrealism]
+fn test_geometry_point(
+	point: surrealism::types::geometry::Point,
+) -> surrealism::types::geometry::Point {
+	surrealism::types::geometry::Point::new(point.x() * 2.0, point.y() * 2.0)
+}
+
+/// The subtype of any geometry, such as `point` or `polygon`.
+#[surrealism]
+fn test_geometry_kind(geometry: surrealism::types::geometry::Geometry) -> String {
+	geometry.kind().to_string()
+}
+
+/// The number of integers in a range, such as 4 for `1..5` or 5 for `1..=5`.
+#[surrealism]
+fn test_range_len(range: surrealdb_types::Range) -> Result<i64> {
+	let int = |value: &surrealdb_types::Value| match value {
+		surrealdb_types::Value::Number(surrealdb_types::Number::Int(v)) => Ok(*v),
+		value => anyhow::bail!("Expected an int bound, found {}", value.kind()),
+	};
+	let start = match range.start() {
+		Bound::Included(v) => int(v)?,
+		Bound::Excluded(v) => int(v)? + 1,
+		Bound::Unbounded => anyhow::bail!("Range has no start"),
+	};
+	let end = match range.end() {
+		Bound::Included(v) => int(v)? + 1,
+		Bound::Excluded(v) => int(v)?,
+		Bound::Unbounded => anyhow::bail!("Range has no end"),
+	};
+	Ok((end - start).max(0))
+}
+
+#[surrealism]
+fn test_record_table(
+	id: surrealism::types::record::Record<UserTable>,
+) -> surrealism::types::record::Record<UserTable> {
+	id
+}
+
+/// Describes itself as an `int`, but converts into a string, like a module whose body
+/// returns a different variant than its signature declares.
+#[derive(Debug)]
+struct WrongKind;
+
+impl SurrealValue for WrongKind {
+	fn kind_of() -> surrealdb_types::Kind {
+		surrealdb_types::Kind::Int
+	}
+
+	fn is_value(value: &surrealdb_types::Value) -> bool {
+		matches!(value, surrealdb_types::Value::Number(surrealdb_types::Number::Int(_)))
+	}
+
+	fn into_value(self) -> surrealdb_types::Value {
+		surrealdb_types::Value::String("not a number".to_string())
+	}
+
+	fn from_value(value: surrealdb_types::Value) -> Result<Self> {
+		anyhow::bail!("Cannot convert {} into WrongKind", value.kind())
+	}
+}
+
+#[surrealism]
+fn test_recursion(depth: i64) -> i64 {
+	fn sum(n: i64) -> i64 {
+		// Keep the call from being turned into a loop
+		if n == 0 {
+			0
+		} else {
+			n + std::hint::black_box(sum(n - 1))
+		}
+	}
+	sum(depth)
+}
+
+#[surrealism]
+fn test_panic(message: String) -> String {
+	panic!("{message}")
+}
+
+#[surrealism]
+fn test_divide(a: i64, b: i64) -> i64 {
+	// Rust checks for a zero divisor, so this panics rather than trapping on the division
+	a / b
+}
+
+#[surrealism]
+fn test_wrong_return() -> WrongKind {
+	WrongKind
+}
+
+unsafe extern "C" {
+	unsafe fn __sr_run(fnc_ptr: u32, version_ptr: u32, vars_ptr: u32) -> i32;
+	unsafe fn __sr_random(len_ptr: u32) -> i32;
+}
+
+/// Calls `__sr_run` with an argument array whose length claims `u32::MAX` elements with no
+/// data behind them, which the host should reject instead of allocating for them.
+#[surrealism]
+fn test_oversized_array() -> Result<bool> {
+	use surrealism::types::serialize::Serialized;
+	use surrealism::types::transfer::Transfer;
+
+	let mut controller = surrealism::Controller {};
+	let fnc = "fn::noop".to_string().transfer(&mut controller)?;
+	let version = None::<String>.transfer(&mut controller)?;
+	let args = Serialized(u32::MAX.to_le_bytes().to_vec().into()).transfer(&mut controller)?;
+	let result = unsafe { __sr_run(*fnc, *version, *args) };
+	Ok(result == -1)
+}
+
+/// Calls `__sr_run` with an argument whose length prefix is rewritten to nearly `u32::MAX`,
+/// which the host should reject instead of reading past the end of memory.
+#[surrealism]
+fn test_overflowing_length() -> Result<bool> {
+	use surrealism::types::controller::MemoryController;
+	use surrealism::types::serialize::Serialized;
+	use surrealism::types::transfer::Transfer;
+
+	let mut controller = surrealism::Controller {};
+	let fnc = "fn::noop".to_string().transfer(&mut controller)?;
+	let version = None::<String>.transfer(&mut controller)?;
+	let args = Serialized(0u32.to_le_bytes().to_vec().into()).transfer(&mut controller)?;
+	controller.mut_mem(*args, 4).copy_from_slice(&(u32::MAX - 2).to_le_bytes());
+	let result = unsafe { __sr_run(*fnc, *version, *args) };
+	// The host rejects the argument without freeing it
+	controller.free(*args, 8)?;
+	Ok(result == -1)
+}
+
+/// Deserializes a range whose bound lengths are both `u32::MAX`, whose sum overflows the
+/// guest's `usize`, which should be rejected instead of wrapping around.
+#[surrealism]
+fn test_overflowing_range() -> Result<bool> {
+	use surrealism::types::serialize::{Serializable, SerializableRange, Serialized};
+
+	let mut bytes = u32::MAX.to_le_bytes().to_vec();
+	bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+	Ok(SerializableRange::<i64>::deserialize(Serialized(bytes.into())).is_err())
+}
+
+/// Calls `__sr_run` with a function name pointer past the end of guest memory, which the
+/// host should reject with a bounds error instead of reading outside of memory.
+#[surrealism]
+fn test_out_of_bounds_pointer() -> Result<bool> {
+	use surrealism::types::serialize::Serialized;
+	use surrealism::types::transfer::Transfer;
+
+	let mut controller = surrealism::Controller {};
+	let version = None::<String>.transfer(&mut controller)?;
+	let args = Serialized(0u32.to_le_bytes().to_vec().into()).transfer(&mut controller)?;
+	let result = unsafe { __sr_run(u32::MAX - 8, *version, *args) };
+	Ok(result == -1)
+}
+
+/// Calls `__sr_run` with a function name whose length prefix claims 1GiB, more than the guest
+/// memory holds, which the host should reject with a bounds error.
+#[surrealism]
+fn test_out_of_bounds_length() -> Result<bool> {
+	use surrealism::types::controller::MemoryController;
+	use surrealism::types::serialize::Serialized;
+	use surrealism::types::transfer::Transfer;
+
+	let mut controller = surrealism::Controller {};
+	let fnc = "fn::noop".to_string().transfer(&mut controller)?;
+	let version = None::<String>.transfer(&mut controller)?;
+	let args = Serialized(0u32.to_le_bytes().to_vec().into()).transfer(&mut controller)?;
+	controller.mut_mem(*fnc, 4).copy_from_slice(&(1u32 << 30).to_le_bytes());
+	let result = unsafe { __sr_run(*fnc, *version, *args) };
+	// The host rejects the name without freeing it
+	controller.free(*fnc, 4 + "fn::noop".len() as u32)?;
+	Ok(result == -1)
+}
+
+/// Requests `len` random bytes from the host in one call, without the chunking done by
+/// `rng::fill_bytes`, which the host should refuse beyond its limit.
+#[surrealism]
+fn test_random_oversized(len: i64) -> Result<i64> {
+	use surrealism::types::transfer::Transfer;
+
+	let mut controller = surrealism::Controller {};
+	let len = u64::try_from(len)?.transfer(&mut controller)?;
+	let result = unsafe { __sr_random(*len) };
+	let bytes = Result::<Vec<u8>>::receive(result.try_into()?, &mut controller)??;
+	Ok(bytes.len() as i64)
+}
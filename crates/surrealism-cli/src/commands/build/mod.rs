@@ -1,9 +1,10 @@
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::Result;
-use surrealism_runtime::config::SurrealismConfig;
+use surrealism_runtime::config::{SurrealismConfig, SurrealismIntegrity};
 use surrealism_runtime::package::SurrealismPackage;
 use surrealism_types::err::PrefixError;
 use tempfile::TempDir;
@@ -15,20 +16,29 @@ use crate::commands::SurrealismCommand;
 pub struct BuildCommand {
 	pub path: Option<PathBuf>,
 	pub out: Option<PathBuf>,
+	pub no_optimize: bool,
 }
 
 impl SurrealismCommand for BuildCommand {
 	async fn run(self) -> Result<()> {
 		// Ensure all requirements are met
 		let path = self.path.unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
-		let config = load_config(&path)?;
+		let mut config = load_config(&path)?;
 		let source_wasm = get_source_wasm(&path)?;
 
-		// Compile the WASM module and optimize it
+		// Compile the WASM module and optimize it, unless disabled for debugging
 		build_wasm_module(&path)?;
-		let wasm = optimize_wasm(&source_wasm)?;
+		let wasm = if self.no_optimize {
+			read_wasm(&source_wasm)?
+		} else {
+			optimize_wasm(&source_wasm)?
+		};
+
+		// Record a checksum of the final module, so that corrupted packages are rejected on load
+		config.integrity = Some(SurrealismIntegrity::new(&wasm));
 
-		// Pack the optimized WASM into a Surrealism package
+		// Pack the optimized WASM into a Surrealism package. The config keeps the
+		// `[capabilities]` section of surrealism.toml, so the runtime can enforce it
 		let package = SurrealismPackage {
 			config,
 			wasm,
@@ -66,23 +76,51 @@ fn build_wasm_module(path: &PathBuf) -> Result<()> {
 	Ok(())
 }
 
-fn optimize_wasm(source_wasm: &PathBuf) -> Result<Vec<u8>> {
+fn read_wasm(source_wasm: &PathBuf) -> Result<Vec<u8>> {
 	if !source_wasm.exists() {
 		anyhow::bail!("Expected WASM file not found: {}", source_wasm.display());
 	}
 
+	fs::read(source_wasm).prefix_err(|| "Failed to read WASM file")
+}
+
+fn optimize_wasm(source_wasm: &PathBuf) -> Result<Vec<u8>> {
+	let wasm_bytes = read_wasm(source_wasm)?;
+
 	println!("Optimizing bundle...");
 
-	// Read and strip WASM
-	let wasm_bytes = fs::read(source_wasm).prefix_err(|| "Failed to read WASM file")?;
+	// Strip WASM and remove unused code
+	let exports = runtime_exports(&wasm_bytes)?;
 	let stripped_bytes = strip_wasm_sections(&wasm_bytes)?;
 
 	// Apply wasm-opt optimization
 	let optimized_bytes = apply_wasm_opt(&stripped_bytes)?;
 
+	// Exports are the roots for dead code elimination, so they should always survive, but the
+	// runtime cannot load the package without them
+	let missing: Vec<_> =
+		exports.difference(&runtime_exports(&optimized_bytes)?).cloned().collect();
+	if !missing.is_empty() {
+		anyhow::bail!("Optimization removed required exports: {}", missing.join(", "));
+	}
+
+	println!("Optimized bundle from {} to {} bytes", wasm_bytes.len(), optimized_bytes.len());
+
 	Ok(optimized_bytes)
 }
 
+/// The exports the runtime relies on: `memory`, and every `__sr_*` function, such as
+/// `__sr_alloc`, `__sr_free` and each `__sr_fnc__*`.
+fn runtime_exports(wasm_bytes: &[u8]) -> Result<BTreeSet<String>> {
+	let module = Module::from_buffer(wasm_bytes).prefix_err(|| "Failed to parse WASM module")?;
+	Ok(module
+		.exports
+		.iter()
+		.map(|export| export.name.clone())
+		.filter(|name| name == "memory" || name.starts_with("__sr_"))
+		.collect())
+}
+
 fn strip_wasm_sections(wasm_bytes: &[u8]) -> Result<Vec<u8>> {
 	let mut module =
 		Module::from_buffer(wasm_bytes).prefix_err(|| "Failed to parse WASM module")?;
@@ -108,6 +146,9 @@ fn strip_wasm_sections(wasm_bytes: &[u8]) -> Result<Vec<u8>> {
 	// Clear producers section
 	module.producers.clear();
 
+	// Remove functions, globals and data which are not reachable from any export
+	walrus::passes::gc::run(&mut module);
+
 	Ok(module.emit_wasm())
 }
 
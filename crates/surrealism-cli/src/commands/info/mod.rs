@@ -3,11 +3,13 @@ use std::path::PathBuf;
 use surrealism_runtime::package::SurrealismPackage;
 use surrealism_types::err::PrefixError;
 
-use crate::commands::SurrealismCommand;
+use crate::commands::{SurrealismCommand, format_args};
 use crate::host::DemoHost;
 
 pub struct InfoCommand {
 	pub file: PathBuf,
+	/// Fail on the first function which cannot be described, rather than reporting it inline.
+	pub strict: bool,
 }
 
 impl SurrealismCommand for InfoCommand {
@@ -15,6 +17,7 @@ impl SurrealismCommand for InfoCommand {
 		let package = SurrealismPackage::from_file(self.file)
 			.prefix_err(|| "Failed to load Surrealism package")?;
 		let meta = package.config.meta.clone();
+		let capabilities = package.config.capabilities.clone();
 		let runtime = surrealism_runtime::controller::Runtime::new(package)?;
 
 		// Load the WASM module from memory
@@ -22,44 +25,99 @@ impl SurrealismCommand for InfoCommand {
 		let mut controller =
 			runtime.new_controller(host).await.prefix_err(|| "Failed to load WASM module")?;
 
-		let exports =
-			controller.list().prefix_err(|| "Failed to list functions in the WASM module")?;
-
-		let mut results = Vec::new();
-		for name in exports {
-			let args = controller
-				.args(Some(name.clone()))
+		// A function which cannot be described is reported in place of its signature, so that
+		// the others are still listed, unless the command is strict
+		let exports = if self.strict {
+			controller
+				.manifest()
 				.await
-				.prefix_err(|| format!("Failed to collect arguments for function '{name}'"))?;
-
-			let returns = controller
-				.returns(Some(name.clone()))
+				.prefix_err(|| "Failed to describe the functions in the WASM module")?
+				.into_iter()
+				.map(Ok)
+				.collect()
+		} else {
+			controller
+				.describe()
 				.await
-				.prefix_err(|| format!("Failed to collect return type for function '{name}'"))?;
-
-			results.push((name, args, returns));
-		}
-
-		let exports = results;
+				.prefix_err(|| "Failed to describe the functions in the WASM module")?
+				.into_iter()
+				.map(|function| {
+					let path = function.path();
+					function.signature.map_err(|e| (path, e))
+				})
+				.collect::<Vec<_>>()
+		};
 
 		let title = format!("Info for @{}/{}@{}", meta.organisation, meta.name, meta.version,);
 		println!("\n{title}");
 		println!("{}\n", "=".repeat(title.len() + 2));
 
-		for (name, args, returns) in exports {
-			let name = if name.is_empty() {
-				"<mod>".to_string()
-			} else {
-				format!("<mod>::{name}")
+		let display_name = |path: String| match path {
+			path if path.is_empty() => "<mod>".to_string(),
+			path => format!("<mod>::{path}"),
+		};
+		for function in exports {
+			let function = match function {
+				Ok(function) => function,
+				Err((path, e)) => {
+					println!("- {}", display_name(path));
+					println!("  ❌ Failed to describe: {e}");
+					continue;
+				}
 			};
+			let name = display_name(function.path());
 
 			println!(
 				"- {name}({}) -> {}",
-				args.iter().map(|arg| format!("{arg}")).collect::<Vec<_>>().join(", "),
-				returns
+				format_args(&function.args, &function.arg_names),
+				function.returns
 			);
+			if function.pure {
+				println!("  Pure: results depend only on the arguments");
+			}
+			if function.stream {
+				println!("  Stream: values are returned one at a time");
+			}
+			if let Some(note) = function.deprecated {
+				println!("  ⚠️ Deprecated: {note}");
+			}
+		}
+
+		println!("\nBuild:");
+		match controller.build_info().await.prefix_err(|| "Failed to read build information")? {
+			Some(build) => {
+				let unknown = || "unknown".to_string();
+				println!("- Crate version: {}", build.crate_version.unwrap_or_else(unknown));
+				println!("- Surrealism version: {}", build.surrealism_version);
+				println!("- Built at: {}", build.built_at.unwrap_or_else(unknown));
+			}
+			None => println!("- No build information is exported by this module"),
+		}
+
+		println!("\nCapabilities:");
+		match capabilities {
+			Some(capabilities) => {
+				println!("- allow_scripting: {}", capabilities.allow_scripting);
+				println!("- allow_arbitrary_queries: {}", capabilities.allow_arbitrary_queries);
+				if let Some(allowed) = capabilities.allowed_statements() {
+					let allowed: Vec<String> = allowed.iter().map(ToString::to_string).collect();
+					println!("- allow_statements: {}", format_list(&allowed));
+				}
+				println!("- allow_functions: {}", format_list(&capabilities.allow_functions));
+				println!("- allow_net: {}", format_list(&capabilities.allow_net));
+				println!("- allow_fs: {}", format_list(&capabilities.allow_fs));
+			}
+			None => println!("- No capabilities are declared by this package"),
 		}
 
 		Ok(())
 	}
 }
+
+fn format_list(items: &[String]) -> String {
+	if items.is_empty() {
+		"none".to_string()
+	} else {
+		items.join(", ")
+	}
+}
@@ -1,8 +1,45 @@
 pub mod build;
 pub mod info;
+pub mod precompile;
 pub mod run;
+pub mod schema;
 pub mod sig;
+pub mod verify;
+
+use surrealism_runtime::engine::EngineConfig;
 
 pub trait SurrealismCommand {
 	async fn run(self) -> anyhow::Result<()>;
 }
+
+/// Format the arguments of a signature as `name: kind`, or just `kind` when the module does
+/// not describe its argument names.
+pub fn format_args(args: &[surrealdb_types::Kind], names: &[String]) -> String {
+	if names.len() == args.len() {
+		names
+			.iter()
+			.zip(args)
+			.map(|(name, arg)| format!("{name}: {arg}"))
+			.collect::<Vec<_>>()
+			.join(", ")
+	} else {
+		args.iter().map(|arg| format!("{arg}")).collect::<Vec<_>>().join(", ")
+	}
+}
+
+/// The engine settings given on the command line. Modules must be run with the settings
+/// they were precompiled with.
+pub fn engine_config(
+	max_wasm_stack: Option<usize>,
+	nan_canonicalization: bool,
+	static_memory_maximum_size: Option<u64>,
+) -> EngineConfig {
+	let mut engine = EngineConfig::new().nan_canonicalization(nan_canonicalization);
+	if let Some(bytes) = max_wasm_stack {
+		engine = engine.max_wasm_stack(bytes);
+	}
+	if let Some(bytes) = static_memory_maximum_size {
+		engine = engine.static_memory_maximum_size(bytes);
+	}
+	engine
+}
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use surrealism_runtime::controller::Runtime;
+use surrealism_runtime::package::SurrealismPackage;
+use surrealism_types::err::PrefixError;
+
+use crate::commands::{SurrealismCommand, engine_config};
+
+pub struct PrecompileCommand {
+	pub file: PathBuf,
+	pub out: Option<PathBuf>,
+	pub max_wasm_stack: Option<usize>,
+	pub nan_canonicalization: bool,
+	pub static_memory_maximum_size: Option<u64>,
+}
+
+impl SurrealismCommand for PrecompileCommand {
+	async fn run(self) -> Result<()> {
+		let out = self.out.unwrap_or_else(|| self.file.with_extension("cwasm"));
+		let package = SurrealismPackage::from_file(self.file)
+			.prefix_err(|| "Failed to load Surrealism package")?;
+		let engine = engine_config(
+			self.max_wasm_stack,
+			self.nan_canonicalization,
+			self.static_memory_maximum_size,
+		);
+		let runtime = Runtime::new_with_config(package, &engine)?;
+		runtime.precompile_to(&out)?;
+		println!(
+			"Precompiled module written to {}, and its digest to {}.sha256",
+			out.display(),
+			out.display()
+		);
+
+		Ok(())
+	}
+}
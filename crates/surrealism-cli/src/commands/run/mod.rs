@@ -1,38 +1,175 @@
+use std::io::Read;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use surrealdb_types::ToSql;
-use surrealism_runtime::controller::Runtime;
+use surrealism_runtime::config::SurrealismIntegrity;
+use surrealism_runtime::controller::{Controller, Runtime};
+use surrealism_runtime::host::InvocationContext;
+use surrealism_runtime::json::from_json;
+use surrealism_runtime::kv::FileStore;
 use surrealism_runtime::package::SurrealismPackage;
+use surrealism_runtime::wasi_context::WasiConfig;
 use surrealism_types::err::PrefixError;
 
-use crate::commands::SurrealismCommand;
-use crate::host::DemoHost;
+use crate::commands::{SurrealismCommand, engine_config};
+use crate::host::{DemoHost, DryRunHost, ScriptedHost};
+use crate::parse_value;
 
 pub struct RunCommand {
 	pub file: PathBuf,
 	pub fnc: Option<String>,
+	pub namespace: Option<String>,
 	pub args: Vec<surrealdb_types::Value>,
+	pub arg_json: Vec<serde_json::Value>,
+	pub json: bool,
+	pub stream: bool,
+	pub take: Option<usize>,
+	pub arg_files: Vec<PathBuf>,
+	pub arg_stdin: bool,
+	pub env: Vec<String>,
+	pub context: Vec<(String, surrealdb_types::Value)>,
+	pub seed: Option<u64>,
+	pub kv_path: Option<PathBuf>,
+	pub kv_namespaced: bool,
+	pub responses: Option<PathBuf>,
+	pub dry_run: bool,
+	pub max_transfer_bytes: Option<u32>,
+	pub max_array_len: Option<u32>,
+	pub max_memory: Option<usize>,
+	pub deadline: Option<Duration>,
+	pub max_wasm_stack: Option<usize>,
+	pub nan_canonicalization: bool,
+	pub static_memory_maximum_size: Option<u64>,
+	pub precompiled: Option<PathBuf>,
 }
 
 impl SurrealismCommand for RunCommand {
-	async fn run(self) -> Result<()> {
-		let package = SurrealismPackage::from_file(self.file)?;
+	async fn run(mut self) -> Result<()> {
+		// JSON arguments follow any `--arg` values, unless the call is made through JSON
+		if !self.json {
+			self.args.extend(self.arg_json.drain(..).map(from_json));
+		}
+		// Read arguments from files and stdin, after any passed inline
+		for path in &self.arg_files {
+			let arg = std::fs::read_to_string(path)
+				.prefix_err(|| format!("Failed to read argument file {}", path.display()))?;
+			self.args.push(
+				parse_value(arg.trim())
+					.prefix_err(|| format!("Failed to parse argument file {}", path.display()))?,
+			);
+		}
+		// A package read from stdin leaves nothing there for an argument
+		let from_stdin = self.file.as_os_str() == "-";
+		if from_stdin && self.arg_stdin {
+			anyhow::bail!("Cannot read both the package and an argument from stdin");
+		}
+		if self.arg_stdin {
+			let mut arg = String::new();
+			std::io::stdin().read_to_string(&mut arg).prefix_err(|| "Failed to read stdin")?;
+			self.args.push(parse_value(arg.trim()).prefix_err(|| "Failed to parse stdin")?);
+		}
+
+		let package = if from_stdin {
+			let mut bytes = Vec::new();
+			std::io::stdin().read_to_end(&mut bytes).prefix_err(|| "Failed to read stdin")?;
+			SurrealismPackage::from_bytes(&bytes)?
+		} else {
+			SurrealismPackage::from_file(self.file)?
+		};
 
-		// Load the WASM module
-		let runtime = Runtime::new(package)?;
-		let host = Box::new(DemoHost::new());
+		// Load the WASM module, or the module precompiled from it
+		let wasi = self.env.into_iter().fold(WasiConfig::new(), WasiConfig::allow_env);
+		let engine = engine_config(
+			self.max_wasm_stack,
+			self.nan_canonicalization,
+			self.static_memory_maximum_size,
+		);
+		let runtime = match self.precompiled {
+			// The precompiled module must have been compiled from this package's module, so
+			// check its digest against the package even when the package declares none
+			Some(path) => {
+				let mut config = package.config;
+				config.integrity.get_or_insert_with(|| SurrealismIntegrity::new(&package.wasm));
+				// SAFETY: the precompiled module is a local file named by the user running the CLI
+				unsafe { Runtime::from_precompiled_with_config(path, config, &engine)? }
+			}
+			None => Runtime::new_with_config(package, &engine)?,
+		}
+		.with_wasi(wasi);
+		let mut host = match self.seed {
+			Some(seed) => DemoHost::new().with_seed(seed),
+			None => DemoHost::new(),
+		};
+		if let Some(path) = self.kv_path {
+			host = host.with_kv(FileStore::open(path)?);
+		}
+		let mut dry_run = None;
+		let host: Box<dyn InvocationContext> = match self.responses {
+			Some(path) => Box::new(ScriptedHost::from_file(&path, host)?),
+			None if self.dry_run => {
+				let host = DryRunHost::new(host);
+				dry_run = Some(host.log());
+				Box::new(host)
+			}
+			None => Box::new(host),
+		};
 		let mut controller =
 			runtime.new_controller(host).await.prefix_err(|| "Failed to load WASM module")?;
+		controller.set_namespace(self.namespace);
+		controller.set_max_transfer_bytes(self.max_transfer_bytes);
+		controller.set_max_array_len(self.max_array_len);
+		controller.set_max_memory(self.max_memory);
+		controller.set_kv_namespacing(self.kv_namespaced);
+		for (key, value) in self.context {
+			controller.set_context(key, value);
+		}
 
 		controller.init().await?;
 
-		// Invoke the function with the provided arguments
-		let result = controller.invoke(self.fnc, self.args).await;
+		// Invoke the function with the provided arguments, starting the deadline after `init`
+		let before = controller.alloc_stats().await?;
+		controller.set_deadline(self.deadline.map(|deadline| Instant::now() + deadline));
+		let result = if self.json {
+			controller.call_json(self.fnc, self.arg_json).await.and_then(|value| {
+				serde_json::to_string_pretty(&value).prefix_err(|| "Failed to serialize result")
+			})
+		} else if self.stream {
+			print_stream(&mut controller, self.fnc, self.args, self.take).await
+		} else {
+			controller
+				.invoke_checked(self.fnc, self.args)
+				.await
+				.map(|value| format!("{:#}", value.to_sql()))
+		};
+
+		// Report transfer allocations left behind by the call, if the module tracks them
+		if let (Some(before), Some(after)) = (before, controller.alloc_stats().await?)
+			&& after != before
+		{
+			eprintln!(
+				"⚠️ Leaked transfer allocations: {} live allocations ({} bytes) before the call, {} ({} bytes) after",
+				before.allocations, before.bytes, after.allocations, after.bytes
+			);
+		}
+
+		// Summarise what the module would have done, whether or not the call succeeded
+		if let Some(log) = dry_run {
+			let requests = log.requests();
+			match requests.len() {
+				0 => println!("Dry run: the module made no requests"),
+				1 => println!("Dry run: the module made 1 request:"),
+				n => println!("Dry run: the module made {n} requests:"),
+			}
+			for request in requests {
+				println!(" - {request}");
+			}
+		}
 
 		match result {
 			Ok(result) => {
-				println!("✅ {:#}", result.to_sql());
+				println!("✅ {result}");
 			}
 			Err(e) => {
 				eprintln!("❌ {}", e);
@@ -43,3 +180,24 @@ impl SurrealismCommand for RunCommand {
 		Ok(())
 	}
 }
+
+/// Print the values of a stream function as the module produces them, closing the stream
+/// after `take` values, if given, and describe how the stream ended.
+async fn print_stream(
+	controller: &mut Controller,
+	fnc: Option<String>,
+	args: Vec<surrealdb_types::Value>,
+	take: Option<usize>,
+) -> Result<String> {
+	let mut stream = controller.invoke_stream(fnc, args).await?;
+	let mut count = 0;
+	while take.is_none_or(|take| count < take) {
+		let Some(value) = stream.next().await.transpose()? else {
+			return Ok(format!("Stream ended after {count} value(s)"));
+		};
+		println!("{:#}", value.to_sql());
+		count += 1;
+	}
+	stream.close().await?;
+	Ok(format!("Stream closed after {count} value(s)"))
+}
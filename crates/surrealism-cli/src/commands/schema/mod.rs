@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use serde_json::{Value, json};
+use surrealism_runtime::package::SurrealismPackage;
+use surrealism_types::err::PrefixError;
+
+use crate::commands::SurrealismCommand;
+use crate::host::DemoHost;
+
+/// Version of the schema document, raised whenever its shape changes incompatibly.
+const SCHEMA_VERSION: u32 = 1;
+
+pub struct SchemaCommand {
+	pub file: PathBuf,
+}
+
+impl SurrealismCommand for SchemaCommand {
+	async fn run(self) -> anyhow::Result<()> {
+		let package = SurrealismPackage::from_file(self.file)
+			.prefix_err(|| "Failed to load Surrealism package")?;
+		let meta = serde_json::to_value(&package.config.meta)
+			.prefix_err(|| "Failed to describe package metadata")?;
+		let capabilities = serde_json::to_value(&package.config.capabilities)
+			.prefix_err(|| "Failed to describe package capabilities")?;
+		let runtime = surrealism_runtime::controller::Runtime::new(package)?;
+
+		// Load the WASM module from memory
+		let host = Box::new(DemoHost::new());
+		let mut controller =
+			runtime.new_controller(host).await.prefix_err(|| "Failed to load WASM module")?;
+
+		let functions: Vec<Value> = controller
+			.manifest()
+			.await
+			.prefix_err(|| "Failed to describe the functions in the WASM module")?
+			.into_iter()
+			.map(|function| {
+				let args: Vec<Value> = function
+					.args
+					.iter()
+					.enumerate()
+					.map(|(i, kind)| {
+						json!({
+							"name": function.arg_names.get(i),
+							"kind": kind.to_string(),
+						})
+					})
+					.collect();
+				json!({
+					"name": function.path(),
+					"namespace": function.namespace,
+					"function": function.name,
+					"args": args,
+					"returns": function.returns.to_string(),
+					"doc": function.doc,
+					"deprecated": function.deprecated,
+					"pure": function.pure,
+					"stream": function.stream,
+				})
+			})
+			.collect();
+
+		let schema = json!({
+			"schema_version": SCHEMA_VERSION,
+			"package": meta,
+			"capabilities": capabilities,
+			"functions": functions,
+		});
+		let output =
+			serde_json::to_string_pretty(&schema).prefix_err(|| "Failed to serialize schema")?;
+		println!("{output}");
+
+		Ok(())
+	}
+}
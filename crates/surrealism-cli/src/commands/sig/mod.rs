@@ -4,12 +4,13 @@ use surrealism_runtime::controller::Runtime;
 use surrealism_runtime::package::SurrealismPackage;
 use surrealism_types::err::PrefixError;
 
-use crate::commands::SurrealismCommand;
+use crate::commands::{SurrealismCommand, format_args};
 use crate::host::DemoHost;
 
 pub struct SigCommand {
 	pub file: PathBuf,
 	pub fnc: Option<String>,
+	pub namespace: Option<String>,
 }
 
 impl SurrealismCommand for SigCommand {
@@ -22,6 +23,7 @@ impl SurrealismCommand for SigCommand {
 		let host = Box::new(DemoHost::new());
 		let mut controller =
 			runtime.new_controller(host).await.prefix_err(|| "Failed to load WASM module")?;
+		controller.set_namespace(self.namespace.clone());
 
 		// Invoke the function with the provided arguments
 		let args =
@@ -30,13 +32,32 @@ impl SurrealismCommand for SigCommand {
 			.returns(self.fnc.clone())
 			.await
 			.prefix_err(|| "Failed to collect return type")?;
+		let names = controller
+			.arg_names(self.fnc.clone())
+			.await
+			.prefix_err(|| "Failed to collect argument names")?;
+		let deprecated = controller
+			.deprecated(self.fnc.clone())
+			.await
+			.prefix_err(|| "Failed to collect deprecation")?;
+		let pure = controller.is_pure(self.fnc.clone());
+		let stream = controller.is_stream(self.fnc.clone());
 
 		println!(
 			"\nSignature:\n - {}({}) -> {}",
 			self.fnc.as_deref().unwrap_or("<default>"),
-			args.iter().map(|arg| format!("{arg}")).collect::<Vec<_>>().join(", "),
+			format_args(&args, &names),
 			returns
 		);
+		if pure {
+			println!("\nPure: results depend only on the arguments");
+		}
+		if stream {
+			println!("\nStream: values are returned one at a time");
+		}
+		if let Some(note) = deprecated {
+			println!("\n⚠️ Deprecated: {note}");
+		}
 
 		Ok(())
 	}
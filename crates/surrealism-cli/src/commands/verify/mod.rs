@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use surrealism_runtime::controller::Runtime;
+use surrealism_runtime::package::SurrealismPackage;
+use surrealism_types::err::PrefixError;
+
+use crate::commands::SurrealismCommand;
+use crate::host::DemoHost;
+
+/// Exports every Surrealism module must provide to be callable by the runtime.
+const REQUIRED_EXPORTS: [&str; 4] = ["memory", "__sr_abi_version", "__sr_alloc", "__sr_free"];
+
+pub struct VerifyCommand {
+	pub file: PathBuf,
+}
+
+impl SurrealismCommand for VerifyCommand {
+	async fn run(self) -> anyhow::Result<()> {
+		// Loading the package parses the config and its version
+		let package = SurrealismPackage::from_file(self.file)
+			.prefix_err(|| "Failed to load Surrealism package")?;
+		let meta = package.config.meta.clone();
+		println!("✅ Loaded package @{}/{}@{}", meta.organisation, meta.name, meta.version);
+
+		// Compile and instantiate the WASM module
+		let runtime = Runtime::new(package).prefix_err(|| "Failed to compile WASM module")?;
+		let host = Box::new(DemoHost::new());
+		let mut controller = runtime
+			.new_controller(host)
+			.await
+			.prefix_err(|| "Failed to instantiate WASM module")?;
+		println!("✅ Instantiated WASM module");
+
+		let mut failures = 0;
+
+		for name in REQUIRED_EXPORTS {
+			if controller.has_export(name) {
+				println!("✅ Export '{name}' found");
+			} else {
+				println!("❌ Export '{name}' is missing");
+				failures += 1;
+			}
+		}
+
+		// Collect the functions in the root namespace, followed by those in each namespace
+		let mut exports = Vec::new();
+		let namespaces = controller.namespaces();
+		for namespace in std::iter::once(None).chain(namespaces.into_iter().map(Some)) {
+			controller.set_namespace(namespace.clone());
+			let names =
+				controller.list().prefix_err(|| "Failed to list functions in the WASM module")?;
+			exports.extend(names.into_iter().map(|name| (namespace.clone(), name)));
+		}
+		if exports.is_empty() {
+			println!("❌ No functions are exported");
+			failures += 1;
+		}
+
+		for (namespace, name) in exports {
+			controller.set_namespace(namespace.clone());
+			let display = match (&namespace, name.is_empty()) {
+				(None, true) => "<mod>".to_string(),
+				(None, false) => format!("<mod>::{name}"),
+				(Some(namespace), true) => format!("<mod>::{namespace}"),
+				(Some(namespace), false) => format!("<mod>::{namespace}::{name}"),
+			};
+
+			let args = controller.args(Some(name.clone())).await;
+			let returns = controller.returns(Some(name.clone())).await;
+
+			match (args, returns) {
+				(Ok(args), Ok(returns)) => println!(
+					"✅ {display}({}) -> {returns}",
+					args.iter().map(|arg| format!("{arg}")).collect::<Vec<_>>().join(", ")
+				),
+				(Err(e), _) => {
+					println!("❌ {display}: failed to collect arguments: {e}");
+					failures += 1;
+				}
+				(_, Err(e)) => {
+					println!("❌ {display}: failed to collect return type: {e}");
+					failures += 1;
+				}
+			}
+		}
+
+		if failures > 0 {
+			anyhow::bail!("Verification failed with {failures} error(s)");
+		}
+
+		println!("\nVerification passed");
+		Ok(())
+	}
+}
@@ -1,30 +1,69 @@
-use std::io::BufRead;
+use std::collections::VecDeque;
+use std::io::{BufRead, Read};
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
 
 use anyhow::Result;
 use async_trait::async_trait;
-use surrealdb_types::ToSql;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use surrealdb_types::{SurrealValue, ToSql};
 use surrealism_runtime::config::SurrealismConfig;
 use surrealism_runtime::host::InvocationContext;
 use surrealism_runtime::kv::{BTreeMapStore, KVStore};
+use surrealism_runtime::statement;
+use surrealism_types::err::PrefixError;
+use surrealism_types::query::QueryResult;
 
 use crate::parse_value;
 
 pub struct DemoHost {
-	kv: BTreeMapStore,
+	kv: Box<dyn KVStore>,
+	rng: StdRng,
 }
 
 impl DemoHost {
 	pub fn new() -> Self {
 		Self {
-			kv: BTreeMapStore::new(),
+			kv: Box::new(BTreeMapStore::new()),
+			rng: StdRng::from_os_rng(),
 		}
 	}
+
+	/// Seed the random number generator exposed to the module, for reproducible runs.
+	pub fn with_seed(mut self, seed: u64) -> Self {
+		self.rng = StdRng::seed_from_u64(seed);
+		self
+	}
+
+	/// Keep the module's KV entries in `kv`, instead of in memory for this run only.
+	pub fn with_kv(mut self, kv: impl KVStore + 'static) -> Self {
+		self.kv = Box::new(kv);
+		self
+	}
 }
 
 #[async_trait]
 impl InvocationContext for DemoHost {
 	fn kv(&mut self) -> Result<&dyn KVStore> {
-		Ok(&self.kv)
+		Ok(self.kv.as_ref())
+	}
+
+	fn random(&mut self, len: u64) -> Result<Vec<u8>> {
+		let mut buf = vec![0; usize::try_from(len)?];
+		self.rng.fill_bytes(&mut buf);
+		Ok(buf)
+	}
+
+	async fn read_file(&mut self, path: &str) -> Result<Vec<u8>> {
+		// Read one byte past the limit, so that larger files are rejected without reading them
+		let file = std::fs::File::open(path).prefix_err(|| format!("Failed to read '{path}'"))?;
+		let mut contents = Vec::new();
+		file.take(surrealism_types::MAX_FS_READ_BYTES + 1)
+			.read_to_end(&mut contents)
+			.prefix_err(|| format!("Failed to read '{path}'"))?;
+		Ok(contents)
 	}
 
 	async fn sql(
@@ -36,31 +75,33 @@ impl InvocationContext for DemoHost {
 		println!("The module is running a SQL query:");
 		println!("SQL: {query}");
 		println!("Vars: {}", vars.to_sql());
-		println!("Please enter the result:");
+		println!("Please enter the result, or an error message prefixed with '!':");
 
-		let stdin = std::io::stdin();
-		loop {
-			let line = match stdin.lock().lines().next() {
-				Some(Ok(line)) => line,
-				Some(Err(e)) => {
-					anyhow::bail!("Failed to read from stdin: {e}");
-				}
-				None => {
-					anyhow::bail!("stdin closed unexpectedly");
-				}
-			};
+		read_sql_result()?.map_err(anyhow::Error::msg)
+	}
 
-			match parse_value(&line) {
-				Ok(x) => {
-					println!(" ");
-					return Ok(x);
-				}
-				Err(e) => {
-					println!("Failed to parse value: {e}");
-					println!("Please try again");
-				}
-			}
-		}
+	async fn sql_results(
+		&mut self,
+		_config: &SurrealismConfig,
+		query: String,
+		vars: surrealdb_types::Object,
+	) -> Result<Vec<QueryResult>> {
+		let count = statement::count(&query);
+		println!("The module is running a SQL script of {count} statement(s):");
+		println!("SQL: {query}");
+		println!("Vars: {}", vars.to_sql());
+		println!(
+			"Please enter the result of each statement on its own line, or an error message prefixed with '!':"
+		);
+
+		(0..count)
+			.map(|_| {
+				Ok(match read_sql_result()? {
+					Ok(result) => QueryResult::ok(result),
+					Err(error) => QueryResult::err(error),
+				})
+			})
+			.collect()
 	}
 
 	async fn run(
@@ -104,12 +145,373 @@ impl InvocationContext for DemoHost {
 	}
 
 	fn stdout(&mut self, output: &str) -> Result<()> {
-		println!("[surli::out] {}", output);
+		// Each chunk is printed on its own line, so drop its own trailing newline
+		println!("[surli::out] {}", output.trim_end_matches('\n'));
 		Ok(())
 	}
 
 	fn stderr(&mut self, output: &str) -> Result<()> {
-		eprintln!("[surli::err] {}", output);
+		eprintln!("[surli::err] {}", output.trim_end_matches('\n'));
 		Ok(())
 	}
 }
+
+/// Read the result of a SQL statement from stdin, or the error message it fails with when
+/// the line is prefixed with `!`. Lines which do not parse are asked for again.
+fn read_sql_result() -> Result<std::result::Result<surrealdb_types::Value, String>> {
+	let stdin = std::io::stdin();
+	loop {
+		let line = match stdin.lock().lines().next() {
+			Some(Ok(line)) => line,
+			Some(Err(e)) => {
+				anyhow::bail!("Failed to read from stdin: {e}");
+			}
+			None => {
+				anyhow::bail!("stdin closed unexpectedly");
+			}
+		};
+
+		// Simulate a failed query
+		if let Some(error) = line.strip_prefix('!') {
+			println!(" ");
+			return Ok(Err(error.trim().to_string()));
+		}
+
+		match parse_value(&line) {
+			Ok(x) => {
+				println!(" ");
+				return Ok(Ok(x));
+			}
+			Err(e) => {
+				println!("Failed to parse value: {e}");
+				println!("Please try again");
+			}
+		}
+	}
+}
+
+/// A scripted response to a SQL query or function call made by the module.
+#[derive(Debug, SurrealValue)]
+struct ScriptedResponse {
+	/// The SQL query the module is expected to run, if it should be checked.
+	query: Option<String>,
+	/// The function the module is expected to run, if it should be checked.
+	fnc: Option<String>,
+	/// The value to return.
+	result: surrealdb_types::Value,
+	/// An error message to fail the call with, instead of returning `result`.
+	error: Option<String>,
+}
+
+/// The responses file, with the responses to SQL queries and function calls in order.
+#[derive(Debug, SurrealValue)]
+struct ScriptedResponses {
+	sql: Option<Vec<ScriptedResponse>>,
+	run: Option<Vec<ScriptedResponse>>,
+}
+
+/// A non-interactive host which answers SQL queries and function calls from a responses
+/// file, so that modules can be run unattended, for example in CI.
+///
+/// The file holds a JSON or SurrealQL object, with the responses used in order:
+///
+/// ```json
+/// {
+///     "sql": [
+///         { "query": "SELECT * FROM user", "result": [{ "name": "Tobie" }] },
+///         { "error": "Database record `user:tobie` already exists" }
+///     ],
+///     "run": [{ "fnc": "fn::greet", "result": "Hello" }]
+/// }
+/// ```
+///
+/// When a response names a `query` or `fnc`, the call must match it. A call with no
+/// responses left fails, rather than waiting for input.
+pub struct ScriptedHost {
+	inner: DemoHost,
+	sql: VecDeque<ScriptedResponse>,
+	run: VecDeque<ScriptedResponse>,
+}
+
+impl ScriptedHost {
+	/// Load the responses file, delegating everything else to the given host.
+	pub fn from_file(path: &Path, inner: DemoHost) -> Result<Self> {
+		let content = std::fs::read_to_string(path)
+			.prefix_err(|| format!("Failed to read responses file {}", path.display()))?;
+		let responses = parse_value(&content)
+			.map_err(anyhow::Error::msg)
+			.and_then(ScriptedResponses::from_value)
+			.prefix_err(|| format!("Failed to parse responses file {}", path.display()))?;
+		Ok(Self {
+			inner,
+			sql: responses.sql.unwrap_or_default().into(),
+			run: responses.run.unwrap_or_default().into(),
+		})
+	}
+}
+
+/// Take the next response, checking that it was scripted for this call.
+fn next_response(
+	responses: &mut VecDeque<ScriptedResponse>,
+	kind: &str,
+	call: &str,
+	expected: impl Fn(&ScriptedResponse) -> Option<&String>,
+) -> Result<surrealdb_types::Value> {
+	let Some(response) = responses.pop_front() else {
+		anyhow::bail!("No scripted response left for {kind}: {call}");
+	};
+	if let Some(expected) = expected(&response)
+		&& expected != call
+	{
+		anyhow::bail!("Expected {kind} {expected}, but the module ran: {call}");
+	}
+	match response.error {
+		Some(error) => Err(anyhow::Error::msg(error)),
+		None => Ok(response.result),
+	}
+}
+
+#[async_trait]
+impl InvocationContext for ScriptedHost {
+	fn kv(&mut self) -> Result<&dyn KVStore> {
+		self.inner.kv()
+	}
+
+	fn random(&mut self, len: u64) -> Result<Vec<u8>> {
+		self.inner.random(len)
+	}
+
+	async fn read_file(&mut self, path: &str) -> Result<Vec<u8>> {
+		self.inner.read_file(path).await
+	}
+
+	async fn sql(
+		&mut self,
+		_config: &SurrealismConfig,
+		query: String,
+		_vars: surrealdb_types::Object,
+	) -> Result<surrealdb_types::Value> {
+		next_response(&mut self.sql, "SQL query", &query, |response| response.query.as_ref())
+	}
+
+	async fn run(
+		&mut self,
+		_config: &SurrealismConfig,
+		fnc: String,
+		_version: Option<String>,
+		_args: Vec<surrealdb_types::Value>,
+	) -> Result<surrealdb_types::Value> {
+		next_response(&mut self.run, "function", &fnc, |response| response.fnc.as_ref())
+	}
+
+	fn stdout(&mut self, output: &str) -> Result<()> {
+		self.inner.stdout(output)
+	}
+
+	fn stderr(&mut self, output: &str) -> Result<()> {
+		self.inner.stderr(output)
+	}
+}
+
+/// The requests a module made of a [`DryRunHost`], shared with the command running it.
+#[derive(Clone, Default)]
+pub struct DryRunLog(Arc<Mutex<Vec<String>>>);
+
+impl DryRunLog {
+	fn record(&self, request: String) {
+		println!("[dry-run] {request}");
+		self.0.lock().unwrap_or_else(PoisonError::into_inner).push(request);
+	}
+
+	/// The requests made so far, in order.
+	pub fn requests(&self) -> Vec<String> {
+		self.0.lock().unwrap_or_else(PoisonError::into_inner).clone()
+	}
+}
+
+/// A host which records every SQL query, function call and KV request a module makes,
+/// without running them, so that what a module does can be reviewed before trusting it.
+///
+/// SQL queries and function calls return `NONE`. KV requests are applied to a scratch store
+/// which is dropped after the run, so the module reads back its own writes without anything
+/// being kept. File reads change nothing, so they are recorded and then read as usual.
+pub struct DryRunHost {
+	inner: DemoHost,
+	log: DryRunLog,
+}
+
+impl DryRunHost {
+	pub fn new(inner: DemoHost) -> Self {
+		let log = DryRunLog::default();
+		Self {
+			inner: inner.with_kv(RecordingStore {
+				store: BTreeMapStore::new(),
+				log: log.clone(),
+			}),
+			log,
+		}
+	}
+
+	/// The log the module's requests are recorded in.
+	pub fn log(&self) -> DryRunLog {
+		self.log.clone()
+	}
+}
+
+#[async_trait]
+impl InvocationContext for DryRunHost {
+	fn kv(&mut self) -> Result<&dyn KVStore> {
+		self.inner.kv()
+	}
+
+	fn random(&mut self, len: u64) -> Result<Vec<u8>> {
+		self.inner.random(len)
+	}
+
+	async fn read_file(&mut self, path: &str) -> Result<Vec<u8>> {
+		self.log.record(format!("fs::read {path:?}"));
+		self.inner.read_file(path).await
+	}
+
+	async fn sql(
+		&mut self,
+		_config: &SurrealismConfig,
+		query: String,
+		vars: surrealdb_types::Object,
+	) -> Result<surrealdb_types::Value> {
+		let vars = if vars.is_empty() {
+			String::new()
+		} else {
+			format!(" with {}", vars.to_sql())
+		};
+		self.log.record(format!("sql: {query}{vars}"));
+		Ok(surrealdb_types::Value::None)
+	}
+
+	async fn run(
+		&mut self,
+		_config: &SurrealismConfig,
+		fnc: String,
+		version: Option<String>,
+		args: Vec<surrealdb_types::Value>,
+	) -> Result<surrealdb_types::Value> {
+		let version = version.map(|x| format!("<{x}>")).unwrap_or_default();
+		let args = args.iter().map(|x| x.to_sql()).collect::<Vec<String>>().join(", ");
+		self.log.record(format!("run: {fnc}{version}({args})"));
+		Ok(surrealdb_types::Value::None)
+	}
+
+	fn stdout(&mut self, output: &str) -> Result<()> {
+		self.inner.stdout(output)
+	}
+
+	fn stderr(&mut self, output: &str) -> Result<()> {
+		self.inner.stderr(output)
+	}
+}
+
+/// A KV store which records each request before applying it to the store it wraps.
+struct RecordingStore {
+	store: BTreeMapStore,
+	log: DryRunLog,
+}
+
+/// A key range as written in Rust, such as `"a".."b"` or `"a"..`.
+fn range(start: &Bound<String>, end: &Bound<String>) -> String {
+	let start = match start {
+		Bound::Included(key) => format!("{key:?}"),
+		Bound::Excluded(key) => format!("{key:?} (excluded)"),
+		Bound::Unbounded => String::new(),
+	};
+	let end = match end {
+		Bound::Included(key) => format!("={key:?}"),
+		Bound::Excluded(key) => format!("{key:?}"),
+		Bound::Unbounded => String::new(),
+	};
+	format!("{start}..{end}")
+}
+
+#[async_trait]
+impl KVStore for RecordingStore {
+	async fn get(&self, key: String) -> Result<Option<surrealdb_types::Value>> {
+		self.log.record(format!("kv::get {key:?}"));
+		self.store.get(key).await
+	}
+
+	async fn set(&self, key: String, value: surrealdb_types::Value) -> Result<()> {
+		self.log.record(format!("kv::set {key:?} = {}", value.to_sql()));
+		self.store.set(key, value).await
+	}
+
+	async fn del(&self, key: String) -> Result<()> {
+		self.log.record(format!("kv::del {key:?}"));
+		self.store.del(key).await
+	}
+
+	async fn exists(&self, key: String) -> Result<bool> {
+		self.log.record(format!("kv::exists {key:?}"));
+		self.store.exists(key).await
+	}
+
+	async fn del_rng(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
+		self.log.record(format!("kv::del_rng {}", range(&start, &end)));
+		self.store.del_rng(start, end).await
+	}
+
+	async fn get_batch(&self, keys: Vec<String>) -> Result<Vec<Option<surrealdb_types::Value>>> {
+		self.log.record(format!("kv::get_batch {keys:?}"));
+		self.store.get_batch(keys).await
+	}
+
+	async fn set_batch(&self, entries: Vec<(String, surrealdb_types::Value)>) -> Result<()> {
+		let described: Vec<String> =
+			entries.iter().map(|(key, value)| format!("{key:?} = {}", value.to_sql())).collect();
+		self.log.record(format!("kv::set_batch [{}]", described.join(", ")));
+		self.store.set_batch(entries).await
+	}
+
+	async fn del_batch(&self, keys: Vec<String>) -> Result<()> {
+		self.log.record(format!("kv::del_batch {keys:?}"));
+		self.store.del_batch(keys).await
+	}
+
+	async fn keys(&self, start: Bound<String>, end: Bound<String>) -> Result<Vec<String>> {
+		self.log.record(format!("kv::keys {}", range(&start, &end)));
+		self.store.keys(start, end).await
+	}
+
+	async fn values(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+	) -> Result<Vec<surrealdb_types::Value>> {
+		self.log.record(format!("kv::values {}", range(&start, &end)));
+		self.store.values(start, end).await
+	}
+
+	async fn entries(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+	) -> Result<Vec<(String, surrealdb_types::Value)>> {
+		self.log.record(format!("kv::entries {}", range(&start, &end)));
+		self.store.entries(start, end).await
+	}
+
+	async fn count(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
+		self.log.record(format!("kv::count {}", range(&start, &end)));
+		self.store.count(start, end).await
+	}
+
+	async fn scan(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+		limit: u64,
+		after: Option<String>,
+	) -> Result<(Vec<(String, surrealdb_types::Value)>, Option<String>)> {
+		let from = after.as_ref().map(|key| format!(" after {key:?}")).unwrap_or_default();
+		self.log.record(format!("kv::scan {} limit {limit}{from}", range(&start, &end)));
+		self.store.scan(start, end, limit, after).await
+	}
+}
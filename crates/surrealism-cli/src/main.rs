@@ -8,8 +8,11 @@ use clap::{Parser, Subcommand};
 use crate::commands::SurrealismCommand;
 use crate::commands::build::BuildCommand;
 use crate::commands::info::InfoCommand;
+use crate::commands::precompile::PrecompileCommand;
 use crate::commands::run::RunCommand;
+use crate::commands::schema::SchemaCommand;
 use crate::commands::sig::SigCommand;
+use crate::commands::verify::VerifyCommand;
 
 /// CLI definition
 #[derive(Debug, Parser)]
@@ -19,6 +22,9 @@ struct Cli {
 	command: Commands,
 }
 
+// `Run` holds every run option, so it is a few hundred bytes larger than the other variants.
+// The command is parsed once and moved straight into `main`, so boxing it would save nothing.
+#[expect(clippy::large_enum_variant)]
 #[derive(Debug, Subcommand)]
 enum Commands {
 	/// Run a function with arguments
@@ -27,11 +33,110 @@ enum Commands {
 		#[arg(long = "arg", value_parser = parse_value)]
 		args: Vec<surrealdb_types::Value>,
 
+		/// Arguments passed to function as JSON, after any `--arg` values (repeatable)
+		#[arg(long = "arg-json", value_name = "JSON", value_parser = parse_json)]
+		arg_json: Vec<serde_json::Value>,
+
+		/// Invoke the function entirely through JSON, with the arguments passed by `--arg-json`
+		/// and the result printed as JSON
+		#[arg(long, conflicts_with_all = ["args", "arg_files", "arg_stdin"])]
+		json: bool,
+
+		/// Print the values of a stream function as the module produces them, instead of
+		/// collecting them into an array
+		#[arg(long, conflicts_with = "json")]
+		stream: bool,
+
+		/// Stop the stream after this many values, releasing it in the module
+		#[arg(long, value_name = "COUNT", requires = "stream")]
+		take: Option<usize>,
+
+		/// Files containing an argument each, passed after any `--arg` and `--arg-json` values
+		/// (repeatable)
+		#[arg(long = "arg-file", value_name = "PATH")]
+		arg_files: Vec<PathBuf>,
+
+		/// Read a final argument from stdin, which is then unavailable for answering queries
+		#[arg(long)]
+		arg_stdin: bool,
+
+		/// Host environment variables exposed to the module (repeatable)
+		#[arg(long = "env", value_name = "KEY")]
+		env: Vec<String>,
+
+		/// Context values readable by the module, as `KEY=VALUE` (repeatable)
+		#[arg(long = "ctx", value_name = "KEY=VALUE", value_parser = parse_context)]
+		context: Vec<(String, surrealdb_types::Value)>,
+
+		/// Seed for the random number generator exposed to the module
+		#[arg(long)]
+		seed: Option<u64>,
+
+		/// File the module's KV store is kept in, so that entries persist between runs
+		#[arg(long, value_name = "PATH")]
+		kv_path: Option<PathBuf>,
+
+		/// Keep the module's KV keys under `organisation/name/`, so that modules sharing one
+		/// KV store do not see each other's keys
+		#[arg(long)]
+		kv_namespaced: bool,
+
+		/// File with scripted responses to SQL queries and function calls, instead of prompting
+		#[arg(long, value_name = "PATH")]
+		responses: Option<PathBuf>,
+
+		/// List the SQL queries, function calls and KV requests the module makes, without
+		/// running them
+		#[arg(long, conflicts_with_all = ["kv_path", "responses"])]
+		dry_run: bool,
+
+		/// Largest value, in bytes, the module may transfer to the host
+		#[arg(long, value_name = "BYTES")]
+		max_transfer_bytes: Option<u32>,
+
+		/// Most elements an array transferred from the module to the host may have
+		#[arg(long, value_name = "LEN")]
+		max_array_len: Option<u32>,
+
+		/// Most linear memory, in bytes, the module may grow to
+		#[arg(long, value_name = "BYTES")]
+		max_memory: Option<usize>,
+
+		/// Time the function should finish within, such as `500ms`, which the module can check
+		/// to stop early, but which does not interrupt it
+		#[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+		deadline: Option<std::time::Duration>,
+
+		/// Most stack, in bytes, the module may use, for deeply recursive modules
+		#[arg(long, value_name = "BYTES")]
+		max_wasm_stack: Option<usize>,
+
+		/// Replace NaNs with a canonical NaN, so float results do not depend on the host CPU
+		#[arg(long)]
+		nan_canonicalization: bool,
+
+		/// Virtual address space, in bytes, reserved up front for the module memory
+		#[arg(long, value_name = "BYTES")]
+		static_memory_maximum_size: Option<u64>,
+
+		/// Module precompiled from the package with `precompile`, used instead of compiling it
+		#[arg(long, value_name = "PATH")]
+		precompiled: Option<PathBuf>,
+
 		/// Required name
 		#[arg(long)]
 		fnc: Option<String>,
 
-		/// Path to WASM file
+		/// Run the default function, the same as omitting `--fnc`
+		#[arg(long, conflicts_with = "fnc")]
+		default: bool,
+
+		/// Namespace the function is declared in, if any
+		#[arg(long)]
+		namespace: Option<String>,
+
+		/// Path to WASM file, or `-` to read the package from stdin, which is then unavailable
+		/// for answering queries
 		#[arg(value_name = "FILE")]
 		file: PathBuf,
 	},
@@ -42,6 +147,10 @@ enum Commands {
 		#[arg(long)]
 		fnc: Option<String>,
 
+		/// Namespace the function is declared in, if any
+		#[arg(long)]
+		namespace: Option<String>,
+
 		/// Path to WASM file
 		#[arg(value_name = "FILE")]
 		file: PathBuf,
@@ -49,6 +158,48 @@ enum Commands {
 
 	/// Show the module information
 	Info {
+		/// Fail if any function cannot be described, instead of reporting it inline
+		#[arg(long)]
+		strict: bool,
+
+		/// Path to WASM file
+		#[arg(value_name = "FILE")]
+		file: PathBuf,
+	},
+
+	/// Describe the package and its functions as JSON, for generating bindings
+	Schema {
+		/// Path to WASM file
+		#[arg(value_name = "FILE")]
+		file: PathBuf,
+	},
+
+	/// Compile a package ahead of time, for `run --precompiled` to load without compiling it
+	Precompile {
+		/// Output file path, defaulting to the package path with a `.cwasm` extension
+		#[arg(short = 'o', long)]
+		out: Option<PathBuf>,
+
+		/// Most stack, in bytes, the module may use, which must match when it is run
+		#[arg(long, value_name = "BYTES")]
+		max_wasm_stack: Option<usize>,
+
+		/// Replace NaNs with a canonical NaN, which must match when it is run
+		#[arg(long)]
+		nan_canonicalization: bool,
+
+		/// Virtual address space, in bytes, reserved for the module memory, which must match
+		/// when it is run
+		#[arg(long, value_name = "BYTES")]
+		static_memory_maximum_size: Option<u64>,
+
+		/// Path to WASM file
+		#[arg(value_name = "FILE")]
+		file: PathBuf,
+	},
+
+	/// Instantiate a package and check that all of its functions can be described
+	Verify {
 		/// Path to WASM file
 		#[arg(value_name = "FILE")]
 		file: PathBuf,
@@ -59,6 +210,10 @@ enum Commands {
 		#[arg(short = 'o', long)]
 		out: Option<PathBuf>,
 
+		/// Skip stripping and optimizing the WASM module, keeping debug information
+		#[arg(long)]
+		no_optimize: bool,
+
 		/// Path to source directory (defaults to current directory)
 		#[arg(value_name = "SOURCE_PATH")]
 		path: Option<PathBuf>,
@@ -70,6 +225,22 @@ fn parse_value(s: &str) -> Result<surrealdb_types::Value, String> {
 	surrealdb_core::syn::value(s).map_err(|e| format!("Invalid value: {e}"))
 }
 
+/// Custom parser for JSON, for arguments produced by other tools
+fn parse_json(s: &str) -> Result<serde_json::Value, String> {
+	serde_json::from_str(s).map_err(|e| format!("Invalid JSON: {e}"))
+}
+
+fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+	s.parse::<surrealdb_types::Duration>()
+		.map(Into::into)
+		.map_err(|e| format!("Invalid duration: {e}"))
+}
+
+fn parse_context(s: &str) -> Result<(String, surrealdb_types::Value), String> {
+	let (key, value) = s.split_once('=').ok_or_else(|| format!("Expected KEY=VALUE, got '{s}'"))?;
+	Ok((key.to_string(), parse_value(value)?))
+}
+
 #[tokio::main]
 async fn main() {
 	let cli = Cli::parse();
@@ -77,13 +248,59 @@ async fn main() {
 	match cli.command {
 		Commands::Run {
 			args,
+			arg_json,
+			json,
+			stream,
+			take,
+			arg_files,
+			arg_stdin,
+			env,
+			context,
+			seed,
+			kv_path,
+			kv_namespaced,
+			responses,
+			dry_run,
+			max_transfer_bytes,
+			max_array_len,
+			max_memory,
+			deadline,
+			max_wasm_stack,
+			nan_canonicalization,
+			static_memory_maximum_size,
+			precompiled,
 			fnc,
+			// Conflicts with `--fnc`, so the default function is run when `fnc` is `None`
+			default: _,
+			namespace,
 			file,
 		} => {
 			let run_command = RunCommand {
 				file,
 				fnc,
+				namespace,
 				args,
+				arg_json,
+				json,
+				stream,
+				take,
+				arg_files,
+				arg_stdin,
+				env,
+				context,
+				seed,
+				kv_path,
+				kv_namespaced,
+				responses,
+				dry_run,
+				max_transfer_bytes,
+				max_array_len,
+				max_memory,
+				deadline,
+				max_wasm_stack,
+				nan_canonicalization,
+				static_memory_maximum_size,
+				precompiled,
 			};
 
 			if let Err(e) = run_command.run().await {
@@ -93,11 +310,13 @@ async fn main() {
 		}
 		Commands::Sig {
 			fnc,
+			namespace,
 			file,
 		} => {
 			let run_command = SigCommand {
 				file,
 				fnc,
+				namespace,
 			};
 
 			if let Err(e) = run_command.run().await {
@@ -106,23 +325,68 @@ async fn main() {
 			}
 		}
 		Commands::Info {
+			strict,
 			file,
 		} => {
 			let info_command = InfoCommand {
 				file,
+				strict,
 			};
 			if let Err(e) = info_command.run().await {
 				eprintln!("Error: {e}");
 				std::process::exit(1);
 			}
 		}
+		Commands::Schema {
+			file,
+		} => {
+			let schema_command = SchemaCommand {
+				file,
+			};
+			if let Err(e) = schema_command.run().await {
+				eprintln!("Error: {e}");
+				std::process::exit(1);
+			}
+		}
+		Commands::Precompile {
+			out,
+			max_wasm_stack,
+			nan_canonicalization,
+			static_memory_maximum_size,
+			file,
+		} => {
+			let precompile_command = PrecompileCommand {
+				file,
+				out,
+				max_wasm_stack,
+				nan_canonicalization,
+				static_memory_maximum_size,
+			};
+			if let Err(e) = precompile_command.run().await {
+				eprintln!("Error: {e}");
+				std::process::exit(1);
+			}
+		}
+		Commands::Verify {
+			file,
+		} => {
+			let verify_command = VerifyCommand {
+				file,
+			};
+			if let Err(e) = verify_command.run().await {
+				eprintln!("Error: {e}");
+				std::process::exit(1);
+			}
+		}
 		Commands::Build {
 			out,
+			no_optimize,
 			path,
 		} => {
 			let build_command = BuildCommand {
 				path,
 				out,
+				no_optimize,
 			};
 			if let Err(e) = build_command.run().await {
 				eprintln!("Error: {e}");
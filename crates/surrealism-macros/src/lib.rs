@@ -3,18 +3,348 @@ use quote::{format_ident, quote};
 use syn::punctuated::Punctuated;
 use syn::token::Comma;
 use syn::{
-	Expr, ExprLit, FnArg, GenericArgument, ItemFn, Lit, Meta, MetaNameValue, PatType,
-	PathArguments, ReturnType, Type, TypePath, parse_macro_input,
+	Error, Expr, ExprLit, FnArg, GenericArgument, ItemFn, Lit, LitStr, Meta, MetaList,
+	MetaNameValue, Pat, PatType, PathArguments, ReturnType, Type, TypeParamBound, TypePath,
+	parse_macro_input,
 };
 
+/// Names starting with this prefix are reserved for the symbols generated by the macro and
+/// the runtime, such as `__sr_fnc__<name>` and `__sr_alloc`.
+const RESERVED_PREFIX: &str = "__";
+
+fn validate_export_name(name: &LitStr, attr: &str) -> syn::Result<()> {
+	let value = name.value();
+	if value.is_empty() {
+		return Err(Error::new_spanned(
+			name,
+			format!("#[surrealism({attr})] cannot be empty, use #[surrealism(default)] instead"),
+		));
+	}
+	if !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+		return Err(Error::new_spanned(
+			name,
+			format!("#[surrealism({attr})] must use only ASCII letters, digits, and underscores"),
+		));
+	}
+	validate_reserved_name(&value, name.span())
+}
+
+fn validate_reserved_name(name: &str, span: proc_macro2::Span) -> syn::Result<()> {
+	if name.starts_with(RESERVED_PREFIX) {
+		return Err(Error::new(
+			span,
+			format!(
+				"#[surrealism] function name \"{name}\" is reserved: names cannot start with \"{RESERVED_PREFIX}\""
+			),
+		));
+	}
+	Ok(())
+}
+
+/// The first generic type argument of `ty`, if it is a path ending in `ident`, such as `T`
+/// in `Result<T, E>` or `std::option::Option<T>`.
+fn generic_inner<'a>(ty: &'a Type, ident: &str) -> Option<&'a Type> {
+	let Type::Path(TypePath {
+		path,
+		..
+	}) = ty
+	else {
+		return None;
+	};
+	let last_segment = path.segments.last()?;
+	if last_segment.ident != ident {
+		return None;
+	}
+	let PathArguments::AngleBracketed(args) = &last_segment.arguments else {
+		return None;
+	};
+	match args.args.first()? {
+		GenericArgument::Type(inner_type) => Some(inner_type),
+		_ => None,
+	}
+}
+
+/// The element type of a `BTreeSet<T>` or `HashSet<T>`.
+fn set_element(ty: &Type) -> Option<&Type> {
+	generic_inner(ty, "BTreeSet").or_else(|| generic_inner(ty, "HashSet"))
+}
+
+/// The item type of an `impl Iterator<Item = T>`, as returned by a stream function.
+fn iterator_item(ty: &Type) -> Option<&Type> {
+	let Type::ImplTrait(impl_trait) = ty else {
+		return None;
+	};
+	impl_trait.bounds.iter().find_map(|bound| {
+		let TypeParamBound::Trait(bound) = bound else {
+			return None;
+		};
+		let last_segment = bound.path.segments.last()?;
+		if last_segment.ident != "Iterator" {
+			return None;
+		}
+		let PathArguments::AngleBracketed(args) = &last_segment.arguments else {
+			return None;
+		};
+		args.args.iter().find_map(|arg| match arg {
+			GenericArgument::AssocType(assoc) if assoc.ident == "Item" => Some(&assoc.ty),
+			_ => None,
+		})
+	})
+}
+
+/// Whether `ty` is a borrowed string, such as `&'static str` or `Cow<'_, str>`.
+fn is_borrowed_str(ty: &Type) -> bool {
+	let is_str = |ty: &Type| matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("str"));
+	match ty {
+		Type::Reference(reference) => reference.mutability.is_none() && is_str(&reference.elem),
+		Type::Path(TypePath {
+			path,
+			..
+		}) => {
+			let Some(last_segment) = path.segments.last() else {
+				return false;
+			};
+			let PathArguments::AngleBracketed(args) = &last_segment.arguments else {
+				return false;
+			};
+			last_segment.ident == "Cow"
+				&& args
+					.args
+					.iter()
+					.any(|arg| matches!(arg, GenericArgument::Type(ty) if is_str(ty)))
+		}
+		_ => false,
+	}
+}
+
+/// How a type is passed across the boundary when `SurrealValue` cannot be implemented for it
+/// outside of `surrealdb_types`, such as sets, `char`, `u128`, `[T; N]` and borrowed strings,
+/// or an `Option<T>` or `Vec<T>` of such a type.
+struct Wrapper {
+	/// The type the value is transferred as, such as `SetOf<T>`.
+	ty: proc_macro2::TokenStream,
+	/// Converts a received wrapper, bound to an identifier, into the declared type.
+	unwrap: fn(&proc_macro2::Ident, &Type) -> proc_macro2::TokenStream,
+	/// A function converting a returned value into the wrapper.
+	wrap: proc_macro2::TokenStream,
+}
+
+/// The wrapper a type is passed across the boundary as, or `None` if it is passed as is.
+fn wrapper(ty: &Type) -> Option<Wrapper> {
+	// Options and vectors compose with `SurrealValue` as is, and only need a wrapper for an
+	// element which has one, such as `Option<char>` or `Vec<Option<u128>>`. The unwrapping
+	// functions are plain functions, so they find the element wrapper from the type again.
+	if let Some(inner) = generic_inner(ty, "Option") {
+		let Wrapper {
+			ty: inner_ty,
+			wrap: inner_wrap,
+			..
+		} = wrapper(inner)?;
+		return Some(Wrapper {
+			ty: quote! { Option<#inner_ty> },
+			unwrap: |ident, ty| {
+				let inner = generic_inner(ty, "Option").expect("an option type");
+				let value = element_unwrap(inner);
+				quote! { #ident.map(|__v| #value) }
+			},
+			wrap: quote! { (|__v: Option<_>| __v.map(#inner_wrap)) },
+		});
+	}
+	if let Some(inner) = generic_inner(ty, "Vec") {
+		// A vector of bytes is transferred as `bytes`, rather than as an array of numbers
+		if matches!(inner, Type::Path(path) if path.qself.is_none() && path.path.is_ident("u8")) {
+			return Some(Wrapper {
+				ty: quote! { surrealism::types::binary::ByteVec },
+				unwrap: |ident, _| quote! { #ident.0 },
+				wrap: quote! { <surrealism::types::binary::ByteVec>::from },
+			});
+		}
+		let Wrapper {
+			ty: inner_ty,
+			wrap: inner_wrap,
+			..
+		} = wrapper(inner)?;
+		return Some(Wrapper {
+			ty: quote! { Vec<#inner_ty> },
+			unwrap: |ident, ty| {
+				let inner = generic_inner(ty, "Vec").expect("a vector type");
+				let value = element_unwrap(inner);
+				quote! { #ident.into_iter().map(|__v| #value).collect::<#ty>() }
+			},
+			wrap: quote! { (|__v: Vec<_>| __v.into_iter().map(#inner_wrap).collect::<Vec<_>>()) },
+		});
+	}
+	// Borrowed strings own no guest memory of their own, and are transferred as a `String`
+	if is_borrowed_str(ty) {
+		return Some(Wrapper {
+			ty: quote! { String },
+			unwrap: |ident, _| quote! { ::std::borrow::Cow::<str>::Owned(#ident) },
+			wrap: quote! { <String>::from },
+		});
+	}
+	if let Some(element) = set_element(ty) {
+		return Some(Wrapper {
+			ty: quote! { surrealism::types::set::SetOf<#element> },
+			unwrap: |ident, ty| quote! { #ident.0.into_iter().collect::<#ty>() },
+			wrap: quote! { surrealism::types::set::SetOf::from_iter },
+		});
+	}
+	let wrapper = match ty {
+		Type::Path(path) if path.qself.is_none() && path.path.is_ident("char") => {
+			quote! { surrealism::types::char::Char }
+		}
+		Type::Path(path) if path.qself.is_none() && path.path.is_ident("u128") => {
+			quote! { surrealism::types::int128::U128 }
+		}
+		Type::Path(path) if path.qself.is_none() && path.path.is_ident("i128") => {
+			quote! { surrealism::types::int128::I128 }
+		}
+		Type::Array(array) => {
+			let (elem, len) = (&array.elem, &array.len);
+			match &*array.elem {
+				Type::Path(path) if path.qself.is_none() && path.path.is_ident("u8") => {
+					quote! { surrealism::types::array::BytesOf<#len> }
+				}
+				_ => quote! { surrealism::types::array::ArrayOf<#elem, #len> },
+			}
+		}
+		_ => return None,
+	};
+	Some(Wrapper {
+		wrap: quote! { <#wrapper>::from },
+		ty: wrapper,
+		unwrap: |ident, _| quote! { #ident.0 },
+	})
+}
+
+/// Converts a received element wrapper, bound to `__v`, into the element type `ty` of an
+/// `Option<T>` or `Vec<T>`.
+fn element_unwrap(ty: &Type) -> proc_macro2::TokenStream {
+	let wrapper = wrapper(ty).expect("an element type with a wrapper");
+	(wrapper.unwrap)(&format_ident!("__v"), ty)
+}
+
+/// The wrapper passing a `serde` type across the boundary through its JSON form, for
+/// functions marked `#[surrealism(serde)]`.
+fn serde_wrapper(ty: &Type) -> Wrapper {
+	Wrapper {
+		ty: quote! { surrealism::types::json::Serde<#ty> },
+		unwrap: |ident, _| quote! { #ident.0 },
+		wrap: quote! { surrealism::types::json::Serde },
+	}
+}
+
+/// The owned type an argument taken by reference is received as, such as `Vec<T>` for `&[T]`
+/// or `String` for `&str`, or `None` if it is taken by value. Values are transferred into the
+/// module, so a referenced argument is received as an owned value and borrowed for the call.
+fn referenced_type(ty: &Type) -> syn::Result<Option<Type>> {
+	let Type::Reference(reference) = ty else {
+		return Ok(None);
+	};
+	if reference.mutability.is_some() {
+		return Err(Error::new_spanned(
+			reference,
+			"`&mut` arguments are not supported in #[surrealism] functions, take them by value",
+		));
+	}
+	let owned = match &*reference.elem {
+		Type::Slice(slice) => {
+			let elem = &slice.elem;
+			syn::parse_quote! { Vec<#elem> }
+		}
+		Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => {
+			syn::parse_quote! { String }
+		}
+		elem => elem.clone(),
+	};
+	Ok(Some(owned))
+}
+
+/// The time the module is compiled, in seconds since the Unix epoch. The macro expands again
+/// whenever the crate is compiled, so this is when the module was last compiled.
+fn compile_time() -> u64 {
+	std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map(|elapsed| elapsed.as_secs())
+		.unwrap_or(0)
+}
+
+/// The name of an argument as shown in signatures. Tuple patterns keep their shape, such as
+/// `(name, age)`, and anything without a name, such as `_`, is named after its position.
+fn pattern_name(pat: &Pat, position: usize) -> String {
+	match pat {
+		Pat::Ident(ident) => ident.ident.to_string(),
+		Pat::Tuple(tuple) => format!(
+			"({})",
+			tuple
+				.elems
+				.iter()
+				.enumerate()
+				.map(|(i, pat)| pattern_name(pat, i + 1))
+				.collect::<Vec<_>>()
+				.join(", ")
+		),
+		Pat::Paren(paren) => pattern_name(&paren.pat, position),
+		Pat::Reference(reference) => pattern_name(&reference.pat, position),
+		_ => format!("arg{position}"),
+	}
+}
+
+/// Export a function from a Surrealism module.
+///
+/// Invalid uses are reported as compile errors pointing at the offending attribute or part of
+/// the signature. For example, names starting with `__` are reserved for generated symbols:
+///
+/// ```compile_fail
+/// use surrealism::surrealism;
+///
+/// #[surrealism(name = "__sr_alloc")]
+/// fn alloc() -> i64 {
+///     0
+/// }
+/// ```
+///
+/// Arguments cannot be borrowed mutably, as they are transferred into the module:
+///
+/// ```compile_fail
+/// use surrealism::surrealism;
+///
+/// #[surrealism]
+/// fn push(values: &mut Vec<i64>) {
+///     values.push(1);
+/// }
+/// ```
+///
+/// Stream functions cannot also pass their values through `serde`:
+///
+/// ```compile_fail
+/// use surrealism::surrealism;
+///
+/// #[surrealism(stream, serde)]
+/// fn numbers() -> impl Iterator<Item = i64> {
+///     0..10
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn surrealism(attr: TokenStream, item: TokenStream) -> TokenStream {
 	let args = parse_macro_input!(attr with Punctuated::<Meta, Comma>::parse_terminated);
 	let input_fn = parse_macro_input!(item as ItemFn);
+	expand(args, input_fn).unwrap_or_else(Error::into_compile_error).into()
+}
 
+fn expand(
+	args: Punctuated<Meta, Comma>,
+	input_fn: ItemFn,
+) -> syn::Result<proc_macro2::TokenStream> {
 	let mut is_default = false;
 	let mut export_name_override: Option<String> = None;
 	let mut is_init = false;
+	let mut is_pure = false;
+	let mut is_stream = false;
+	let mut is_serde = false;
+	let mut aliases: Vec<LitStr> = Vec::new();
+	let mut deprecated: Option<String> = None;
+	let mut namespace: Option<String> = None;
 
 	for meta in args.iter() {
 		match meta {
@@ -28,13 +358,71 @@ pub fn surrealism(attr: TokenStream, item: TokenStream) -> TokenStream {
 					..
 				}) = value
 				{
-					let val = s.value();
-					if !val.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
-						panic!(
-							"#[surrealism(name = \"...\")] must use only ASCII letters, digits, and underscores"
-						);
+					validate_export_name(s, "name = \"...\"")?;
+					export_name_override = Some(s.value());
+				}
+			}
+			Meta::NameValue(MetaNameValue {
+				path,
+				value,
+				..
+			}) if path.is_ident("deprecated") => {
+				if let Expr::Lit(ExprLit {
+					lit: Lit::Str(s),
+					..
+				}) = value
+				{
+					deprecated = Some(s.value());
+				} else {
+					return Err(Error::new_spanned(
+						value,
+						"#[surrealism(deprecated = \"...\")] expects a string literal",
+					));
+				}
+			}
+			Meta::NameValue(MetaNameValue {
+				path,
+				value,
+				..
+			}) if path.is_ident("namespace") => {
+				if let Expr::Lit(ExprLit {
+					lit: Lit::Str(s),
+					..
+				}) = value
+				{
+					if s.value().is_empty() {
+						return Err(Error::new_spanned(
+							s,
+							"#[surrealism(namespace = \"...\")] cannot be empty",
+						));
 					}
-					export_name_override = Some(val);
+					validate_export_name(s, "namespace = \"...\"")?;
+					namespace = Some(s.value());
+				} else {
+					return Err(Error::new_spanned(
+						value,
+						"#[surrealism(namespace = \"...\")] expects a string literal",
+					));
+				}
+			}
+			Meta::List(MetaList {
+				path,
+				tokens,
+				..
+			}) if path.is_ident("aliases") => {
+				let names = syn::parse::Parser::parse2(
+					Punctuated::<LitStr, Comma>::parse_terminated,
+					tokens.clone(),
+				)
+				.map_err(|e| {
+					Error::new(
+						e.span(),
+						"#[surrealism(aliases(...))] expects a list of string literals",
+					)
+				})?;
+				for name in names {
+					validate_export_name(&name, "aliases(...)")?;
+					aliases.push(name);
 				}
 			}
 			Meta::Path(path) if path.is_ident("default") => {
@@ -43,12 +431,44 @@ pub fn surrealism(attr: TokenStream, item: TokenStream) -> TokenStream {
 			Meta::Path(path) if path.is_ident("init") => {
 				is_init = true;
 			}
-			_ => panic!(
-				"Unsupported attribute: expected #[surrealism], #[surrealism(default)], #[surrealism(init)], or #[surrealism(name = \"...\")]"
-			),
+			Meta::Path(path) if path.is_ident("pure") => {
+				is_pure = true;
+			}
+			Meta::Path(path) if path.is_ident("stream") => {
+				is_stream = true;
+			}
+			Meta::Path(path) if path.is_ident("serde") => {
+				is_serde = true;
+			}
+			_ => {
+				return Err(Error::new_spanned(
+					meta,
+					"Unsupported attribute: expected #[surrealism], #[surrealism(default)], #[surrealism(init)], #[surrealism(pure)], #[surrealism(stream)], #[surrealism(serde)], #[surrealism(name = \"...\")], #[surrealism(aliases(\"...\"))], #[surrealism(namespace = \"...\")], or #[surrealism(deprecated = \"...\")]",
+				));
+			}
 		}
 	}
 
+	// Doc comments are exported, so that tooling can describe the function
+	let doc = input_fn
+		.attrs
+		.iter()
+		.filter_map(|attr| match &attr.meta {
+			Meta::NameValue(MetaNameValue {
+				path,
+				value: Expr::Lit(ExprLit {
+					lit: Lit::Str(line),
+					..
+				}),
+				..
+			}) if path.is_ident("doc") => Some(line.value()),
+			_ => None,
+		})
+		.map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+		.collect::<Vec<_>>()
+		.join("\n");
+	let doc = doc.trim();
+
 	let fn_name = &input_fn.sig.ident;
 	let fn_vis = &input_fn.vis;
 	let fn_sig = &input_fn.sig;
@@ -68,51 +488,138 @@ pub fn surrealism(attr: TokenStream, item: TokenStream) -> TokenStream {
 				arg_patterns.push(pat.clone());
 				arg_types.push(ty);
 			}
-			FnArg::Receiver(_) => panic!("`self` is not supported in #[surrealism] functions"),
+			FnArg::Receiver(receiver) => {
+				return Err(Error::new_spanned(
+					receiver,
+					"`self` is not supported in #[surrealism] functions",
+				));
+			}
 		}
 	}
 
+	// Argument names exported for signatures
+	let arg_names: Vec<String> =
+		arg_patterns.iter().enumerate().map(|(i, pat)| pattern_name(pat, i + 1)).collect();
+
+	// Bind each argument to a plain identifier, as patterns such as `mut age` or `_` cannot be
+	// passed on to the function as expressions
+	let arg_idents: Vec<_> = (0..arg_types.len()).map(|i| format_ident!("__arg{}", i)).collect();
+
+	// Arguments taken by reference are received as an owned value and borrowed for the call
+	let referenced_types =
+		arg_types.iter().map(|ty| referenced_type(ty)).collect::<syn::Result<Vec<_>>>()?;
+	let owned_types: Vec<&Type> = arg_types
+		.iter()
+		.zip(&referenced_types)
+		.map(|(ty, owned)| owned.as_ref().unwrap_or(ty))
+		.collect();
+
+	// Types without a `SurrealValue` implementation, such as sets, are received as a wrapper
+	// and converted into the declared type. Every value of a serde function is passed through
+	// its JSON form.
+	let wrapper_of = |ty: &Type| {
+		if is_serde {
+			Some(serde_wrapper(ty))
+		} else {
+			wrapper(ty)
+		}
+	};
+	let transfer_types: Vec<_> = owned_types
+		.iter()
+		.map(|ty| match wrapper_of(ty) {
+			Some(wrapper) => wrapper.ty,
+			None => quote! { #ty },
+		})
+		.collect();
+	let call_args: Vec<_> = owned_types
+		.iter()
+		.zip(&referenced_types)
+		.zip(&arg_idents)
+		.map(|((ty, owned), ident)| {
+			let arg = match wrapper_of(ty) {
+				Some(wrapper) => (wrapper.unwrap)(ident, ty),
+				None => quote! { #ident },
+			};
+			match owned {
+				Some(_) => quote! { &#arg },
+				None => arg,
+			}
+		})
+		.collect();
+
 	// Compose tuple type and pattern (single args are passed directly)
 	let (tuple_type, tuple_pattern) = if arg_types.is_empty() {
 		(quote! { () }, quote! { () })
 	} else if arg_types.len() == 1 {
-		(quote! { (#(#arg_types),*,) }, quote! { (#(#arg_patterns),*,) })
+		(quote! { (#(#transfer_types),*,) }, quote! { (#(#arg_idents),*,) })
 	} else {
-		(quote! { ( #(#arg_types),*, ) }, quote! { ( #(#arg_patterns),*, ) })
+		(quote! { ( #(#transfer_types),*, ) }, quote! { ( #(#arg_idents),*, ) })
 	};
 
-	// Return type analysis
-	let (result_type, is_result) = match &fn_sig.output {
-		ReturnType::Default => (quote! { () }, false),
+	// Return type analysis. A fallible function describes its success kind, so both
+	// `Result<Option<T>, E>` and `Option<Result<T, E>>` are described as `option<T>`.
+	let (success_type, is_result, is_optional_result) = match &fn_sig.output {
+		ReturnType::Default => (None, false, false),
 		ReturnType::Type(_, ty) => {
-			// Check if the return type is Result<T, E>
-			if let Type::Path(TypePath {
-				path,
-				..
-			}) = &**ty
+			if let Some(inner_type) = generic_inner(ty, "Result") {
+				(Some(inner_type), true, false)
+			} else if let Some(inner_type) =
+				generic_inner(ty, "Option").and_then(|option| generic_inner(option, "Result"))
 			{
-				if let Some(last_segment) = path.segments.last() {
-					if last_segment.ident == "Result" {
-						if let PathArguments::AngleBracketed(args) = &last_segment.arguments {
-							if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
-								(quote! { #inner_type }, true)
-							} else {
-								(quote! { #ty }, false)
-							}
-						} else {
-							(quote! { #ty }, false)
-						}
-					} else {
-						(quote! { #ty }, false)
-					}
-				} else {
-					(quote! { #ty }, false)
-				}
+				(Some(inner_type), true, true)
 			} else {
-				(quote! { #ty }, false)
+				(Some(&**ty), false, false)
 			}
 		}
 	};
+	// Returned values are converted into their wrapper, as with arguments. The iterator of a
+	// stream function is kept in the module, and only its handle is returned. The result of a
+	// serde function is serialized into a value by the generated code, so that a failure to
+	// serialize it fails the call.
+	let serde_return = is_serde && success_type.is_some();
+	let return_wrapper = if is_stream {
+		if is_serde {
+			return Err(Error::new_spanned(
+				&args,
+				"#[surrealism(stream)] functions cannot be marked #[surrealism(serde)]",
+			));
+		}
+		if is_optional_result {
+			return Err(Error::new_spanned(
+				&fn_sig.output,
+				"#[surrealism(stream)] functions cannot return an `Option<Result<...>>`",
+			));
+		}
+		let Some(item) = success_type.and_then(iterator_item) else {
+			return Err(Error::new_spanned(
+				&fn_sig.output,
+				"#[surrealism(stream)] functions must return `impl Iterator<Item = T>`",
+			));
+		};
+		Some(Wrapper {
+			ty: quote! { surrealism::stream::Stream<#item> },
+			unwrap: |ident, _| quote! { #ident },
+			wrap: quote! { surrealism::stream::Stream::open },
+		})
+	} else if serde_return {
+		Some(Wrapper {
+			ty: quote! { surrealism::types::Value },
+			unwrap: |ident, _| quote! { #ident },
+			wrap: quote! { surrealism::types::json::serialize },
+		})
+	} else {
+		success_type.and_then(wrapper_of)
+	};
+	let result_type = match (success_type, &return_wrapper) {
+		(None, _) => quote! { () },
+		(Some(_), Some(wrapper)) => wrapper.ty.clone(),
+		(Some(ty), None) => quote! { #ty },
+	};
+	let result_type = if is_optional_result {
+		quote! { Option<#result_type> }
+	} else {
+		result_type
+	};
 
 	// Export function names
 	let export_suffix = if is_default {
@@ -120,10 +627,42 @@ pub fn surrealism(attr: TokenStream, item: TokenStream) -> TokenStream {
 	} else {
 		export_name_override.unwrap_or_else(|| fn_name.to_string())
 	};
+	if !is_init {
+		validate_reserved_name(&export_suffix, fn_name.span())?;
+	}
 
-	let export_ident = format_ident!("__sr_fnc__{}", export_suffix);
-	let args_ident = format_ident!("__sr_args__{}", export_suffix);
-	let returns_ident = format_ident!("__sr_returns__{}", export_suffix);
+	// Every alias is exported as an additional set of functions sharing the same body
+	let init_error = if !is_init {
+		None
+	} else if !aliases.is_empty() {
+		Some("#[surrealism(init)] functions cannot have aliases")
+	} else if deprecated.is_some() {
+		Some("#[surrealism(init)] functions cannot be deprecated")
+	} else if namespace.is_some() {
+		Some("#[surrealism(init)] functions cannot be namespaced")
+	} else if is_pure {
+		Some("#[surrealism(init)] functions cannot be pure")
+	} else if is_stream {
+		Some("#[surrealism(init)] functions cannot be streams")
+	} else {
+		None
+	};
+	if let Some(message) = init_error {
+		return Err(Error::new_spanned(&args, message));
+	}
+	let mut export_suffixes = vec![export_suffix];
+	for alias in aliases {
+		let value = alias.value();
+		if export_suffixes.contains(&value) {
+			return Err(Error::new_spanned(
+				alias,
+				format!(
+					"#[surrealism] alias \"{value}\" collides with another name of this function"
+				),
+			));
+		}
+		export_suffixes.push(value);
+	}
 
 	// DRY error handling pattern
 	let try_or_fail = |expr: proc_macro2::TokenStream, context: &str| {
@@ -167,14 +706,38 @@ pub fn surrealism(attr: TokenStream, item: TokenStream) -> TokenStream {
 			}
 		}
 	} else {
-		let function_call = if is_result {
-			quote! {
-				#fn_name(#(#arg_patterns),*).map_err(|e| e.to_string())
-			}
-		} else {
-			quote! {
-				Ok(#fn_name(#(#arg_patterns),*))
-			}
+		let call = quote! { #fn_name(#(#call_args),*) };
+		let serialize = quote! {
+			|value| surrealism::types::json::serialize(&value).map_err(|e| e.to_string())
+		};
+		let function_call = match (is_optional_result, is_result, return_wrapper.map(|w| w.wrap)) {
+			(true, _, Some(_)) if serde_return => quote! {
+				#call.transpose().map_err(|e| e.to_string()).and_then(|x| x.map(#serialize).transpose())
+			},
+			(false, true, Some(_)) if serde_return => quote! {
+				#call.map_err(|e| e.to_string()).and_then(#serialize)
+			},
+			(false, false, Some(_)) if serde_return => quote! {
+				surrealism::types::json::serialize(&#call).map_err(|e| e.to_string())
+			},
+			(true, _, None) => quote! {
+				#call.transpose().map_err(|e| e.to_string())
+			},
+			(true, _, Some(wrap)) => quote! {
+				#call.transpose().map(|x| x.map(#wrap)).map_err(|e| e.to_string())
+			},
+			(false, true, None) => quote! {
+				#call.map_err(|e| e.to_string())
+			},
+			(false, true, Some(wrap)) => quote! {
+				#call.map(#wrap).map_err(|e| e.to_string())
+			},
+			(false, false, None) => quote! {
+				Ok(#call)
+			},
+			(false, false, Some(wrap)) => quote! {
+				Ok(#wrap(#call))
+			},
 		};
 
 		let transfer_call = if is_result {
@@ -264,40 +827,204 @@ pub fn surrealism(attr: TokenStream, item: TokenStream) -> TokenStream {
 			}
 		};
 
-		quote! {
-			#fn_vis #fn_sig #fn_block
+		// Namespaced functions are exported as `__sr_fnc__<namespace>::<name>`. As "::" is not
+		// valid in an identifier, the symbol is set with `export_name`, and the Rust item joins
+		// the namespace and name with "__" instead.
+		let export = |kind: &str, export_suffix: &str| match &namespace {
+			Some(namespace) => (
+				format!("__sr_{kind}__{namespace}::{export_suffix}"),
+				format_ident!("__sr_{}__{}__{}", kind, namespace, export_suffix),
+			),
+			None => (
+				format!("__sr_{kind}__{export_suffix}"),
+				format_ident!("__sr_{}__{}", kind, export_suffix),
+			),
+		};
 
-			#[unsafe(no_mangle)]
-			pub extern "C" fn #export_ident(ptr: u32) -> i32 {
-				use surrealism::types::transfer::Transfer;
-				let mut controller = surrealism::Controller {};
-				let f = surrealism::SurrealismFunction::<#tuple_type, #result_type, _>::from(
-					|#tuple_pattern: #tuple_type| #function_call
-				);
-				#transfer_call
-			}
+		// Every exported name is listed in the manifest returned by `__sr_manifest`
+		let manifest_namespace = match &namespace {
+			Some(namespace) => quote! { Some(#namespace) },
+			None => quote! { None },
+		};
+		let manifest_doc = if doc.is_empty() {
+			quote! { None }
+		} else {
+			quote! { Some(#doc) }
+		};
+		let manifest_deprecated = match &deprecated {
+			Some(note) => quote! { Some(#note) },
+			None => quote! { None },
+		};
 
-			#[unsafe(no_mangle)]
-			pub extern "C" fn #args_ident() -> i32 {
-				use surrealism::types::transfer::Transfer;
-				let mut controller = surrealism::Controller {};
-				let f = surrealism::SurrealismFunction::<#tuple_type, #result_type, _>::from(
-					|#tuple_pattern: #tuple_type| #function_call
-				);
-				#args_call
+		let exports = export_suffixes.iter().map(|export_suffix| {
+			let (export_name, export_ident) = export("fnc", export_suffix);
+			let (args_name, args_ident) = export("args", export_suffix);
+			let (returns_name, returns_ident) = export("returns", export_suffix);
+			let (argnames_name, argnames_ident) = export("argnames", export_suffix);
+
+			// Deprecated functions additionally export their deprecation note
+			let deprecated_export = deprecated.as_ref().map(|note| {
+				let (deprecated_name, deprecated_ident) = export("deprecated", export_suffix);
+				quote! {
+					#[unsafe(export_name = #deprecated_name)]
+					pub extern "C" fn #deprecated_ident() -> i32 {
+						use surrealism::types::transfer::Transfer;
+						let mut controller = surrealism::Controller {};
+						match #note.to_string().transfer(&mut controller) {
+							Ok(result) => match (*result).try_into() {
+								Ok(ptr) => ptr,
+								Err(_) => {
+									eprintln!("Transfer error: pointer overflow");
+									-1
+								}
+							},
+							Err(e) => {
+								eprintln!("Deprecated error: {}", e);
+								-1
+							}
+						}
+					}
+				}
+			});
+
+			// Pure functions additionally export a flag, which the host only checks for
+			let pure_export = is_pure.then(|| {
+				let (pure_name, pure_ident) = export("pure", export_suffix);
+				quote! {
+					#[unsafe(export_name = #pure_name)]
+					pub extern "C" fn #pure_ident() -> i32 {
+						1
+					}
+				}
+			});
+
+			// Stream functions additionally export a flag, so the host pulls their values
+			let stream_export = is_stream.then(|| {
+				let (stream_name, stream_ident) = export("stream", export_suffix);
+				quote! {
+					#[unsafe(export_name = #stream_name)]
+					pub extern "C" fn #stream_ident() -> i32 {
+						1
+					}
+				}
+			});
+
+			// Documented functions additionally export their doc comment
+			let doc_export = (!doc.is_empty()).then(|| {
+				let (doc_name, doc_ident) = export("doc", export_suffix);
+				quote! {
+					#[unsafe(export_name = #doc_name)]
+					pub extern "C" fn #doc_ident() -> i32 {
+						use surrealism::types::transfer::Transfer;
+						let mut controller = surrealism::Controller {};
+						match #doc.to_string().transfer(&mut controller) {
+							Ok(result) => match (*result).try_into() {
+								Ok(ptr) => ptr,
+								Err(_) => {
+									eprintln!("Transfer error: pointer overflow");
+									-1
+								}
+							},
+							Err(e) => {
+								eprintln!("Doc error: {}", e);
+								-1
+							}
+						}
+					}
+				}
+			});
+
+			quote! {
+
+				#[unsafe(export_name = #export_name)]
+				pub extern "C" fn #export_ident(ptr: u32) -> i32 {
+					use surrealism::types::transfer::Transfer;
+					let mut controller = surrealism::Controller {};
+					let f = surrealism::SurrealismFunction::<#tuple_type, #result_type, _>::from(
+						|#tuple_pattern: #tuple_type| #function_call
+					);
+					#transfer_call
+				}
+
+				#[unsafe(export_name = #args_name)]
+				pub extern "C" fn #args_ident() -> i32 {
+					use surrealism::types::transfer::Transfer;
+					let mut controller = surrealism::Controller {};
+					let f = surrealism::SurrealismFunction::<#tuple_type, #result_type, _>::from(
+						|#tuple_pattern: #tuple_type| #function_call
+					);
+					#args_call
+				}
+
+				#[unsafe(export_name = #returns_name)]
+				pub extern "C" fn #returns_ident() -> i32 {
+					use surrealism::types::transfer::Transfer;
+					let mut controller = surrealism::Controller {};
+					let f = surrealism::SurrealismFunction::<#tuple_type, #result_type, _>::from(
+						|#tuple_pattern: #tuple_type| #function_call
+					);
+					#returns_call
+				}
+
+				#[unsafe(export_name = #argnames_name)]
+				pub extern "C" fn #argnames_ident() -> i32 {
+					use surrealism::types::transfer::Transfer;
+					let mut controller = surrealism::Controller {};
+					let names: Vec<String> = vec![#(#arg_names.to_string()),*];
+					match names.transfer(&mut controller) {
+						Ok(result) => match (*result).try_into() {
+							Ok(ptr) => ptr,
+							Err(_) => {
+								eprintln!("Transfer error: pointer overflow");
+								-1
+							}
+						},
+						Err(e) => {
+							eprintln!("Argument names error: {}", e);
+							-1
+						}
+					}
+				}
+
+				#deprecated_export
+
+				#pure_export
+
+				#stream_export
+
+				#doc_export
+
+				surrealism::inventory::submit! {
+					surrealism::manifest::ManifestEntry::new::<#tuple_type, #result_type>(
+						#manifest_namespace,
+						#export_suffix,
+						&[#(#arg_names),*],
+						#manifest_doc,
+						#manifest_deprecated,
+					)
+				}
 			}
+		});
 
-			#[unsafe(no_mangle)]
-			pub extern "C" fn #returns_ident() -> i32 {
-				use surrealism::types::transfer::Transfer;
-				let mut controller = surrealism::Controller {};
-				let f = surrealism::SurrealismFunction::<#tuple_type, #result_type, _>::from(
-					|#tuple_pattern: #tuple_type| #function_call
-				);
-				#returns_call
+		// The crate version and `SOURCE_DATE_EPOCH` are read where the macro expands, which is
+		// within the module's crate. Reading them through `env!` and `option_env!` lets cargo
+		// rebuild the crate when they change.
+		let compiled_at = compile_time();
+
+		quote! {
+			#fn_vis #fn_sig #fn_block
+
+			#(#exports)*
+
+			surrealism::inventory::submit! {
+				surrealism::build_info::BuildInfoEntry {
+					crate_version: env!("CARGO_PKG_VERSION"),
+					source_date_epoch: option_env!("SOURCE_DATE_EPOCH"),
+					compiled_at: #compiled_at,
+				}
 			}
 		}
 	};
 
-	TokenStream::from(expanded)
+	Ok(expanded)
 }
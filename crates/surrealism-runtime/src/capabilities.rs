@@ -1,13 +1,82 @@
+use std::path::{Component, Path};
+
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use crate::host::InvocationContext;
+use crate::statement::StatementKind;
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct SurrealismCapabilities {
 	#[serde(default)]
 	pub allow_scripting: bool,
 	#[serde(default)]
 	pub allow_arbitrary_queries: bool,
+	/// The statements the module may run when it is not allowed arbitrary queries, or `None`
+	/// for [`StatementKind::SAFE`].
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub allow_statements: Option<Vec<StatementKind>>,
 	#[serde(default)]
 	pub allow_functions: Vec<String>,
 	#[serde(default)]
 	pub allow_net: Vec<String>,
+	/// The files the module may read through `fs::read`, and the directories whose files it
+	/// may read. Relative paths are resolved against the working directory of the host, and
+	/// may not be empty or `.`, which would allow the whole working directory.
+	#[serde(default)]
+	pub allow_fs: Vec<String>,
+}
+
+impl SurrealismCapabilities {
+	/// The statements the module may run, or `None` if it may run any query.
+	pub fn allowed_statements(&self) -> Option<&[StatementKind]> {
+		if self.allow_arbitrary_queries {
+			return None;
+		}
+		Some(self.allow_statements.as_deref().unwrap_or(StatementKind::SAFE))
+	}
+
+	/// Check that every statement in a query, including nested statements, is allowed. The
+	/// statements are classified by the host, see [`InvocationContext::statements`].
+	pub fn check_query(&self, query: &str, context: &mut dyn InvocationContext) -> Result<()> {
+		let Some(allowed) = self.allowed_statements() else {
+			return Ok(());
+		};
+		let statements =
+			context.statements(query).map_err(|e| anyhow::anyhow!("Capability error: {e}"))?;
+		if let Some(kind) = statements.into_iter().find(|x| !allowed.contains(x)) {
+			anyhow::bail!("Capability error: the module is not allowed to run {kind} statements");
+		}
+		Ok(())
+	}
+
+	/// Check that the module may read the file at `path`, which must be one of the paths in
+	/// `allow_fs` or inside one of them, returning the canonical path of the file, which is
+	/// the one to read.
+	///
+	/// Both the path and the entries of `allow_fs` are canonicalized before they are compared
+	/// by component, so `..` components and symbolic links are resolved, and cannot step out
+	/// of an allowed directory. Files which do not exist, and entries which do not exist, are
+	/// never allowed. Entries which are empty or `.` fail every check.
+	pub fn check_fs_read(&self, path: &str) -> Result<String> {
+		if let Some(entry) = self.allow_fs.iter().find(|entry| {
+			Path::new(entry.trim()).components().all(|component| component == Component::CurDir)
+		}) {
+			anyhow::bail!(
+				"Capability error: the allow_fs entry '{entry}' would allow the whole working directory"
+			);
+		}
+		let denied =
+			|| anyhow::anyhow!("Capability error: the module is not allowed to read '{path}'");
+		let file = std::fs::canonicalize(path).map_err(|_| denied())?;
+		let allowed = self
+			.allow_fs
+			.iter()
+			.filter_map(|entry| std::fs::canonicalize(entry).ok())
+			.any(|entry| file.starts_with(entry));
+		if !allowed {
+			return Err(denied());
+		}
+		file.into_os_string().into_string().map_err(|_| denied())
+	}
 }
@@ -0,0 +1,120 @@
+//! Coercion of argument and return values to the kinds a function declares.
+//!
+//! Hosts pass arguments as generic [`Value`]s, which do not always match the kind a guest
+//! function declares, for example an `int` passed where a `float` is expected. [`coerce`]
+//! converts a value to a declared kind where this can be done without losing information,
+//! following SurrealDB's coercion (rather than casting) rules:
+//!
+//! - Any value is accepted for `any`, and values already of the declared kind are unchanged.
+//! - Numbers convert between `int`, `float` and `decimal` when the value is represented
+//!   exactly, so `1` becomes `1f` but `1.5f` is not truncated into an `int`.
+//! - Arrays and sets have each element coerced to the element kind, and their length
+//!   checked against the declared maximum. An array is accepted where a set is declared.
+//! - GeoJSON objects, such as `{ type: 'Point', coordinates: [1, 2] }`, convert to a declared
+//!   `geometry` when their type matches one of its subtypes.
+//! - For a union such as `int | string`, the first kind the value coerces to is used.
+//!
+//! Anything else is rejected, including strings where a number is declared: `'1'` is not
+//! implicitly parsed as `1`.
+//!
+//! The same rules apply to return values checked by `Controller::invoke_checked`, which
+//! catches modules whose results do not match their declared return kind.
+
+use anyhow::Result;
+use surrealdb_types::{Array, Decimal, Geometry, Kind, Number, Set, Value};
+
+/// Coerce a value to the given kind, or return an error describing the mismatch.
+pub fn coerce(value: Value, kind: &Kind) -> Result<Value> {
+	if value.is_kind(kind) {
+		return Ok(value);
+	}
+
+	let mismatch = |value: &Value| anyhow::anyhow!("Expected {kind}, found {}", value.kind());
+
+	match (value, kind) {
+		(Value::Number(number), Kind::Int | Kind::Float | Kind::Decimal) => {
+			match coerce_number(&number, kind) {
+				Some(number) => Ok(Value::Number(number)),
+				None => Err(mismatch(&Value::Number(number))),
+			}
+		}
+		(value, Kind::Either(kinds)) => {
+			for kind in kinds {
+				if let Ok(value) = coerce(value.clone(), kind) {
+					return Ok(value);
+				}
+			}
+			Err(mismatch(&value))
+		}
+		(Value::Array(array), Kind::Array(inner, max)) => {
+			let values = coerce_elements(array.into_inner(), inner, *max)?;
+			Ok(Value::Array(Array::from(values)))
+		}
+		(Value::Array(array), Kind::Set(inner, max)) => {
+			let values = coerce_elements(array.into_inner(), inner, *max)?;
+			Ok(Value::Set(Set::from(values)))
+		}
+		(Value::Set(set), Kind::Set(inner, max)) => {
+			let values = coerce_elements(set.into_iter().collect(), inner, *max)?;
+			Ok(Value::Set(Set::from(values)))
+		}
+		(Value::Object(object), Kind::Geometry(_)) => {
+			let mut geojson = object.clone();
+			if let Some(coordinates) = geojson.remove("coordinates") {
+				geojson.insert("coordinates", float_coordinates(coordinates));
+			}
+			match Geometry::try_from_object(&geojson).map(Value::Geometry) {
+				Some(value) if value.is_kind(kind) => Ok(value),
+				_ => Err(mismatch(&Value::Object(object))),
+			}
+		}
+		(value, _) => Err(mismatch(&value)),
+	}
+}
+
+/// GeoJSON coordinates with integers converted to floats, as geometries only accept floats.
+fn float_coordinates(value: Value) -> Value {
+	match value {
+		Value::Number(Number::Int(v)) => Value::Number(Number::Float(v as f64)),
+		Value::Array(array) => Value::Array(
+			array.into_inner().into_iter().map(float_coordinates).collect::<Vec<_>>().into(),
+		),
+		value => value,
+	}
+}
+
+fn coerce_elements(values: Vec<Value>, kind: &Kind, max: Option<u64>) -> Result<Vec<Value>> {
+	if let Some(max) = max
+		&& values.len() as u64 > max
+	{
+		anyhow::bail!("Expected at most {max} elements, found {}", values.len());
+	}
+
+	values.into_iter().map(|value| coerce(value, kind)).collect()
+}
+
+fn coerce_number(number: &Number, kind: &Kind) -> Option<Number> {
+	match (*number, kind) {
+		(Number::Int(v), Kind::Float) => {
+			// Compared as i128, as `f as i64` saturates, so i64::MAX would round trip through
+			// the float 2^63 which is one more than it
+			let f = v as f64;
+			(f as i128 == v as i128).then_some(Number::Float(f))
+		}
+		(Number::Int(v), Kind::Decimal) => Some(Number::Decimal(Decimal::from(v))),
+		(Number::Float(v), Kind::Int) => {
+			(v.fract() == 0.0 && v >= i64::MIN as f64 && v < i64::MAX as f64)
+				.then_some(Number::Int(v as i64))
+		}
+		(Number::Float(v), Kind::Decimal) => {
+			let d = Decimal::try_from(v).ok()?;
+			(f64::try_from(d).ok()? == v).then_some(Number::Decimal(d))
+		}
+		(Number::Decimal(v), Kind::Int) if v.is_integer() => i64::try_from(v).ok().map(Number::Int),
+		(Number::Decimal(v), Kind::Float) => {
+			let f = f64::try_from(v).ok()?;
+			(Decimal::try_from(f).ok()? == v).then_some(Number::Float(f))
+		}
+		_ => None,
+	}
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use semver::Version;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use surrealism_types::err::PrefixError;
 
 use crate::capabilities::SurrealismCapabilities;
@@ -9,8 +10,21 @@ use crate::capabilities::SurrealismCapabilities;
 pub struct SurrealismConfig {
 	#[serde(rename = "package")]
 	pub meta: SurrealismMeta,
-	#[serde(default)]
-	pub capabilities: SurrealismCapabilities,
+	/// The capabilities requested by the package, or `None` if it declares none.
+	///
+	/// Hosts treat a package without a `[capabilities]` table as having the
+	/// [default](SurrealismCapabilities::default) capabilities: it may only run
+	/// [`StatementKind::SAFE`](crate::statement::StatementKind::SAFE) statements, and may not
+	/// call functions, reach the network or read files. Use
+	/// `capabilities.clone().unwrap_or_default()` to apply the same rule.
+	///
+	/// This field was a plain [`SurrealismCapabilities`] before packages could omit the table,
+	/// so code which read it directly needs to handle `None`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub capabilities: Option<SurrealismCapabilities>,
+	/// Checksums of the packaged module, or `None` for packages built without them.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub integrity: Option<SurrealismIntegrity>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -20,6 +34,33 @@ pub struct SurrealismMeta {
 	pub version: Version,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SurrealismIntegrity {
+	/// Hex encoded SHA-256 digest of `mod.wasm`.
+	pub sha256: String,
+}
+
+impl SurrealismIntegrity {
+	pub fn new(wasm: &[u8]) -> Self {
+		Self {
+			sha256: hex::encode(Sha256::digest(wasm)),
+		}
+	}
+
+	/// Checks that `wasm` matches the recorded digest.
+	pub fn verify(&self, wasm: &[u8]) -> Result<()> {
+		let actual = Self::new(wasm);
+		if !actual.sha256.eq_ignore_ascii_case(&self.sha256) {
+			anyhow::bail!(
+				"Package integrity check failed: mod.wasm has SHA-256 {}, but the manifest declares {}",
+				actual.sha256,
+				self.sha256
+			);
+		}
+		Ok(())
+	}
+}
+
 impl SurrealismConfig {
 	pub fn parse(s: &str) -> Result<Self> {
 		toml::from_str(s).prefix_err(|| "Failed to parse Surrealism config")
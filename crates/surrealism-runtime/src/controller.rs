@@ -8,6 +8,13 @@
 //! - **`Controller`**: Per-execution instance. Single-threaded, created from Runtime. Cheap to
 //!   create, can be done per-request or pooled.
 //!
+//! # Guest State Isolation
+//!
+//! Guest globals, such as a `static` counter or a lazily initialised cache, live in the
+//! instance's linear memory and persist across `invoke` calls on the same `Controller`. When
+//! invocations belong to different tenants or requests, either create a new `Controller` for
+//! each, or call `Controller::reset` between them to start over with fresh memory.
+//!
 //! # Concurrency Patterns
 //!
 //! ```no_run
@@ -26,27 +33,83 @@
 //! });
 //! # Ok::<(), anyhow::Error>(())
 //! ```
+//!
+//! # Precompilation
+//!
+//! Compiling a module is the slowest part of loading it. `Runtime::precompile_to` writes the
+//! compiled module to disk, and `Runtime::from_precompiled` loads it in later processes without
+//! compiling it again, as long as they use the same wasmtime version and compatible engine
+//! settings. The digest of the WASM module is written next to the compiled one, and checked
+//! against the package's `integrity` when loading it.
+//!
+//! # Namespaces
+//!
+//! Functions declared with `#[surrealism(namespace = "...")]` are exported within that
+//! namespace, so modules composed into a single WASM binary can reuse function names. A
+//! controller starts in the root namespace, holding the functions declared without one. Call
+//! `Controller::set_namespace` to select another, after which `list`, `args`, `returns`,
+//! `deprecated` and `invoke` only see the functions within it. `Controller::namespaces` lists
+//! the namespaces a module declares.
+//!
+//! ```rust,ignore
+//! controller.set_namespace(Some("geo".to_string()));
+//! let distance = controller.invoke(Some("distance".into()), (a, b)).await?;
+//! ```
+//!
+//! # Shared State
+//!
+//! Each `Controller` owns its `InvocationContext`, which is borrowed mutably for the duration
+//! of a host call, so a context is never shared between concurrent invocations. State that
+//! must outlive or span invocations, such as a KV store, is shared by giving each context an
+//! `Arc` to it instead. `KVStore` takes `&self` and is implemented for `Arc<T>`, so a single
+//! `Arc<BTreeMapStore>` can back any number of controllers running on different threads.
 
+use std::collections::BTreeMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use surrealism_types::ABI_VERSION;
+use surrealism_types::arg::SerializableArg;
 use surrealism_types::args::Args;
-use surrealism_types::err::PrefixError;
+use surrealism_types::build_info::BuildInfo;
+use surrealism_types::err::{MemoryError, PrefixError};
 use surrealism_types::transfer::AsyncTransfer;
 use wasmtime::*;
 use wasmtime_wasi::preview1::{self, WasiP1Ctx};
 
-use crate::config::SurrealismConfig;
+use crate::coerce::coerce;
+use crate::config::{SurrealismConfig, SurrealismIntegrity};
+use crate::engine::EngineConfig;
 use crate::host::{InvocationContext, implement_host_functions};
+use crate::json::{from_json, to_json};
+use crate::kv::KVStore;
 use crate::package::SurrealismPackage;
+use crate::trap::describe as describe_trap;
+use crate::wasi_context::WasiConfig;
 
 /// Store data for WASM execution. Each Controller has its own isolated StoreData.
 pub struct StoreData {
 	pub wasi: WasiP1Ctx,
 	pub config: Arc<SurrealismConfig>,
 	pub(crate) context: Box<dyn InvocationContext>,
+	/// The function currently being invoked, used to attribute log records.
+	pub(crate) function: Option<String>,
+	/// The largest payload accepted from the guest, see [`Controller::set_max_transfer_bytes`].
+	pub(crate) max_transfer_bytes: Option<u32>,
+	/// The most elements accepted in an array from the guest, see [`Controller::set_max_array_len`].
+	pub(crate) max_array_len: Option<u32>,
+	/// Request-scoped values readable by the guest, see [`Controller::set_context`].
+	pub(crate) vars: BTreeMap<String, surrealdb_types::Value>,
+	/// When calls should finish by, see [`Controller::set_deadline`].
+	pub(crate) deadline: Option<Instant>,
+	/// The prefix of every KV key the guest uses, see [`Controller::set_kv_namespacing`].
+	pub(crate) kv_prefix: Option<String>,
+	/// The limits on the guest's memory, see [`Controller::set_max_memory`].
+	pub(crate) limits: StoreLimits,
 }
 
 impl fmt::Debug for StoreData {
@@ -56,113 +119,720 @@ impl fmt::Debug for StoreData {
 	}
 }
 
+/// The context a store holds while its own is lent to another store, as during
+/// [`Controller::reset`]. No guest code runs in such a store, so every call fails.
+struct DetachedContext;
+
+#[async_trait]
+impl InvocationContext for DetachedContext {
+	async fn sql(
+		&mut self,
+		_config: &SurrealismConfig,
+		_query: String,
+		_vars: surrealdb_types::Object,
+	) -> Result<surrealdb_types::Value> {
+		anyhow::bail!("The invocation context is detached")
+	}
+
+	async fn run(
+		&mut self,
+		_config: &SurrealismConfig,
+		_fnc: String,
+		_version: Option<String>,
+		_args: Vec<surrealdb_types::Value>,
+	) -> Result<surrealdb_types::Value> {
+		anyhow::bail!("The invocation context is detached")
+	}
+
+	fn kv(&mut self) -> Result<&dyn KVStore> {
+		anyhow::bail!("The invocation context is detached")
+	}
+}
+
 /// Compiled WASM runtime. Thread-safe, can be shared across threads via Arc.
 /// Compiles WASM once, then each controller gets its own isolated Store/Instance.
-/// The Engine, Module, and Linker are immutable and safely shared.
-#[derive(Debug)]
+/// The Engine and pre-linked module are immutable and safely shared.
 pub struct Runtime {
 	engine: Engine,
-	module: Module,
-	linker: Linker<StoreData>,
+	pre: InstancePre<StoreData>,
 	config: Arc<SurrealismConfig>,
+	wasi: WasiConfig,
+	/// The digest of the WASM module the runtime was compiled from.
+	integrity: SurrealismIntegrity,
+}
+
+impl fmt::Debug for Runtime {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Runtime {{ engine: ?, pre: ?, config: {:?}, wasi: {:?} }}",
+			self.config, self.wasi
+		)
+	}
 }
 
 impl Runtime {
 	/// Compile the WASM module and prepare the runtime.
 	/// This is expensive - do it once and share via Arc<Runtime>.
 	/// The compiled artifacts (Engine, Module, Linker) are immutable and thread-safe.
-	pub fn new(
+	pub fn new(package: SurrealismPackage) -> Result<Self> {
+		Self::new_with_config(package, &EngineConfig::default())
+	}
+
+	/// Compile the WASM module with the given engine settings, such as a larger stack for
+	/// deeply recursive modules. See [`EngineConfig`] for the defaults used by [`Runtime::new`].
+	pub fn new_with_config(
 		SurrealismPackage {
 			wasm,
 			config,
 		}: SurrealismPackage,
+		settings: &EngineConfig,
 	) -> Result<Self> {
-		// Configure engine for fast compilation in debug, optimized runtime in release
-		let mut engine_config = Config::new();
-		// Enable async support for async host functions
-		engine_config.async_support(true);
-		#[cfg(debug_assertions)]
-		{
-			// Use Winch baseline compiler for extremely fast compilation in debug builds
-			// Falls back to Cranelift if Winch doesn't support the WASM features used
-			engine_config.strategy(Strategy::Winch);
+		let engine = build_engine(settings)?;
+		let integrity = SurrealismIntegrity::new(&wasm);
+		let module =
+			Module::new(&engine, wasm).prefix_err(|| "Failed to construct module from bytes")?;
+		Self::link(engine, &module, config, integrity)
+	}
+
+	/// Load a module compiled by [`Runtime::precompile_to`], skipping compilation, with the
+	/// default engine settings.
+	///
+	/// # Safety
+	///
+	/// The file is loaded as native code, so it must have been written by
+	/// [`Runtime::precompile_to`] and kept where it cannot be tampered with. See
+	/// [`Module::deserialize_file`].
+	pub unsafe fn from_precompiled(
+		path: impl AsRef<Path>,
+		config: SurrealismConfig,
+	) -> Result<Self> {
+		// SAFETY: upheld by the caller
+		unsafe { Self::from_precompiled_with_config(path, config, &EngineConfig::default()) }
+	}
+
+	/// Load a module compiled by [`Runtime::precompile_to`] with the given engine settings,
+	/// which must match those it was compiled with.
+	///
+	/// Files compiled by another version of wasmtime, with incompatible engine settings, or
+	/// for another platform are rejected rather than loaded. So are files compiled from
+	/// another module than the package's: the digest recorded next to the file must match
+	/// the package's `integrity`, which is required.
+	///
+	/// # Safety
+	///
+	/// The file is loaded as native code, so it must have been written by
+	/// [`Runtime::precompile_to`] and kept where it cannot be tampered with. See
+	/// [`Module::deserialize_file`].
+	pub unsafe fn from_precompiled_with_config(
+		path: impl AsRef<Path>,
+		config: SurrealismConfig,
+		settings: &EngineConfig,
+	) -> Result<Self> {
+		let path = path.as_ref();
+		let Some(expected) = config.integrity.clone() else {
+			anyhow::bail!(
+				"Cannot check that {} was compiled from this package, which has no integrity section",
+				path.display()
+			);
+		};
+		let digest_path = precompiled_digest_path(path);
+		let integrity = SurrealismIntegrity {
+			sha256: std::fs::read_to_string(&digest_path)
+				.prefix_err(|| format!("Failed to read module digest {}", digest_path.display()))?
+				.trim()
+				.to_string(),
+		};
+		if !integrity.sha256.eq_ignore_ascii_case(&expected.sha256) {
+			anyhow::bail!(
+				"{} was compiled from a module with SHA-256 {}, but the package declares {}",
+				path.display(),
+				integrity.sha256,
+				expected.sha256
+			);
 		}
-		#[cfg(not(debug_assertions))]
+		let engine = build_engine(settings)?;
+		if Engine::detect_precompiled_file(path)
+			.prefix_err(|| format!("Failed to read precompiled module {}", path.display()))?
+			!= Some(Precompiled::Module)
 		{
-			// Optimize for runtime performance in release builds
-			engine_config.cranelift_opt_level(OptLevel::Speed);
+			anyhow::bail!("{} is not a precompiled WASM module", path.display());
 		}
-		let engine = Engine::new(&engine_config)?;
-		let module =
-			Module::new(&engine, wasm).prefix_err(|| "Failed to construct module from bytes")?;
+		// SAFETY: upheld by the caller
+		let module = unsafe { Module::deserialize_file(&engine, path) }.map_err(|e| {
+			anyhow::anyhow!(
+				"Failed to load precompiled module {}, which must be compiled by the same version of wasmtime with compatible engine settings: {e:#}",
+				path.display()
+			)
+		})?;
+		Self::link(engine, &module, config, integrity)
+	}
+
+	/// Write the compiled module to `path`, so that later processes can load it with
+	/// [`Runtime::from_precompiled`] instead of compiling it again. The digest of the WASM
+	/// module it was compiled from is written next to it, to `<path>.sha256`.
+	pub fn precompile_to(&self, path: impl AsRef<Path>) -> Result<()> {
+		let path = path.as_ref();
+		let bytes = self.pre.module().serialize().prefix_err(|| "Failed to serialize module")?;
+		std::fs::write(path, bytes)
+			.prefix_err(|| format!("Failed to write precompiled module {}", path.display()))?;
+		let digest_path = precompiled_digest_path(path);
+		std::fs::write(&digest_path, format!("{}\n", self.integrity.sha256))
+			.prefix_err(|| format!("Failed to write module digest {}", digest_path.display()))
+	}
+
+	/// Link a compiled module against WASI and the host functions.
+	fn link(
+		engine: Engine,
+		module: &Module,
+		config: SurrealismConfig,
+		integrity: SurrealismIntegrity,
+	) -> Result<Self> {
+		validate_exports(module)?;
 
 		let mut linker: Linker<StoreData> = Linker::new(&engine);
 		preview1::add_to_linker_async(&mut linker, |data| &mut data.wasi)
 			.prefix_err(|| "failed to add WASI to linker")?;
 		implement_host_functions(&mut linker)
 			.prefix_err(|| "failed to implement host functions")?;
+		// Resolve imports once, so each instantiation only has to create the instance
+		let pre = linker.instantiate_pre(module).prefix_err(|| "failed to link WASM module")?;
 
 		Ok(Self {
 			engine,
-			module,
-			linker,
+			pre,
 			config: Arc::new(config),
+			wasi: WasiConfig::default(),
+			integrity,
 		})
 	}
 
+	/// Set the WASI sandbox used by controllers created from this runtime.
+	/// Defaults to [`WasiConfig::default`], which exposes no environment and no filesystem.
+	pub fn with_wasi(mut self, wasi: WasiConfig) -> Self {
+		self.wasi = wasi;
+		self
+	}
+
 	/// Create a new Controller with its own isolated Store and Instance.
 	/// This is cheap (relative to compilation) - the expensive compilation is shared.
 	/// Each controller has its own mutable Store, ensuring no shared mutable state.
 	/// Safe for concurrent execution: no mutable state is shared between controllers.
 	pub async fn new_controller(&self, context: Box<dyn InvocationContext>) -> Result<Controller> {
-		let wasi_ctx = super::wasi_context::build()?;
-
 		let store_data = StoreData {
-			wasi: wasi_ctx,
+			wasi: super::wasi_context::build(&self.wasi)?,
 			config: self.config.clone(),
 			context,
+			function: None,
+			max_transfer_bytes: None,
+			max_array_len: None,
+			vars: BTreeMap::new(),
+			deadline: None,
+			kv_prefix: None,
+			limits: StoreLimits::default(),
 		};
-		let mut store = Store::new(&self.engine, store_data);
-		let instance = self
-			.linker
-			.instantiate_async(&mut store, &self.module)
-			.await
-			.prefix_err(|| "failed to instantiate WASM module")?;
-		let memory = instance
-			.get_memory(&mut store, "memory")
-			.prefix_err(|| "WASM module must export 'memory'")?;
+		Controller::instantiate(
+			Store::new(&self.engine, store_data),
+			self.pre.clone(),
+			self.wasi.clone(),
+		)
+		.await
+	}
+}
 
-		Ok(Controller {
-			store,
-			instance,
-			memory,
-		})
+/// Create an engine with the given settings. Modules must be loaded by an engine with the
+/// same settings as the one which compiled them.
+fn build_engine(settings: &EngineConfig) -> Result<Engine> {
+	// Configure engine for fast compilation in debug, optimized runtime in release
+	let mut engine_config = Config::new();
+	// Enable async support for async host functions
+	engine_config.async_support(true);
+	settings.apply(&mut engine_config);
+	#[cfg(debug_assertions)]
+	{
+		// Use Winch baseline compiler for extremely fast compilation in debug builds
+		// Falls back to Cranelift if Winch doesn't support the WASM features used
+		engine_config.strategy(Strategy::Winch);
+	}
+	#[cfg(not(debug_assertions))]
+	{
+		// Optimize for runtime performance in release builds
+		engine_config.cranelift_opt_level(OptLevel::Speed);
+	}
+	Engine::new(&engine_config)
+}
+
+/// Check that a module exports what every transfer relies on, so a module which cannot be
+/// called fails when it is loaded, rather than partway through a call.
+fn validate_exports(module: &Module) -> Result<()> {
+	match module.get_export("memory") {
+		Some(ExternType::Memory(_)) => {}
+		_ => anyhow::bail!("WASM module must export 'memory'"),
+	}
+	match module.get_export("__sr_abi_version") {
+		Some(ExternType::Func(_)) => {}
+		_ => anyhow::bail!(
+			"Incompatible module ABI: the WASM module does not export '__sr_abi_version', so it was not built with the surrealism crate, or was built with a version older than ABI {ABI_VERSION}"
+		),
+	}
+	for name in ["__sr_alloc", "__sr_free"] {
+		match module.get_export(name) {
+			Some(ExternType::Func(_)) => {}
+			_ => anyhow::bail!("WASM module must export the function '{name}'"),
+		}
+	}
+	Ok(())
+}
+
+/// The file the digest of a precompiled module's source is kept in, next to the module.
+fn precompiled_digest_path(path: &Path) -> PathBuf {
+	let mut digest_path = path.as_os_str().to_owned();
+	digest_path.push(".sha256");
+	PathBuf::from(digest_path)
+}
+
+/// Check that an instance was built against the ABI version of this runtime.
+async fn check_abi_version(instance: &Instance, store: &mut Store<StoreData>) -> Result<()> {
+	let version = instance
+		.get_typed_func::<(), u32>(&mut *store, "__sr_abi_version")
+		.prefix_err(|| "Incompatible module ABI: '__sr_abi_version' has the wrong signature")?
+		.call_async(&mut *store, ())
+		.await
+		.prefix_err(|| "Failed to read the module ABI version")?;
+	if version != ABI_VERSION {
+		anyhow::bail!(
+			"Incompatible module ABI: the module was built against ABI {version}, but this runtime supports ABI {ABI_VERSION}"
+		);
+	}
+	Ok(())
+}
+
+/// Live transfer allocations in a guest, as reported by [`Controller::alloc_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+	/// The number of allocations not yet freed.
+	pub allocations: u64,
+	/// The number of bytes held by those allocations.
+	pub bytes: u64,
+}
+
+/// A function exported by a module, as listed by [`Controller::manifest`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionSignature {
+	/// The namespace the function is declared in, if any.
+	pub namespace: Option<String>,
+	/// The name of the function in its namespace, which is empty for a default function.
+	pub name: String,
+	pub args: Vec<surrealdb_types::Kind>,
+	/// The argument names, which are empty for modules built before they were exported.
+	pub arg_names: Vec<String>,
+	pub returns: surrealdb_types::Kind,
+	pub doc: Option<String>,
+	pub deprecated: Option<String>,
+	/// Whether the function is marked pure, see [`Controller::is_pure`].
+	pub pure: bool,
+	/// Whether the function returns a stream, see [`Controller::invoke_stream`].
+	pub stream: bool,
+}
+
+impl FunctionSignature {
+	/// The name the function is called by, such as `namespace::name`, or the namespace alone
+	/// for the default function of a namespace.
+	pub fn path(&self) -> String {
+		signature_path(self.namespace.as_deref(), &self.name)
+	}
+}
+
+/// A function exported by a module, with its signature or the error raised describing it,
+/// as listed by [`Controller::describe`].
+#[derive(Debug)]
+pub struct DescribedFunction {
+	/// The namespace the function is declared in, if any.
+	pub namespace: Option<String>,
+	/// The name of the function in its namespace, which is empty for a default function.
+	pub name: String,
+	pub signature: Result<FunctionSignature>,
+}
+
+impl DescribedFunction {
+	/// The name the function is called by, as in [`FunctionSignature::path`].
+	pub fn path(&self) -> String {
+		signature_path(self.namespace.as_deref(), &self.name)
+	}
+}
+
+fn signature_path(namespace: Option<&str>, name: &str) -> String {
+	match namespace {
+		Some(namespace) if name.is_empty() => namespace.to_string(),
+		Some(namespace) => format!("{namespace}::{name}"),
+		None => name.to_string(),
+	}
+}
+
+/// The symbol a module exports for a function, such as `__sr_fnc__math::double`.
+fn export_symbol(kind: &str, namespace: Option<&str>, name: &str) -> String {
+	match namespace {
+		Some(namespace) => format!("__sr_{kind}__{namespace}::{name}"),
+		None => format!("__sr_{kind}__{name}"),
+	}
+}
+
+/// A function as transferred by `__sr_manifest`: its namespace, name, argument kinds,
+/// argument names, return kind, doc comment and deprecation note.
+type ManifestEntry = (
+	Option<String>,
+	String,
+	Vec<surrealdb_types::Kind>,
+	Vec<String>,
+	surrealdb_types::Kind,
+	Option<String>,
+	Option<String>,
+);
+
+/// Why a call to a module function failed, as returned by [`Controller::try_invoke`].
+#[derive(Debug)]
+pub enum InvokeError {
+	/// The module trapped, such as on a panic, running out of memory, or an interrupted call.
+	/// The error names the function which trapped and the likely cause, and can be downcast
+	/// to a [`Trap`] for its code, or to a [`WasmBacktrace`] for where it trapped.
+	Trap(anyhow::Error),
+	/// The call failed in the host, such as on an argument which does not match the declared
+	/// kind, a function which does not exist, or a failed transfer.
+	HostError(anyhow::Error),
+	/// The module ran out of memory while receiving the arguments, such as on reaching the
+	/// limit set with [`Controller::set_max_memory`].
+	OutOfMemory(MemoryError),
+	/// The function ran, and returned an error of its own.
+	ModuleError(String),
+}
+
+impl fmt::Display for InvokeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			InvokeError::Trap(e) => write!(f, "{e}"),
+			InvokeError::HostError(e) => write!(f, "{e:#}"),
+			InvokeError::OutOfMemory(e) => write!(f, "{e}"),
+			InvokeError::ModuleError(e) => write!(f, "WASM function returned error: {e}"),
+		}
+	}
+}
+
+impl std::error::Error for InvokeError {}
+
+/// The error a failed call into the module is reported as, naming the function which trapped.
+fn invoke_error(function: &str, e: anyhow::Error) -> InvokeError {
+	match e.downcast_ref::<Trap>().copied() {
+		Some(trap) => {
+			let function = match function {
+				"" => "the default function".to_string(),
+				function => format!("function '{function}'"),
+			};
+			let message = match describe_trap(trap) {
+				Some(cause) => format!("WASM module trapped in {function}: {cause} ({trap})"),
+				None => format!("WASM module trapped in {function}: {trap}"),
+			};
+			InvokeError::Trap(e.context(message))
+		}
+		None => host_error(e),
+	}
+}
+
+/// The error a failure in the host is reported as, keeping the module running out of memory
+/// apart from other failures.
+fn host_error(e: anyhow::Error) -> InvokeError {
+	match e.downcast_ref::<MemoryError>() {
+		Some(e) => InvokeError::OutOfMemory(*e),
+		None => InvokeError::HostError(e),
+	}
+}
+
+/// Whether an argument of this kind may be omitted, as it is declared `none` or `option<T>`.
+/// Unlike [`Value::is_kind`](surrealdb_types::Value::is_kind), `any` is not optional, so a
+/// function taking a `Value` still requires its argument.
+fn is_optional(kind: &surrealdb_types::Kind) -> bool {
+	match kind {
+		surrealdb_types::Kind::None => true,
+		surrealdb_types::Kind::Either(kinds) => kinds.iter().any(is_optional),
+		_ => false,
+	}
+}
+
+/// The values of a stream function, pulled from the module one at a time, as returned by
+/// [`Controller::invoke_stream`].
+///
+/// The stream borrows the controller until it is dropped. A stream dropped before it is
+/// exhausted without being closed with [`ValueStream::close`] is released in the module when
+/// the controller next invokes a function, as dropping it cannot call into the module.
+pub struct ValueStream<'a> {
+	controller: &'a mut Controller,
+	function: String,
+	/// The handle of the stream in the module, or `None` once it is exhausted.
+	handle: Option<u32>,
+}
+
+impl fmt::Debug for ValueStream<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ValueStream")
+			.field("function", &self.function)
+			.field("handle", &self.handle)
+			.finish_non_exhaustive()
+	}
+}
+
+impl ValueStream<'_> {
+	/// Pull the next value, or `None` once the stream is exhausted.
+	pub async fn next(&mut self) -> Option<Result<surrealdb_types::Value>> {
+		self.try_next().await.map_err(anyhow::Error::from).transpose()
+	}
+
+	/// Pull the next value, reporting why it failed as with [`Controller::try_invoke`].
+	pub async fn try_next(
+		&mut self,
+	) -> std::result::Result<Option<surrealdb_types::Value>, InvokeError> {
+		let Some(handle) = self.handle else {
+			return Ok(None);
+		};
+		let controller = &mut *self.controller;
+		let next = controller
+			.instance
+			.get_typed_func::<(u32,), (i32,)>(&mut controller.store, "__sr_next")
+			.map_err(InvokeError::HostError)?;
+		controller.store.data_mut().function = Some(self.function.clone());
+		let result = next.call_async(&mut controller.store, (handle,)).await;
+		controller.store.data_mut().function = None;
+		let (ptr,) = result.map_err(|e| invoke_error(&self.function, e))?;
+
+		let result: Result<Result<Option<surrealdb_types::Value>, String>> = async {
+			if ptr == -1 {
+				anyhow::bail!("WASM function returned error (-1)");
+			}
+			let ptr_u32: u32 = ptr.try_into()?;
+			AsyncTransfer::receive(ptr_u32.into(), &mut *controller).await
+		}
+		.await;
+		let value = result.map_err(InvokeError::HostError)?.map_err(InvokeError::ModuleError)?;
+		if value.is_none() {
+			self.handle = None;
+		}
+		Ok(value)
+	}
+
+	/// Stop pulling values, releasing the stream in the module.
+	pub async fn close(mut self) -> Result<()> {
+		let Some(handle) = self.handle.take() else {
+			return Ok(());
+		};
+		let controller = &mut *self.controller;
+		let close = controller
+			.instance
+			.get_typed_func::<(u32,), i32>(&mut controller.store, "__sr_close")?;
+		close.call_async(&mut controller.store, (handle,)).await?;
+		Ok(())
+	}
+}
+
+impl Drop for ValueStream<'_> {
+	fn drop(&mut self) {
+		if let Some(handle) = self.handle.take() {
+			self.controller.dropped_streams.push(handle);
+		}
 	}
 }
 
 /// Per-execution controller. Not thread-safe - create one per concurrent call.
 /// Lightweight, created from Runtime. Each controller has its own isolated Store and Instance.
-#[derive(Debug)]
 pub struct Controller {
 	pub(super) store: Store<StoreData>,
 	pub(super) instance: Instance,
 	pub(super) memory: Memory,
+	pre: InstancePre<StoreData>,
+	wasi: WasiConfig,
+	namespace: Option<String>,
+	/// The argument kinds of each function called so far, by the name of its `args` export.
+	/// They cannot change while the module is loaded, so they are read from it only once.
+	arg_kinds: BTreeMap<String, Vec<surrealdb_types::Kind>>,
+	/// The handles of streams dropped before they were exhausted or closed, which are
+	/// released in the module before the next call.
+	dropped_streams: Vec<u32>,
+}
+
+impl fmt::Debug for Controller {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Controller")
+			.field("store", &self.store)
+			.field("instance", &self.instance)
+			.field("memory", &self.memory)
+			.field("namespace", &self.namespace)
+			.finish_non_exhaustive()
+	}
 }
 
 impl Controller {
+	async fn instantiate(
+		mut store: Store<StoreData>,
+		pre: InstancePre<StoreData>,
+		wasi: WasiConfig,
+	) -> Result<Self> {
+		let (instance, memory) = Self::instance(&mut store, &pre).await?;
+		Ok(Self {
+			store,
+			instance,
+			memory,
+			pre,
+			wasi,
+			namespace: None,
+			arg_kinds: BTreeMap::new(),
+			dropped_streams: Vec::new(),
+		})
+	}
+
+	async fn instance(
+		store: &mut Store<StoreData>,
+		pre: &InstancePre<StoreData>,
+	) -> Result<(Instance, Memory)> {
+		store.limiter(|data| &mut data.limits);
+		let instance = pre
+			.instantiate_async(&mut *store)
+			.await
+			.prefix_err(|| "failed to instantiate WASM module")?;
+		let memory = instance
+			.get_memory(&mut *store, "memory")
+			.prefix_err(|| "WASM module must export 'memory'")?;
+		check_abi_version(&instance, store).await?;
+		Ok((instance, memory))
+	}
+
+	/// Replace the instance with a fresh one, discarding all guest state such as globals and
+	/// heap allocations. The invocation context is kept, and a new WASI context is created
+	/// from the runtime's sandbox config. As with a new controller, `init` must be called
+	/// again before invoking functions. The selected namespace, transfer limit, context values,
+	/// deadline, KV namespacing and memory limit are kept. If the new instance cannot be
+	/// created, the controller is left as it was, with its context and old instance.
+	pub async fn reset(&mut self) -> Result<()> {
+		let engine = self.store.engine().clone();
+		let wasi = super::wasi_context::build(&self.wasi)?;
+		let data = self.store.data_mut();
+		let store_data = StoreData {
+			wasi,
+			config: data.config.clone(),
+			// A placeholder until the new instance is ready, or the context is moved back
+			context: std::mem::replace(&mut data.context, Box::new(DetachedContext)),
+			function: None,
+			max_transfer_bytes: data.max_transfer_bytes,
+			max_array_len: data.max_array_len,
+			vars: data.vars.clone(),
+			deadline: data.deadline,
+			kv_prefix: data.kv_prefix.clone(),
+			limits: data.limits.clone(),
+		};
+		let mut store = Store::new(&engine, store_data);
+		match Self::instance(&mut store, &self.pre).await {
+			Ok((instance, memory)) => {
+				self.store = store;
+				self.instance = instance;
+				self.memory = memory;
+				// The streams were dropped along with the old instance
+				self.dropped_streams.clear();
+				Ok(())
+			}
+			Err(e) => {
+				self.store.data_mut().context = store.into_data().context;
+				Err(e)
+			}
+		}
+	}
+
+	/// Limit the size of each value received from the guest, such as a function result or the
+	/// arguments of a host call, to protect the host from modules it does not trust. Larger
+	/// transfers fail before any data is copied.
+	pub fn set_max_transfer_bytes(&mut self, max: Option<u32>) {
+		self.store.data_mut().max_transfer_bytes = max;
+	}
+
+	/// Limit the number of elements in an array received from the guest, such as the arguments
+	/// of `__sr_run` or the keys of a KV batch. The count declared by the array is checked
+	/// before any elements are allocated, so an oversized array fails without decoding it.
+	pub fn set_max_array_len(&mut self, max: Option<u32>) {
+		self.store.data_mut().max_array_len = max;
+	}
+
+	/// Limit the linear memory of the module to `max` bytes, or `None` for no limit. Memory
+	/// the module already has is kept, but it cannot grow beyond the limit, so allocations
+	/// which need more fail instead. Arguments which no longer fit are reported as
+	/// [`InvokeError::OutOfMemory`].
+	pub fn set_max_memory(&mut self, max: Option<usize>) {
+		let limits = match max {
+			Some(max) => StoreLimitsBuilder::new().memory_size(max),
+			None => StoreLimitsBuilder::new(),
+		};
+		self.store.data_mut().limits = limits.build();
+	}
+
+	/// Namespace the module's KV keys by its organisation and name, so that modules sharing one
+	/// store do not see each other's keys. Every key is stored under `organisation/name/`,
+	/// which is added and stripped by the runtime without the module knowing, so `kv::get("x")`
+	/// in one module reads a different entry from `kv::get("x")` in another.
+	///
+	/// Off by default, in which case keys are used as the module passes them.
+	pub fn set_kv_namespacing(&mut self, enabled: bool) {
+		let data = self.store.data_mut();
+		data.kv_prefix = enabled.then(|| {
+			let meta = &data.config.meta;
+			format!("{}/{}/", meta.organisation, meta.name)
+		});
+	}
+
+	/// Set a context value, such as the current user or session id, which the guest can read
+	/// with `surrealism::context::get` without it being passed as a function argument.
+	pub fn set_context(&mut self, key: impl Into<String>, value: surrealdb_types::Value) {
+		self.store.data_mut().vars.insert(key.into(), value);
+	}
+
+	/// Set when calls should finish by, or `None` for no deadline. The guest can read the time
+	/// remaining with `surrealism::time::deadline_remaining`, so that long-running work, such
+	/// as generating tokens, can stop early and return a partial result.
+	///
+	/// The deadline is advisory: a guest which does not check it is not interrupted when it
+	/// passes, and is left to finish.
+	pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+		self.store.data_mut().deadline = deadline;
+	}
+
+	/// The namespace functions are looked up in, or `None` for the root namespace.
+	pub fn namespace(&self) -> Option<&str> {
+		self.namespace.as_deref()
+	}
+
+	/// Select the namespace functions are looked up in, or `None` for the root namespace.
+	pub fn set_namespace(&mut self, namespace: Option<String>) {
+		self.namespace = namespace;
+	}
+
+	/// The export name of a function's `kind` export (`fnc`, `args`, ...) in the selected
+	/// namespace, such as `__sr_fnc__name` or `__sr_fnc__namespace::name`.
+	fn export_name(&self, kind: &str, name: &str) -> String {
+		export_symbol(kind, self.namespace.as_deref(), name)
+	}
+
+	/// Allocate `len` bytes in the guest. The guest signals failure by returning a null pointer,
+	/// which is reported as [`MemoryError::OutOfMemory`].
 	pub async fn alloc(&mut self, len: u32) -> Result<u32> {
-		let alloc = self.instance.get_typed_func::<(u32,), i32>(&mut self.store, "__sr_alloc")?;
+		let alloc = self.instance.get_typed_func::<(u32,), u32>(&mut self.store, "__sr_alloc")?;
 		let result = alloc.call_async(&mut self.store, (len,)).await?;
-		if result == -1 {
-			anyhow::bail!("Memory allocation failed");
+		if result == 0 {
+			return Err(MemoryError::OutOfMemory {
+				len,
+			}
+			.into());
 		}
-		Ok(result as u32)
+		Ok(result)
 	}
 
+	/// Free a guest allocation. The guest signals failure by returning `0`.
 	pub async fn free(&mut self, ptr: u32, len: u32) -> Result<()> {
-		let free = self.instance.get_typed_func::<(u32, u32), i32>(&mut self.store, "__sr_free")?;
+		let free = self.instance.get_typed_func::<(u32, u32), u32>(&mut self.store, "__sr_free")?;
 		let result = free.call_async(&mut self.store, (ptr, len)).await?;
-		if result == -1 {
+		if result == 0 {
 			anyhow::bail!("Memory deallocation failed");
 		}
 		Ok(())
@@ -178,33 +848,190 @@ impl Controller {
 		init.call_async(&mut self.store, ()).await
 	}
 
+	/// Invoke a function, flattening every failure into an error. See [`Controller::try_invoke`]
+	/// to tell traps, host errors and errors returned by the module apart.
 	pub async fn invoke<A: Args>(
 		&mut self,
 		name: Option<String>,
 		args: A,
 	) -> Result<surrealdb_types::Value> {
-		let name = format!("__sr_fnc__{}", name.unwrap_or_default());
-		let args = AsyncTransfer::transfer(args.to_values(), self).await?;
-		let invoke = self.instance.get_typed_func::<(u32,), (i32,)>(&mut self.store, &name)?;
-		let (ptr,) = invoke.call_async(&mut self.store, (*args,)).await?;
-		if ptr == -1 {
-			anyhow::bail!("WASM function returned error (-1)");
+		Ok(self.try_invoke(name, args).await?)
+	}
+
+	/// Invoke a function, reporting why it failed, so that embedders can decide whether a
+	/// call should be retried. A module which trapped should be reset before it is used again.
+	///
+	/// The values of a stream function are all pulled and returned as an array, see
+	/// [`Controller::invoke_stream`] to pull them one at a time instead.
+	pub async fn try_invoke<A: Args>(
+		&mut self,
+		name: Option<String>,
+		args: A,
+	) -> std::result::Result<surrealdb_types::Value, InvokeError> {
+		if !self.is_stream(name.clone()) {
+			return self.call(name, args).await;
+		}
+		let mut stream = self.invoke_stream(name, args).await?;
+		let mut values = Vec::new();
+		while let Some(value) = stream.try_next().await? {
+			values.push(value);
+		}
+		Ok(surrealdb_types::Value::Array(values.into()))
+	}
+
+	/// Invoke a stream function, returning a [`ValueStream`] which pulls its values from the
+	/// module one at a time, as they are produced.
+	pub async fn invoke_stream<A: Args>(
+		&mut self,
+		name: Option<String>,
+		args: A,
+	) -> std::result::Result<ValueStream<'_>, InvokeError> {
+		if !self.is_stream(name.clone()) {
+			let name = name.unwrap_or_default();
+			return Err(InvokeError::HostError(anyhow::anyhow!(
+				"Function '{name}' does not return a stream"
+			)));
+		}
+		let function = self.function_path(name.as_deref().unwrap_or_default());
+		let handle = match self.call(name, args).await? {
+			surrealdb_types::Value::Number(surrealdb_types::Number::Int(handle)) => {
+				u32::try_from(handle).map_err(|e| InvokeError::HostError(e.into()))?
+			}
+			value => {
+				return Err(InvokeError::HostError(anyhow::anyhow!(
+					"Expected a stream handle, found {value:?}"
+				)));
+			}
+		};
+		Ok(ValueStream {
+			controller: self,
+			function,
+			handle: Some(handle),
+		})
+	}
+
+	/// The name of a function in the selected namespace, as reported in traps and logs.
+	fn function_path(&self, name: &str) -> String {
+		match &self.namespace {
+			Some(namespace) => format!("{namespace}::{name}"),
+			None => name.to_string(),
+		}
+	}
+
+	/// Release the streams dropped without being closed, see [`ValueStream`].
+	async fn release_dropped_streams(&mut self) -> Result<()> {
+		if self.dropped_streams.is_empty() {
+			return Ok(());
+		}
+		let close = self.instance.get_typed_func::<(u32,), i32>(&mut self.store, "__sr_close")?;
+		for handle in std::mem::take(&mut self.dropped_streams) {
+			close.call_async(&mut self.store, (handle,)).await?;
 		}
-		let ptr_u32: u32 = ptr.try_into()?;
-		let result: Result<surrealdb_types::Value, String> =
-			AsyncTransfer::receive(ptr_u32.into(), self).await?;
-		result.map_err(|e| anyhow::anyhow!("WASM function returned error: {}", e))
+		Ok(())
+	}
+
+	/// Call a function's `fnc` export, returning its result as is.
+	async fn call<A: Args>(
+		&mut self,
+		name: Option<String>,
+		args: A,
+	) -> std::result::Result<surrealdb_types::Value, InvokeError> {
+		self.release_dropped_streams().await.map_err(host_error)?;
+		let name = name.unwrap_or_default();
+		let export = self.export_name("fnc", &name);
+		let prepared: Result<_> = async {
+			// Coerce the arguments to the declared kinds, leaving any surplus for the guest to
+			// reject
+			let kinds = self.arg_kinds(&name).await?;
+			let mut args = args.to_values();
+			// Omitted trailing arguments declared optional, such as an `Option<T>`, are passed
+			// as NONE
+			if args.len() < kinds.len() && kinds[args.len()..].iter().all(is_optional) {
+				args.resize(kinds.len(), surrealdb_types::Value::None);
+			}
+			let args = args
+				.into_iter()
+				.enumerate()
+				.map(|(i, value)| match kinds.get(i) {
+					Some(kind) => {
+						coerce(value, kind).prefix_err(|| format!("Invalid argument {}", i + 1))
+					}
+					None => Ok(value),
+				})
+				.collect::<Result<Vec<_>>>()?;
+			let args = AsyncTransfer::transfer(args, self).await?;
+			let invoke =
+				self.instance.get_typed_func::<(u32,), (i32,)>(&mut self.store, &export)?;
+			Ok((args, invoke))
+		}
+		.await;
+		let (args, invoke) = prepared.map_err(host_error)?;
+
+		let function = self.function_path(&name);
+		self.store.data_mut().function = Some(function.clone());
+		let result = invoke.call_async(&mut self.store, (*args,)).await;
+		self.store.data_mut().function = None;
+		let (ptr,) = result.map_err(|e| invoke_error(&function, e))?;
+
+		let result: Result<Result<surrealdb_types::Value, String>> = async {
+			if ptr == -1 {
+				anyhow::bail!("WASM function returned error (-1)");
+			}
+			let ptr_u32: u32 = ptr.try_into()?;
+			AsyncTransfer::receive(ptr_u32.into(), self).await
+		}
+		.await;
+		result.map_err(InvokeError::HostError)?.map_err(InvokeError::ModuleError)
+	}
+
+	/// Invoke a function and check its result against the declared return kind, coercing it
+	/// where possible. Unlike [`Controller::invoke`], this fails when a module returns a value
+	/// which does not match the kind it describes in its signature.
+	pub async fn invoke_checked<A: Args>(
+		&mut self,
+		name: Option<String>,
+		args: A,
+	) -> Result<surrealdb_types::Value> {
+		let kind = self.returns(name.clone()).await?;
+		let value = self.invoke(name, args).await?;
+		coerce(value, &kind).prefix_err(|| "Invalid return value")
+	}
+
+	/// Invoke a function with JSON arguments, returning its result as JSON, for hosts which
+	/// exchange JSON, such as over HTTP. Arguments are coerced to the declared kinds as with
+	/// [`Controller::invoke`]. The conversion of the result is lossy for values without a JSON
+	/// counterpart, such as records and durations, which become strings, see [`crate::json`].
+	pub async fn call_json(
+		&mut self,
+		name: Option<String>,
+		args: Vec<serde_json::Value>,
+	) -> Result<serde_json::Value> {
+		let args: Vec<surrealdb_types::Value> = args.into_iter().map(from_json).collect();
+		let value = self.invoke(name, args).await?;
+		Ok(to_json(value))
 	}
 
 	pub async fn args(&mut self, name: Option<String>) -> Result<Vec<surrealdb_types::Kind>> {
-		let name = format!("__sr_args__{}", name.unwrap_or_default());
+		let name = self.export_name("args", &name.unwrap_or_default());
 		let args = self.instance.get_typed_func::<(), (i32,)>(&mut self.store, &name)?;
 		let (ptr,) = args.call_async(&mut self.store, ()).await?;
 		AsyncTransfer::receive(ptr.try_into()?, self).await
 	}
 
+	/// The argument kinds of a function, as returned by [`Controller::args`], cached after
+	/// the first call.
+	async fn arg_kinds(&mut self, name: &str) -> Result<Vec<surrealdb_types::Kind>> {
+		let export = self.export_name("args", name);
+		if let Some(kinds) = self.arg_kinds.get(&export) {
+			return Ok(kinds.clone());
+		}
+		let kinds = self.args(Some(name.to_string())).await?;
+		self.arg_kinds.insert(export, kinds.clone());
+		Ok(kinds)
+	}
+
 	pub async fn returns(&mut self, name: Option<String>) -> Result<surrealdb_types::Kind> {
-		let name = format!("__sr_returns__{}", name.unwrap_or_default());
+		let name = self.export_name("returns", &name.unwrap_or_default());
 		let returns = self.instance.get_typed_func::<(), (i32,)>(&mut self.store, &name)?;
 		let (ptr,) = returns.call_async(&mut self.store, ()).await?;
 		if ptr == -1 {
@@ -213,20 +1040,258 @@ impl Controller {
 		AsyncTransfer::receive(ptr.try_into()?, self).await
 	}
 
+	/// The names of a function's arguments, in order. Modules built before argument names were
+	/// exported have none, in which case this is empty.
+	pub async fn arg_names(&mut self, name: Option<String>) -> Result<Vec<String>> {
+		let name = self.export_name("argnames", &name.unwrap_or_default());
+		if !self.has_export(&name) {
+			return Ok(Vec::new());
+		}
+		let names = self.instance.get_typed_func::<(), (i32,)>(&mut self.store, &name)?;
+		let (ptr,) = names.call_async(&mut self.store, ()).await?;
+		if ptr == -1 {
+			anyhow::bail!("WASM function returned error (-1)");
+		}
+		AsyncTransfer::receive(ptr.try_into()?, self).await
+	}
+
+	/// The deprecation note of a function, or `None` if it is not deprecated.
+	pub async fn deprecated(&mut self, name: Option<String>) -> Result<Option<String>> {
+		let name = self.export_name("deprecated", &name.unwrap_or_default());
+		if !self.has_export(&name) {
+			return Ok(None);
+		}
+		let deprecated = self.instance.get_typed_func::<(), (i32,)>(&mut self.store, &name)?;
+		let (ptr,) = deprecated.call_async(&mut self.store, ()).await?;
+		if ptr == -1 {
+			anyhow::bail!("WASM function returned error (-1)");
+		}
+		AsyncTransfer::receive(ptr.try_into()?, self).await.map(Some)
+	}
+
+	/// Whether a function is marked `#[surrealism(pure)]`, declaring that its result depends
+	/// only on its arguments and that it has no side effects, so that hosts may cache results
+	/// by function and arguments. The runtime does not check this, nor cache results itself.
+	pub fn is_pure(&mut self, name: Option<String>) -> bool {
+		let name = self.export_name("pure", &name.unwrap_or_default());
+		self.has_export(&name)
+	}
+
+	/// Whether a function is marked `#[surrealism(stream)]`, returning its values one at a
+	/// time through [`Controller::invoke_stream`].
+	pub fn is_stream(&mut self, name: Option<String>) -> bool {
+		let name = self.export_name("stream", &name.unwrap_or_default());
+		self.has_export(&name)
+	}
+
+	/// The doc comment of a function, or `None` if it is not documented.
+	pub async fn doc(&mut self, name: Option<String>) -> Result<Option<String>> {
+		let name = self.export_name("doc", &name.unwrap_or_default());
+		if !self.has_export(&name) {
+			return Ok(None);
+		}
+		let doc = self.instance.get_typed_func::<(), (i32,)>(&mut self.store, &name)?;
+		let (ptr,) = doc.call_async(&mut self.store, ()).await?;
+		if ptr == -1 {
+			anyhow::bail!("WASM function returned error (-1)");
+		}
+		AsyncTransfer::receive(ptr.try_into()?, self).await.map(Some)
+	}
+
+	/// Which build of the module is loaded: the version of the crate it was built from, the
+	/// version of `surrealism` it was built against, and when it was compiled. Modules built
+	/// before this was exported have none, in which case this is `None`.
+	pub async fn build_info(&mut self) -> Result<Option<BuildInfo>> {
+		if !self.has_export("__sr_build_info") {
+			return Ok(None);
+		}
+		let build_info =
+			self.instance.get_typed_func::<(), (i32,)>(&mut self.store, "__sr_build_info")?;
+		let (ptr,) = build_info.call_async(&mut self.store, ()).await?;
+		if ptr == -1 {
+			anyhow::bail!("WASM function returned error (-1)");
+		}
+		let info: SerializableArg<BuildInfo> =
+			AsyncTransfer::receive(ptr.try_into()?, self).await?;
+		Ok(Some(info.0))
+	}
+
+	/// The signatures of every function in the module, in all namespaces, sorted by namespace
+	/// and name.
+	///
+	/// The signatures are read in one call to the module's `__sr_manifest` export. Modules
+	/// built before it existed are described function by function instead, which is slower.
+	pub async fn manifest(&mut self) -> Result<Vec<FunctionSignature>> {
+		if !self.has_export("__sr_manifest") {
+			return self.describe_functions().await;
+		}
+		let manifest =
+			self.instance.get_typed_func::<(), (i32,)>(&mut self.store, "__sr_manifest")?;
+		let (ptr,) = manifest.call_async(&mut self.store, ()).await?;
+		if ptr == -1 {
+			anyhow::bail!("WASM function returned error (-1)");
+		}
+		let entries: Vec<ManifestEntry> = AsyncTransfer::receive(ptr.try_into()?, self).await?;
+		Ok(entries
+			.into_iter()
+			.map(|(namespace, name, args, arg_names, returns, doc, deprecated)| {
+				// Purity and streams are flag exports, rather than part of the manifest
+				let pure = self.has_export(&export_symbol("pure", namespace.as_deref(), &name));
+				let stream = self.has_export(&export_symbol("stream", namespace.as_deref(), &name));
+				FunctionSignature {
+					namespace,
+					name,
+					args,
+					arg_names,
+					returns,
+					doc,
+					deprecated,
+					pure,
+					stream,
+				}
+			})
+			.collect())
+	}
+
+	/// The signatures of every function in the module, as with [`Controller::manifest`], but
+	/// keeping going past functions which cannot be described, such as one using a kind the
+	/// host does not support. Each function carries its own signature or error, so that the
+	/// others can still be listed.
+	///
+	/// The manifest describes every function in one call, so a single broken function fails
+	/// it. In that case the functions are described one by one instead.
+	pub async fn describe(&mut self) -> Result<Vec<DescribedFunction>> {
+		let manifest = if self.has_export("__sr_manifest") {
+			self.manifest().await.ok()
+		} else {
+			None
+		};
+		match manifest {
+			Some(signatures) => Ok(signatures
+				.into_iter()
+				.map(|signature| DescribedFunction {
+					namespace: signature.namespace.clone(),
+					name: signature.name.clone(),
+					signature: Ok(signature),
+				})
+				.collect()),
+			None => self.describe_each().await,
+		}
+	}
+
+	/// Describe every function through its own exports, for modules without a manifest.
+	async fn describe_functions(&mut self) -> Result<Vec<FunctionSignature>> {
+		self.describe_each().await?.into_iter().map(|function| function.signature).collect()
+	}
+
+	/// Describe every function through its own exports, sorted by namespace and name, keeping
+	/// the error of each function which cannot be described.
+	async fn describe_each(&mut self) -> Result<Vec<DescribedFunction>> {
+		let selected = self.namespace.clone();
+		let namespaces = self.namespaces();
+		let mut functions = Vec::new();
+		for namespace in std::iter::once(None).chain(namespaces.into_iter().map(Some)) {
+			self.namespace.clone_from(&namespace);
+			let names = match self.list() {
+				Ok(names) => names,
+				Err(e) => {
+					self.namespace = selected;
+					return Err(e);
+				}
+			};
+			for name in names {
+				let signature = self.describe_function(namespace.clone(), name.clone()).await;
+				functions.push(DescribedFunction {
+					namespace: namespace.clone(),
+					name,
+					signature,
+				});
+			}
+		}
+		self.namespace = selected;
+		functions.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+		Ok(functions)
+	}
+
+	/// Describe a function of the selected namespace through its own exports.
+	async fn describe_function(
+		&mut self,
+		namespace: Option<String>,
+		name: String,
+	) -> Result<FunctionSignature> {
+		let function = Some(name.clone());
+		Ok(FunctionSignature {
+			namespace,
+			args: self
+				.args(function.clone())
+				.await
+				.prefix_err(|| format!("Failed to collect arguments for function '{name}'"))?,
+			arg_names: self
+				.arg_names(function.clone())
+				.await
+				.prefix_err(|| format!("Failed to collect argument names for function '{name}'"))?,
+			returns: self
+				.returns(function.clone())
+				.await
+				.prefix_err(|| format!("Failed to collect return type for function '{name}'"))?,
+			doc: self
+				.doc(function.clone())
+				.await
+				.prefix_err(|| format!("Failed to collect documentation for function '{name}'"))?,
+			deprecated: self
+				.deprecated(function.clone())
+				.await
+				.prefix_err(|| format!("Failed to collect deprecation for function '{name}'"))?,
+			pure: self.is_pure(function.clone()),
+			stream: self.is_stream(function),
+			name,
+		})
+	}
+
+	/// The live transfer allocations in the guest, or `None` if the module was not built
+	/// with the `alloc-stats` feature of `surrealism`.
+	///
+	/// Every value transferred during a call is freed once it has been received, so the
+	/// stats after a call should match those before it. A difference means a leak.
+	pub async fn alloc_stats(&mut self) -> Result<Option<AllocStats>> {
+		if !self.has_export("__sr_alloc_stats") {
+			return Ok(None);
+		}
+		let stats =
+			self.instance.get_typed_func::<(), (i32,)>(&mut self.store, "__sr_alloc_stats")?;
+		let (ptr,) = stats.call_async(&mut self.store, ()).await?;
+		if ptr == -1 {
+			anyhow::bail!("WASM function returned error (-1)");
+		}
+		let (allocations, bytes): (u64, u64) =
+			AsyncTransfer::receive(ptr.try_into()?, self).await?;
+		Ok(Some(AllocStats {
+			allocations,
+			bytes,
+		}))
+	}
+
+	/// Check whether the module exports an item with the given name.
+	pub fn has_export(&mut self, name: &str) -> bool {
+		self.instance.get_export(&mut self.store, name).is_some()
+	}
+
+	/// The functions in the selected namespace.
 	pub fn list(&mut self) -> Result<Vec<String>> {
 		// scan the exported functions and return a list of available functions
 		let mut functions = Vec::new();
+		let prefix = self.export_name("fnc", "");
 
-		// First, collect all export names that start with __sr_fnc__
+		// First, collect all export names in the namespace. Names in the root namespace
+		// never contain "::", which separates a namespace from the function name.
 		let function_names: Vec<String> = {
 			let exports = self.instance.exports(&mut self.store);
 			exports
 				.filter_map(|export| {
 					let name = export.name();
-					if name.starts_with("__sr_fnc__") {
-						Some(name.to_string())
-					} else {
-						None
+					match name.strip_prefix(prefix.as_str()) {
+						Some(function) if !function.contains("::") => Some(name.to_string()),
+						_ => None,
 					}
 				})
 				.collect()
@@ -238,13 +1303,28 @@ impl Controller {
 				&& let ExternType::Func(_) = export.ty(&self.store)
 			{
 				// strip the prefix
-				let function_name = name.strip_prefix("__sr_fnc__").unwrap_or(&name).to_string();
+				let function_name = name.strip_prefix(prefix.as_str()).unwrap_or(&name).to_string();
 				functions.push(function_name);
 			}
 		}
 
 		Ok(functions)
 	}
+
+	/// The namespaces declared by the module, excluding the root namespace.
+	pub fn namespaces(&mut self) -> Vec<String> {
+		let mut namespaces: Vec<String> = self
+			.instance
+			.exports(&mut self.store)
+			.filter_map(|export| {
+				let function = export.name().strip_prefix("__sr_fnc__")?;
+				function.split_once("::").map(|(namespace, _)| namespace.to_string())
+			})
+			.collect();
+		namespaces.sort();
+		namespaces.dedup();
+		namespaces
+	}
 }
 
 #[async_trait]
@@ -273,4 +1353,12 @@ impl surrealism_types::controller::AsyncMemoryController for Controller {
 
 		Ok(&mut mem[start..end])
 	}
+
+	fn max_transfer_bytes(&self) -> Option<u32> {
+		self.store.data().max_transfer_bytes
+	}
+
+	fn max_array_len(&self) -> Option<u32> {
+		self.store.data().max_array_len
+	}
 }
@@ -0,0 +1,74 @@
+//! Engine configuration for guest modules.
+//!
+//! The defaults suit typical modules. Compute-heavy modules, such as numeric or machine
+//! learning workloads, may need a larger stack for deep recursion, or deterministic NaNs so
+//! that results are identical across runs and hosts. As these settings apply when a module is
+//! compiled, they are passed to [`Runtime::new_with_config`].
+//!
+//! [`Runtime::new_with_config`]: crate::controller::Runtime::new_with_config
+
+use wasmtime::Config;
+
+/// Stack space kept for host functions on top of the guest stack. Async calls run on a
+/// separate stack, which must be larger than the guest stack limit, and this matches the
+/// difference between the wasmtime defaults of 2 MiB and 512 KiB.
+const HOST_STACK_SIZE: usize = 1536 * 1024;
+
+/// Describes how guest modules are compiled and run.
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+	/// The most stack, in bytes, the guest may use before it traps. Defaults to 512 KiB.
+	pub max_wasm_stack: usize,
+	/// Whether NaNs produced by floating point operations are replaced with a single canonical
+	/// NaN, so that results do not depend on the host CPU. Disabled by default, as it slows
+	/// down floating point code.
+	pub nan_canonicalization: bool,
+	/// The virtual address space, in bytes, reserved for the guest memory up front, or `None`
+	/// for the wasmtime default of 4 GiB on 64-bit hosts. Memories which grow beyond this are
+	/// moved, which is slower, so this only needs lowering where address space is limited.
+	pub static_memory_maximum_size: Option<u64>,
+}
+
+impl Default for EngineConfig {
+	fn default() -> Self {
+		Self {
+			max_wasm_stack: 512 * 1024,
+			nan_canonicalization: false,
+			static_memory_maximum_size: None,
+		}
+	}
+}
+
+impl EngineConfig {
+	/// Create the default configuration.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the most stack, in bytes, the guest may use.
+	pub fn max_wasm_stack(mut self, bytes: usize) -> Self {
+		self.max_wasm_stack = bytes;
+		self
+	}
+
+	/// Enable or disable NaN canonicalization.
+	pub fn nan_canonicalization(mut self, enable: bool) -> Self {
+		self.nan_canonicalization = enable;
+		self
+	}
+
+	/// Set the virtual address space, in bytes, reserved for the guest memory.
+	pub fn static_memory_maximum_size(mut self, bytes: u64) -> Self {
+		self.static_memory_maximum_size = Some(bytes);
+		self
+	}
+
+	pub(crate) fn apply(&self, config: &mut Config) {
+		config.max_wasm_stack(self.max_wasm_stack);
+		config.async_stack_size(self.max_wasm_stack.saturating_add(HOST_STACK_SIZE));
+		config.cranelift_nan_canonicalization(self.nan_canonicalization);
+		if let Some(bytes) = self.static_memory_maximum_size {
+			config.memory_reservation(bytes);
+		}
+	}
+}
@@ -1,16 +1,22 @@
 use std::ops::{Deref, DerefMut};
+use std::time::Instant;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use rand::RngCore;
+use surrealism_types::arg::SerializableArg;
 use surrealism_types::controller::AsyncMemoryController;
-use surrealism_types::err::PrefixError;
+use surrealism_types::err::{MemoryError, PrefixError};
+use surrealism_types::log::LogLevel;
+use surrealism_types::query::QueryResult;
 use surrealism_types::serialize::SerializableRange;
 use surrealism_types::transfer::AsyncTransfer;
 use wasmtime::{Caller, Linker};
 
 use crate::config::SurrealismConfig;
 use crate::controller::StoreData;
-use crate::kv::KVStore;
+use crate::kv::{KVStore, PrefixedStore};
+use crate::statement::{self, StatementKind};
 
 macro_rules! host_try_or_return {
 	($error:expr,$expr:expr) => {
@@ -102,6 +108,27 @@ macro_rules! register_host_function {
             )
             .prefix_err(|| "failed to register host function")?
     }};
+    // Async version with mutable controller - no arguments
+    ($linker:expr, $name:expr, |mut $controller:ident : $controller_ty:ty| -> Result<$ret:ty> $body:tt) => {{
+        $linker
+            .func_wrap_async(
+                "env",
+                $name,
+                |caller: Caller<'_, StoreData>, (): ()| {
+                    Box::new(async move {
+                        eprintln!("🔵 Host function called: {}", $name);
+                        let mut $controller: $controller_ty = HostController::from(caller);
+
+                        eprintln!("🟡 Executing async body for: {}", $name);
+                        let result = $body;
+                        eprintln!("🟢 Async body completed for: {}", $name);
+
+                        (*host_try_or_return!("Transfer error", result.transfer(&mut $controller).await)) as i32
+                    })
+                }
+            )
+            .prefix_err(|| "failed to register host function")?
+    }};
 }
 
 macro_rules! map_ok {
@@ -115,14 +142,50 @@ macro_rules! map_ok {
 
 /// Context provided for each WASM function invocation.
 /// Created per-call with borrowed execution context (stack, query context, etc).
+///
+/// # Reentrancy
+///
+/// A host call runs while the guest is suspended in the middle of an import, with its
+/// allocator part way through a transfer. Entering the same instance again at that point,
+/// such as to invoke one of its functions while answering `run`, would allocate over memory
+/// the suspended call still uses. The context is therefore never given access to the
+/// instance. A `run` which calls back into the same module must do so through another
+/// `Controller`, which has an instance and memory of its own.
+///
+/// The only calls into the guest during a host call are the allocations which transfer its
+/// arguments and result, before and after the body of the host function runs.
 #[async_trait]
 pub trait InvocationContext: Send + Sync {
+	/// Execute a SurrealQL query on behalf of the WASM module.
+	/// Scripts with multiple statements must return an array with one result per statement.
 	async fn sql(
 		&mut self,
 		config: &SurrealismConfig,
 		query: String,
 		vars: surrealdb_types::Object,
 	) -> Result<surrealdb_types::Value>;
+	/// Execute a SurrealQL script on behalf of the WASM module, reporting each statement
+	/// separately, so that a failed statement does not hide the results of the others.
+	///
+	/// Hosts should override this to report the status of each statement. By default the
+	/// script is run with [`InvocationContext::sql`], whose results are all reported as
+	/// succeeded, and whose error fails the whole call. A query which [`statement::count`]
+	/// finds a single statement in has a single result, even when it is an array, and the
+	/// array [`InvocationContext::sql`] returns for any other script is split per statement.
+	async fn sql_results(
+		&mut self,
+		config: &SurrealismConfig,
+		query: String,
+		vars: surrealdb_types::Object,
+	) -> Result<Vec<QueryResult>> {
+		let count = statement::count(&query);
+		let results = match self.sql(config, query, vars).await? {
+			surrealdb_types::Value::Array(results) if count != 1 => results.into_inner(),
+			result => vec![result],
+		};
+		Ok(results.into_iter().map(QueryResult::ok).collect())
+	}
+
 	async fn run(
 		&mut self,
 		config: &SurrealismConfig,
@@ -133,6 +196,66 @@ pub trait InvocationContext: Send + Sync {
 
 	fn kv(&mut self) -> Result<&dyn KVStore>;
 
+	/// The statements in a query the module is about to run, including nested statements,
+	/// which are checked against the statements its capabilities allow. Only called for
+	/// modules which are not allowed arbitrary queries.
+	/// Hosts with a SurrealQL parser should override this to classify the parsed query. The
+	/// default scans the query with [`statement::statements`], which reports some fields named
+	/// after statements as statements, and rejects queries it cannot split reliably.
+	fn statements(&mut self, query: &str) -> Result<Vec<StatementKind>> {
+		statement::statements(query)
+	}
+
+	/// Read a file on behalf of the WASM module. The path has already been checked against
+	/// the `allow_fs` capability of the package, and is canonical. Files larger than
+	/// [`MAX_FS_READ_BYTES`](surrealism_types::MAX_FS_READ_BYTES) are rejected, so hosts can
+	/// stop reading beyond it.
+	/// Hosts without a filesystem, or which do not expose it, can keep the default, which
+	/// refuses every read.
+	async fn read_file(&mut self, path: &str) -> Result<Vec<u8>> {
+		anyhow::bail!("Reading '{path}' failed: this host does not provide files to modules")
+	}
+
+	/// Produce `len` random bytes for the WASM module, where `len` is at most
+	/// [`MAX_RANDOM_BYTES`](surrealism_types::MAX_RANDOM_BYTES).
+	/// Hosts can override this to control the entropy source, for example with a seeded RNG.
+	fn random(&mut self, len: u64) -> Result<Vec<u8>> {
+		// Default implementation: use the thread-local OS-seeded RNG
+		let mut buf = vec![0; usize::try_from(len)?];
+		rand::rng().fill_bytes(&mut buf);
+		Ok(buf)
+	}
+
+	/// The current time as seen by the WASM module.
+	/// Hosts can override this to inject a fixed or virtualized clock.
+	fn now(&mut self) -> Result<surrealdb_types::Datetime> {
+		// Default implementation: use the host wall clock
+		Ok(surrealdb_types::Datetime::now())
+	}
+
+	/// Handle a log record from the WASM module, logged while running `function` (empty for
+	/// the default function, and `None` outside of a function call, such as during init).
+	/// Hosts can override this to route module logs into their own logging or telemetry.
+	fn log(
+		&mut self,
+		config: &SurrealismConfig,
+		level: LogLevel,
+		function: Option<&str>,
+		message: &str,
+	) -> Result<()> {
+		// Default implementation: write errors and warnings to stderr, and the rest to stdout
+		let meta = &config.meta;
+		let source = match function {
+			Some(function) => format!("{}/{}::{function}", meta.organisation, meta.name),
+			None => format!("{}/{}", meta.organisation, meta.name),
+		};
+		let line = format!("[{level}] {source}: {message}\n");
+		match level {
+			LogLevel::Error | LogLevel::Warn => self.stderr(&line),
+			LogLevel::Info | LogLevel::Debug => self.stdout(&line),
+		}
+	}
+
 	/// Handle stdout output from the WASM module
 	fn stdout(&mut self, output: &str) -> Result<()> {
 		// Default implementation: print to standard output
@@ -155,9 +278,20 @@ pub fn implement_host_functions(linker: &mut Linker<StoreData>) -> Result<()> {
 	// SQL function
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_sql", |mut controller: HostController, sql: String, vars: Vec<(String, surrealdb_types::Value)>| -> Result<surrealdb_types::Value> {
+        // Variables are collected into an object, so when a name is repeated the last value wins
+        let vars = surrealdb_types::Object::from_iter(vars.into_iter());
+        let config = controller.config().clone();
+        // Packages without a capabilities section are not allowed arbitrary queries either
+        let capabilities = config.capabilities.clone().unwrap_or_default();
+        map_ok!(capabilities.check_query(&sql, controller.context_mut()) => |_allowed| controller.context_mut().sql(&config, sql, vars).await)
+    });
+
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_sql_results", |mut controller: HostController, sql: String, vars: Vec<(String, surrealdb_types::Value)>| -> Result<SerializableArg<Vec<QueryResult>>> {
         let vars = surrealdb_types::Object::from_iter(vars.into_iter());
         let config = controller.config().clone();
-        controller.context_mut().sql(&config, sql, vars).await
+        let capabilities = config.capabilities.clone().unwrap_or_default();
+        map_ok!(capabilities.check_query(&sql, controller.context_mut()) => |_allowed| controller.context_mut().sql_results(&config, sql, vars).await.map(SerializableArg))
     });
 
 	// Run function
@@ -170,62 +304,126 @@ pub fn implement_host_functions(linker: &mut Linker<StoreData>) -> Result<()> {
 	// KV functions
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_get", |mut controller: HostController, key: String| -> Result<Option<surrealdb_types::Value>> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.get(key).await)
+        map_ok!(controller.kv() => |kv| kv.get(key).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_set", |mut controller: HostController, key: String, value: surrealdb_types::Value| -> Result<()> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.set(key, value).await)
+        map_ok!(controller.kv() => |kv| kv.set(key, value).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_del", |mut controller: HostController, key: String| -> Result<()> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.del(key).await)
+        map_ok!(controller.kv() => |kv| kv.del(key).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_exists", |mut controller: HostController, key: String| -> Result<bool> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.exists(key).await)
+        map_ok!(controller.kv() => |kv| kv.exists(key).await)
     });
 
 	#[rustfmt::skip]
-    register_host_function!(linker, "__sr_kv_del_rng", |mut controller: HostController, range: SerializableRange<String>| -> Result<()> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.del_rng(range.beg, range.end).await)
+    register_host_function!(linker, "__sr_kv_del_rng", |mut controller: HostController, range: SerializableRange<String>| -> Result<u64> {
+        map_ok!(controller.kv() => |kv| kv.del_rng(range.beg, range.end).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_get_batch", |mut controller: HostController, keys: Vec<String>| -> Result<Vec<Option<surrealdb_types::Value>>> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.get_batch(keys).await)
+        map_ok!(controller.kv() => |kv| kv.get_batch(keys).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_set_batch", |mut controller: HostController, entries: Vec<(String, surrealdb_types::Value)>| -> Result<()> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.set_batch(entries).await)
+        map_ok!(controller.kv() => |kv| kv.set_batch(entries).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_del_batch", |mut controller: HostController, keys: Vec<String>| -> Result<()> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.del_batch(keys).await)
+        map_ok!(controller.kv() => |kv| kv.del_batch(keys).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_keys", |mut controller: HostController, range: SerializableRange<String>| -> Result<Vec<String>> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.keys(range.beg, range.end).await)
+        map_ok!(controller.kv() => |kv| kv.keys(range.beg, range.end).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_values", |mut controller: HostController, range: SerializableRange<String>| -> Result<Vec<surrealdb_types::Value>> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.values(range.beg, range.end).await)
+        map_ok!(controller.kv() => |kv| kv.values(range.beg, range.end).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_entries", |mut controller: HostController, range: SerializableRange<String>| -> Result<Vec<(String, surrealdb_types::Value)>> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.entries(range.beg, range.end).await)
+        map_ok!(controller.kv() => |kv| kv.entries(range.beg, range.end).await)
     });
 
 	#[rustfmt::skip]
     register_host_function!(linker, "__sr_kv_count", |mut controller: HostController, range: SerializableRange<String>| -> Result<u64> {
-        map_ok!(controller.context_mut().kv() => |kv| kv.count(range.beg, range.end).await)
+        map_ok!(controller.kv() => |kv| kv.count(range.beg, range.end).await)
+    });
+
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_kv_scan", |mut controller: HostController, range: SerializableRange<String>, limit: u64, after: Option<String>| -> Result<(Vec<(String, surrealdb_types::Value)>, Option<String>)> {
+        map_ok!(controller.kv() => |kv| kv.scan(range.beg, range.end, limit, after).await)
+    });
+
+	// Meta function
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_meta", |mut controller: HostController| -> Result<surrealdb_types::Value> {
+        let meta = &controller.config().meta;
+        let mut object = surrealdb_types::Object::new();
+        object.insert("organisation", meta.organisation.clone());
+        object.insert("name", meta.name.clone());
+        object.insert("version", meta.version.to_string());
+        anyhow::Ok(surrealdb_types::Value::Object(object))
+    });
+
+	// Random function
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_random", |mut controller: HostController, len: u64| -> Result<Vec<u8>> {
+        if len > surrealism_types::MAX_RANDOM_BYTES {
+            anyhow::bail!("Requested {len} random bytes, more than the limit of {} per call", surrealism_types::MAX_RANDOM_BYTES);
+        }
+        controller.context_mut().random(len)
+    });
+
+	// Time function
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_now", |mut controller: HostController| -> Result<SerializableArg<surrealdb_types::Datetime>> {
+        controller.context_mut().now().map(SerializableArg)
+    });
+
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_deadline_remaining", |mut controller: HostController| -> Result<SerializableArg<Option<surrealdb_types::Duration>>> {
+        let remaining: Option<surrealdb_types::Duration> = controller.data().deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()).into());
+        anyhow::Ok(SerializableArg(remaining))
+    });
+
+	// Log function
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_log", |mut controller: HostController, level: LogLevel, message: String| -> Result<()> {
+        let config = controller.config().clone();
+        let function = controller.data().function.clone();
+        controller.context_mut().log(&config, level, function.as_deref(), &message)
+    });
+
+	// Filesystem function
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_fs_read", |mut controller: HostController, path: String| -> Result<Vec<u8>> {
+        // Packages without a capabilities section may not read any file
+        let capabilities = controller.config().capabilities.clone().unwrap_or_default();
+        let contents = map_ok!(capabilities.check_fs_read(&path) => |file| controller.context_mut().read_file(&file).await);
+        map_ok!(contents => |contents| if contents.len() as u64 > surrealism_types::MAX_FS_READ_BYTES {
+            Err(anyhow::anyhow!("Reading '{path}' failed: the file is larger than the limit of {} bytes", surrealism_types::MAX_FS_READ_BYTES))
+        } else {
+            Ok(contents)
+        })
+    });
+
+	// Context function
+	#[rustfmt::skip]
+    register_host_function!(linker, "__sr_ctx_get", |mut controller: HostController, key: String| -> Result<Option<surrealdb_types::Value>> {
+        anyhow::Ok(controller.data().vars.get(&key).cloned())
     });
 
 	Ok(())
@@ -242,6 +440,13 @@ impl<'a> HostController<'a> {
 	pub fn config(&self) -> &SurrealismConfig {
 		&self.0.data().config
 	}
+
+	/// The KV store of the invocation context, with keys under the module's prefix when KV
+	/// namespacing is enabled.
+	fn kv(&mut self) -> Result<PrefixedStore<'_>> {
+		let prefix = self.0.data().kv_prefix.clone().unwrap_or_default();
+		Ok(PrefixedStore::new(self.context_mut().kv()?, prefix))
+	}
 }
 
 impl<'a> From<Caller<'a, StoreData>> for HostController<'a> {
@@ -274,7 +479,10 @@ impl<'a> AsyncMemoryController for HostController<'a> {
 		let result =
 			alloc_func.typed::<(u32,), u32>(&mut self.0)?.call_async(&mut self.0, (len,)).await?;
 		if result == 0 {
-			anyhow::bail!("Memory allocation failed");
+			return Err(MemoryError::OutOfMemory {
+				len,
+			}
+			.into());
 		}
 		Ok(result)
 	}
@@ -302,13 +510,26 @@ impl<'a> AsyncMemoryController for HostController<'a> {
 			.into_memory()
 			.ok_or_else(|| anyhow::anyhow!("Export memory is not a memory"))?;
 		let mem = memory.data_mut(&mut self.0);
-		if (ptr as usize) + (len as usize) > mem.len() {
+		let start = ptr as usize;
+		let end = start
+			.checked_add(len as usize)
+			.ok_or_else(|| anyhow::anyhow!("Memory access overflow: ptr={ptr}, len={len}"))?;
+
+		if end > mem.len() {
 			anyhow::bail!(
-				"[ERROR] Out of bounds: ptr + len = {} > mem.len() = {}",
-				(ptr as usize) + (len as usize),
+				"Memory access out of bounds: attempting to access [{start}..{end}), but memory size is {}",
 				mem.len()
 			);
 		}
-		Ok(&mut mem[(ptr as usize)..(ptr as usize) + (len as usize)])
+
+		Ok(&mut mem[start..end])
+	}
+
+	fn max_transfer_bytes(&self) -> Option<u32> {
+		self.0.data().max_transfer_bytes
+	}
+
+	fn max_array_len(&self) -> Option<u32> {
+		self.0.data().max_array_len
 	}
 }
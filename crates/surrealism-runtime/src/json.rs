@@ -0,0 +1,5 @@
+//! Conversion between JSON and SurrealDB values, for hosts which pass arguments and results
+//! as JSON, such as over HTTP. See [`Controller::call_json`](crate::controller::Controller::call_json),
+//! and [`surrealism_types::json`] for how values are mapped.
+
+pub use surrealism_types::json::{from_json, to_json};
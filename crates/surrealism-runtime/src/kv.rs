@@ -1,6 +1,8 @@
 use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::ops::Bound;
-use std::sync::RwLock;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -12,7 +14,8 @@ pub trait KVStore: Send + Sync {
 	async fn del(&self, key: String) -> Result<()>;
 	async fn exists(&self, key: String) -> Result<bool>;
 
-	async fn del_rng(&self, start: Bound<String>, end: Bound<String>) -> Result<()>;
+	/// Delete every entry in the range, returning the number of entries deleted.
+	async fn del_rng(&self, start: Bound<String>, end: Bound<String>) -> Result<u64>;
 
 	async fn get_batch(&self, keys: Vec<String>) -> Result<Vec<Option<surrealdb_types::Value>>>;
 	async fn set_batch(&self, entries: Vec<(String, surrealdb_types::Value)>) -> Result<()>;
@@ -30,6 +33,258 @@ pub trait KVStore: Send + Sync {
 		end: Bound<String>,
 	) -> Result<Vec<(String, surrealdb_types::Value)>>;
 	async fn count(&self, start: Bound<String>, end: Bound<String>) -> Result<u64>;
+
+	/// A page of at most `limit` entries in the range, starting after the key `after`, along
+	/// with the key to continue from, or `None` once the range is exhausted.
+	///
+	/// The default implementation builds on `keys` and `get_batch`, so only the page itself is
+	/// transferred to the guest. Stores which can seek to a key should override it.
+	async fn scan(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+		limit: u64,
+		after: Option<String>,
+	) -> Result<(Vec<(String, surrealdb_types::Value)>, Option<String>)> {
+		if limit == 0 {
+			anyhow::bail!("Scan limit must be greater than zero");
+		}
+		let mut keys = self.keys(scan_start(start, after), end).await?;
+		let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+		let more = keys.len() > limit;
+		keys.truncate(limit);
+		let values = self.get_batch(keys.clone()).await?;
+		let next = if more {
+			keys.last().cloned()
+		} else {
+			None
+		};
+		// Keys deleted since they were listed are skipped
+		let entries = keys
+			.into_iter()
+			.zip(values)
+			.filter_map(|(key, value)| value.map(|value| (key, value)))
+			.collect();
+		Ok((entries, next))
+	}
+}
+
+/// The start bound of a scan continuing after the key `after`, which is whichever of the two
+/// is the tighter bound.
+pub fn scan_start(start: Bound<String>, after: Option<String>) -> Bound<String> {
+	let Some(after) = after else {
+		return start;
+	};
+	match &start {
+		Bound::Included(key) | Bound::Excluded(key) if key.as_str() > after.as_str() => start,
+		_ => Bound::Excluded(after),
+	}
+}
+
+/// A shared store, so one KV store can back the invocation contexts of many controllers.
+/// Implementations use interior mutability, so concurrent access only needs `&self`.
+#[async_trait]
+impl<T: KVStore + ?Sized> KVStore for Arc<T> {
+	async fn get(&self, key: String) -> Result<Option<surrealdb_types::Value>> {
+		(**self).get(key).await
+	}
+
+	async fn set(&self, key: String, value: surrealdb_types::Value) -> Result<()> {
+		(**self).set(key, value).await
+	}
+
+	async fn del(&self, key: String) -> Result<()> {
+		(**self).del(key).await
+	}
+
+	async fn exists(&self, key: String) -> Result<bool> {
+		(**self).exists(key).await
+	}
+
+	async fn del_rng(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
+		(**self).del_rng(start, end).await
+	}
+
+	async fn get_batch(&self, keys: Vec<String>) -> Result<Vec<Option<surrealdb_types::Value>>> {
+		(**self).get_batch(keys).await
+	}
+
+	async fn set_batch(&self, entries: Vec<(String, surrealdb_types::Value)>) -> Result<()> {
+		(**self).set_batch(entries).await
+	}
+
+	async fn del_batch(&self, keys: Vec<String>) -> Result<()> {
+		(**self).del_batch(keys).await
+	}
+
+	async fn keys(&self, start: Bound<String>, end: Bound<String>) -> Result<Vec<String>> {
+		(**self).keys(start, end).await
+	}
+
+	async fn values(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+	) -> Result<Vec<surrealdb_types::Value>> {
+		(**self).values(start, end).await
+	}
+
+	async fn entries(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+	) -> Result<Vec<(String, surrealdb_types::Value)>> {
+		(**self).entries(start, end).await
+	}
+
+	async fn count(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
+		(**self).count(start, end).await
+	}
+
+	async fn scan(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+		limit: u64,
+		after: Option<String>,
+	) -> Result<(Vec<(String, surrealdb_types::Value)>, Option<String>)> {
+		(**self).scan(start, end, limit, after).await
+	}
+}
+
+/// A view of a store with every key under a prefix, such as `organisation/name/`, so that
+/// modules sharing one store do not see each other's keys. Keys are prefixed on the way in and
+/// stripped on the way out, so the prefix is invisible to the module. An empty prefix passes
+/// keys through unchanged.
+pub struct PrefixedStore<'a> {
+	inner: &'a dyn KVStore,
+	prefix: String,
+}
+
+impl<'a> PrefixedStore<'a> {
+	pub fn new(inner: &'a dyn KVStore, prefix: String) -> Self {
+		Self {
+			inner,
+			prefix,
+		}
+	}
+
+	fn key(&self, key: String) -> String {
+		format!("{}{key}", self.prefix)
+	}
+
+	fn strip(&self, mut key: String) -> String {
+		if key.starts_with(&self.prefix) {
+			key.split_off(self.prefix.len())
+		} else {
+			key
+		}
+	}
+
+	/// The start of a range, which is the prefix itself when the range is unbounded.
+	fn start(&self, start: Bound<String>) -> Bound<String> {
+		match start {
+			Bound::Included(key) => Bound::Included(self.key(key)),
+			Bound::Excluded(key) => Bound::Excluded(self.key(key)),
+			Bound::Unbounded if self.prefix.is_empty() => Bound::Unbounded,
+			Bound::Unbounded => Bound::Included(self.prefix.clone()),
+		}
+	}
+
+	/// The end of a range, which is the first key after every prefixed key when the range is
+	/// unbounded, found by incrementing the last character of the prefix.
+	fn end(&self, end: Bound<String>) -> Bound<String> {
+		match end {
+			Bound::Included(key) => Bound::Included(self.key(key)),
+			Bound::Excluded(key) => Bound::Excluded(self.key(key)),
+			Bound::Unbounded => {
+				let mut prefix = self.prefix.clone();
+				match prefix.pop().and_then(|last| char::from_u32(last as u32 + 1)) {
+					Some(next) => {
+						prefix.push(next);
+						Bound::Excluded(prefix)
+					}
+					None => Bound::Unbounded,
+				}
+			}
+		}
+	}
+}
+
+#[async_trait]
+impl KVStore for PrefixedStore<'_> {
+	async fn get(&self, key: String) -> Result<Option<surrealdb_types::Value>> {
+		self.inner.get(self.key(key)).await
+	}
+
+	async fn set(&self, key: String, value: surrealdb_types::Value) -> Result<()> {
+		self.inner.set(self.key(key), value).await
+	}
+
+	async fn del(&self, key: String) -> Result<()> {
+		self.inner.del(self.key(key)).await
+	}
+
+	async fn exists(&self, key: String) -> Result<bool> {
+		self.inner.exists(self.key(key)).await
+	}
+
+	async fn del_rng(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
+		self.inner.del_rng(self.start(start), self.end(end)).await
+	}
+
+	async fn get_batch(&self, keys: Vec<String>) -> Result<Vec<Option<surrealdb_types::Value>>> {
+		self.inner.get_batch(keys.into_iter().map(|key| self.key(key)).collect()).await
+	}
+
+	async fn set_batch(&self, entries: Vec<(String, surrealdb_types::Value)>) -> Result<()> {
+		let entries = entries.into_iter().map(|(key, value)| (self.key(key), value)).collect();
+		self.inner.set_batch(entries).await
+	}
+
+	async fn del_batch(&self, keys: Vec<String>) -> Result<()> {
+		self.inner.del_batch(keys.into_iter().map(|key| self.key(key)).collect()).await
+	}
+
+	async fn keys(&self, start: Bound<String>, end: Bound<String>) -> Result<Vec<String>> {
+		let keys = self.inner.keys(self.start(start), self.end(end)).await?;
+		Ok(keys.into_iter().map(|key| self.strip(key)).collect())
+	}
+
+	async fn values(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+	) -> Result<Vec<surrealdb_types::Value>> {
+		self.inner.values(self.start(start), self.end(end)).await
+	}
+
+	async fn entries(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+	) -> Result<Vec<(String, surrealdb_types::Value)>> {
+		let entries = self.inner.entries(self.start(start), self.end(end)).await?;
+		Ok(entries.into_iter().map(|(key, value)| (self.strip(key), value)).collect())
+	}
+
+	async fn count(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
+		self.inner.count(self.start(start), self.end(end)).await
+	}
+
+	async fn scan(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+		limit: u64,
+		after: Option<String>,
+	) -> Result<(Vec<(String, surrealdb_types::Value)>, Option<String>)> {
+		let after = after.map(|key| self.key(key));
+		let (entries, next) =
+			self.inner.scan(self.start(start), self.end(end), limit, after).await?;
+		let entries = entries.into_iter().map(|(key, value)| (self.strip(key), value)).collect();
+		Ok((entries, next.map(|key| self.strip(key))))
+	}
 }
 
 /// In-memory BTreeMap implementation of KVStore
@@ -86,6 +341,14 @@ impl BTreeMapStore {
 	}
 }
 
+impl From<BTreeMap<String, surrealdb_types::Value>> for BTreeMapStore {
+	fn from(map: BTreeMap<String, surrealdb_types::Value>) -> Self {
+		Self {
+			inner: RwLock::new(map),
+		}
+	}
+}
+
 impl Default for BTreeMapStore {
 	fn default() -> Self {
 		Self::new()
@@ -126,16 +389,16 @@ impl KVStore for BTreeMapStore {
 		Ok(map.contains_key(&key))
 	}
 
-	async fn del_rng(&self, start: Bound<String>, end: Bound<String>) -> Result<()> {
+	async fn del_rng(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
 		let mut map = self.inner.write().map_err(|_| {
 			anyhow::anyhow!("Failed to delete range from KV store: Could not acquire lock")
 		})?;
 		let keys_to_remove: Vec<String> =
 			map.keys().filter(|key| self.in_range(key, &start, &end)).cloned().collect();
-		for key in keys_to_remove {
-			map.remove(&key);
+		for key in &keys_to_remove {
+			map.remove(key);
 		}
-		Ok(())
+		Ok(keys_to_remove.len() as u64)
 	}
 
 	async fn get_batch(&self, keys: Vec<String>) -> Result<Vec<Option<surrealdb_types::Value>>> {
@@ -217,4 +480,185 @@ impl KVStore for BTreeMapStore {
 		let count = map.keys().filter(|key| self.in_range(key, &start, &end)).count();
 		Ok(count as u64)
 	}
+
+	async fn scan(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+		limit: u64,
+		after: Option<String>,
+	) -> Result<(Vec<(String, surrealdb_types::Value)>, Option<String>)> {
+		if limit == 0 {
+			anyhow::bail!("Scan limit must be greater than zero");
+		}
+		let map = self
+			.inner
+			.read()
+			.map_err(|_| anyhow::anyhow!("Failed to scan KV store: Could not acquire lock"))?;
+		let start = scan_start(start, after);
+		// `BTreeMap::range` panics on an empty range rather than returning nothing
+		let empty = match (&start, &end) {
+			(Bound::Included(s), Bound::Included(e)) => s > e,
+			(Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) => {
+				s >= e
+			}
+			_ => false,
+		};
+		if empty {
+			return Ok((Vec::new(), None));
+		}
+		let mut range = map.range::<String, _>((start, end));
+		let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+		let entries: Vec<(String, surrealdb_types::Value)> =
+			range.by_ref().take(limit).map(|(key, value)| (key.clone(), value.clone())).collect();
+		let next = match range.next() {
+			Some(_) => entries.last().map(|(key, _)| key.clone()),
+			None => None,
+		};
+		Ok((entries, next))
+	}
+}
+
+/// File-backed implementation of KVStore, so that state outlives the process.
+///
+/// Entries are kept in memory, and the whole store is written to the file after every change,
+/// through a temporary file which replaces it, so an interrupted write leaves the previous
+/// contents intact. This suits development and small stores, not write-heavy workloads.
+pub struct FileStore {
+	path: PathBuf,
+	store: BTreeMapStore,
+	/// Held while writing, as concurrent writes would share the temporary file
+	write: Mutex<()>,
+}
+
+impl FileStore {
+	/// Open the store at `path`, which is created on the first change if it does not exist.
+	pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+		let path = path.as_ref().to_path_buf();
+		let map = match std::fs::read(&path) {
+			// Entries are persisted as an object value, so they are decoded as one too
+			Ok(bytes) => match surrealdb_types::decode::<surrealdb_types::Value>(&bytes) {
+				Ok(surrealdb_types::Value::Object(object)) => object.into_inner(),
+				Ok(_) => {
+					anyhow::bail!(
+						"Failed to decode KV store {}: expected an object",
+						path.display()
+					)
+				}
+				Err(e) => {
+					anyhow::bail!("Failed to decode KV store {}: {e:#}", path.display())
+				}
+			},
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+			Err(e) => anyhow::bail!("Failed to read KV store {}: {e}", path.display()),
+		};
+		Ok(Self {
+			path,
+			store: BTreeMapStore::from(map),
+			write: Mutex::new(()),
+		})
+	}
+
+	/// The file the store is kept in.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Write the current entries to the file.
+	fn persist(&self) -> Result<()> {
+		let _write = self
+			.write
+			.lock()
+			.map_err(|_| anyhow::anyhow!("Failed to write KV store: Could not acquire lock"))?;
+		let bytes = {
+			let map =
+				self.store.inner.read().map_err(|_| {
+					anyhow::anyhow!("Failed to write KV store: Could not acquire lock")
+				})?;
+			let object = surrealdb_types::Object::from(map.clone());
+			surrealdb_types::encode(&surrealdb_types::Value::Object(object))?
+		};
+		let mut tmp = OsString::from(self.path.as_os_str());
+		tmp.push(".tmp");
+		std::fs::write(&tmp, bytes)
+			.and_then(|_| std::fs::rename(&tmp, &self.path))
+			.map_err(|e| anyhow::anyhow!("Failed to write KV store {}: {e}", self.path.display()))
+	}
+}
+
+#[async_trait]
+impl KVStore for FileStore {
+	async fn get(&self, key: String) -> Result<Option<surrealdb_types::Value>> {
+		self.store.get(key).await
+	}
+
+	async fn set(&self, key: String, value: surrealdb_types::Value) -> Result<()> {
+		self.store.set(key, value).await?;
+		self.persist()
+	}
+
+	async fn del(&self, key: String) -> Result<()> {
+		self.store.del(key).await?;
+		self.persist()
+	}
+
+	async fn exists(&self, key: String) -> Result<bool> {
+		self.store.exists(key).await
+	}
+
+	async fn del_rng(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
+		let deleted = self.store.del_rng(start, end).await?;
+		if deleted > 0 {
+			self.persist()?;
+		}
+		Ok(deleted)
+	}
+
+	async fn get_batch(&self, keys: Vec<String>) -> Result<Vec<Option<surrealdb_types::Value>>> {
+		self.store.get_batch(keys).await
+	}
+
+	async fn set_batch(&self, entries: Vec<(String, surrealdb_types::Value)>) -> Result<()> {
+		self.store.set_batch(entries).await?;
+		self.persist()
+	}
+
+	async fn del_batch(&self, keys: Vec<String>) -> Result<()> {
+		self.store.del_batch(keys).await?;
+		self.persist()
+	}
+
+	async fn keys(&self, start: Bound<String>, end: Bound<String>) -> Result<Vec<String>> {
+		self.store.keys(start, end).await
+	}
+
+	async fn values(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+	) -> Result<Vec<surrealdb_types::Value>> {
+		self.store.values(start, end).await
+	}
+
+	async fn entries(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+	) -> Result<Vec<(String, surrealdb_types::Value)>> {
+		self.store.entries(start, end).await
+	}
+
+	async fn count(&self, start: Bound<String>, end: Bound<String>) -> Result<u64> {
+		self.store.count(start, end).await
+	}
+
+	async fn scan(
+		&self,
+		start: Bound<String>,
+		end: Bound<String>,
+		limit: u64,
+		after: Option<String>,
+	) -> Result<(Vec<(String, surrealdb_types::Value)>, Option<String>)> {
+		self.store.scan(start, end, limit, after).await
+	}
 }
@@ -1,7 +1,15 @@
 pub mod capabilities;
+pub mod coerce;
 pub mod config;
 pub mod controller;
+pub mod engine;
 pub mod host;
+pub mod json;
 pub mod kv;
+pub mod mock;
 pub mod package;
-mod wasi_context;
+pub mod trap;
+pub mod wasi_context;
+
+pub use coerce::coerce;
+pub use surrealism_types::statement;
@@ -0,0 +1,281 @@
+//! A scriptable host for testing modules without a database.
+//!
+//! [`MockHost`] records every `sql` and `run` call a module makes, and answers them from
+//! expectations set up by the test. Calls that match no expectation fail, so a test notices
+//! when a module runs a query it did not expect.
+//!
+//! ```rust,ignore
+//! let mock = MockHost::new();
+//! mock.expect_sql("SELECT * FROM user").returns(Value::Array(Array::new()));
+//!
+//! let mut controller = runtime.new_controller(Box::new(mock.clone())).await?;
+//! controller.invoke(Some("list_users".into()), ()).await?;
+//!
+//! assert_eq!(mock.calls().len(), 1);
+//! mock.verify()?;
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::config::SurrealismConfig;
+use crate::host::InvocationContext;
+use crate::kv::{BTreeMapStore, KVStore};
+
+type SqlHandler =
+	Arc<dyn Fn(String, surrealdb_types::Object) -> Result<surrealdb_types::Value> + Send + Sync>;
+type RunHandler = Arc<
+	dyn Fn(String, Option<String>, Vec<surrealdb_types::Value>) -> Result<surrealdb_types::Value>
+		+ Send
+		+ Sync,
+>;
+
+/// A host call made by the module.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+	Sql {
+		query: String,
+		vars: surrealdb_types::Object,
+	},
+	Run {
+		fnc: String,
+		version: Option<String>,
+		args: Vec<surrealdb_types::Value>,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Matcher {
+	Sql(String),
+	Run(String),
+}
+
+#[derive(Debug, Clone)]
+enum Response {
+	Value(surrealdb_types::Value),
+	Error(String),
+}
+
+#[derive(Debug)]
+struct Expectation {
+	matcher: Matcher,
+	response: Response,
+	times: Option<usize>,
+	used: usize,
+}
+
+#[derive(Default)]
+struct MockState {
+	expectations: Vec<Expectation>,
+	calls: Vec<Call>,
+	sql: Option<SqlHandler>,
+	run: Option<RunHandler>,
+}
+
+/// A host for tests which records calls and answers them from scripted expectations.
+///
+/// Clones share the same expectations, call log and KV store, so a clone can be handed to
+/// the runtime while the original is kept for assertions.
+#[derive(Clone, Default)]
+pub struct MockHost {
+	state: Arc<Mutex<MockState>>,
+	kv: Arc<BTreeMapStore>,
+}
+
+/// An expectation being configured on a [`MockHost`].
+pub struct Expect {
+	state: Arc<Mutex<MockState>>,
+	index: usize,
+}
+
+impl Expect {
+	/// Answer matching calls with the given value. This is the default, returning `NONE`.
+	pub fn returns(self, value: surrealdb_types::Value) -> Self {
+		self.update(|expectation| expectation.response = Response::Value(value))
+	}
+
+	/// Fail matching calls with the given error message.
+	pub fn fails(self, message: impl Into<String>) -> Self {
+		let message = message.into();
+		self.update(|expectation| expectation.response = Response::Error(message))
+	}
+
+	/// Match at most `n` calls, after which later expectations for the same call are used.
+	/// Without a limit, the expectation matches any number of calls.
+	pub fn times(self, n: usize) -> Self {
+		self.update(|expectation| expectation.times = Some(n))
+	}
+
+	fn update(self, f: impl FnOnce(&mut Expectation)) -> Self {
+		f(&mut self.state.lock().unwrap_or_else(PoisonError::into_inner).expectations[self.index]);
+		self
+	}
+}
+
+impl MockHost {
+	/// Create a host with no expectations and an empty KV store.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Answer SQL queries which match no expectation with the given handler.
+	pub fn with_sql<F>(self, handler: F) -> Self
+	where
+		F: Fn(String, surrealdb_types::Object) -> Result<surrealdb_types::Value>
+			+ Send
+			+ Sync
+			+ 'static,
+	{
+		self.lock().sql = Some(Arc::new(handler));
+		self
+	}
+
+	/// Answer function calls which match no expectation with the given handler.
+	pub fn with_run<F>(self, handler: F) -> Self
+	where
+		F: Fn(
+				String,
+				Option<String>,
+				Vec<surrealdb_types::Value>,
+			) -> Result<surrealdb_types::Value>
+			+ Send
+			+ Sync
+			+ 'static,
+	{
+		self.lock().run = Some(Arc::new(handler));
+		self
+	}
+
+	/// Use the given KV store, for example to seed it or inspect it after a call.
+	pub fn with_kv(mut self, kv: Arc<BTreeMapStore>) -> Self {
+		self.kv = kv;
+		self
+	}
+
+	/// Expect the module to run exactly this SQL query.
+	pub fn expect_sql(&self, query: impl Into<String>) -> Expect {
+		self.expect(Matcher::Sql(query.into()))
+	}
+
+	/// Expect the module to run the named function.
+	pub fn expect_run(&self, fnc: impl Into<String>) -> Expect {
+		self.expect(Matcher::Run(fnc.into()))
+	}
+
+	/// The host calls made so far, in order.
+	pub fn calls(&self) -> Vec<Call> {
+		self.lock().calls.clone()
+	}
+
+	/// Check that every expectation was matched, and that limited ones were fully used.
+	pub fn verify(&self) -> Result<()> {
+		let state = self.lock();
+		for expectation in &state.expectations {
+			let satisfied = match expectation.times {
+				Some(times) => expectation.used == times,
+				None => expectation.used > 0,
+			};
+			if !satisfied {
+				anyhow::bail!(
+					"Expectation {:?} was matched {} time(s)",
+					expectation.matcher,
+					expectation.used
+				);
+			}
+		}
+		Ok(())
+	}
+
+	fn expect(&self, matcher: Matcher) -> Expect {
+		let mut state = self.lock();
+		state.expectations.push(Expectation {
+			matcher,
+			response: Response::Value(surrealdb_types::Value::None),
+			times: None,
+			used: 0,
+		});
+		let index = state.expectations.len() - 1;
+		drop(state);
+		Expect {
+			state: self.state.clone(),
+			index,
+		}
+	}
+
+	/// Lock the shared state. A panic while it was held, such as a failed assertion in a
+	/// handler, leaves the state intact, so a poisoned lock is recovered rather than ignored.
+	fn lock(&self) -> MutexGuard<'_, MockState> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Record a call and find the response of the first unexhausted matching expectation.
+	fn respond(&self, call: Call, matcher: Matcher) -> Result<Option<surrealdb_types::Value>> {
+		let mut state = self.lock();
+		state.calls.push(call);
+		let expectation = state.expectations.iter_mut().find(|expectation| {
+			expectation.matcher == matcher
+				&& expectation.times.is_none_or(|times| expectation.used < times)
+		});
+		match expectation {
+			Some(expectation) => {
+				expectation.used += 1;
+				match &expectation.response {
+					Response::Value(value) => Ok(Some(value.clone())),
+					Response::Error(message) => Err(anyhow::anyhow!("{message}")),
+				}
+			}
+			None => Ok(None),
+		}
+	}
+}
+
+#[async_trait]
+impl InvocationContext for MockHost {
+	async fn sql(
+		&mut self,
+		_config: &SurrealismConfig,
+		query: String,
+		vars: surrealdb_types::Object,
+	) -> Result<surrealdb_types::Value> {
+		let call = Call::Sql {
+			query: query.clone(),
+			vars: vars.clone(),
+		};
+		if let Some(value) = self.respond(call, Matcher::Sql(query.clone()))? {
+			return Ok(value);
+		}
+		let handler = self.lock().sql.clone();
+		match handler {
+			Some(handler) => handler(query, vars),
+			None => anyhow::bail!("Unexpected SQL query: {query}"),
+		}
+	}
+
+	async fn run(
+		&mut self,
+		_config: &SurrealismConfig,
+		fnc: String,
+		version: Option<String>,
+		args: Vec<surrealdb_types::Value>,
+	) -> Result<surrealdb_types::Value> {
+		let call = Call::Run {
+			fnc: fnc.clone(),
+			version: version.clone(),
+			args: args.clone(),
+		};
+		if let Some(value) = self.respond(call, Matcher::Run(fnc.clone()))? {
+			return Ok(value);
+		}
+		let handler = self.lock().run.clone();
+		match handler {
+			Some(handler) => handler(fnc, version, args),
+			None => anyhow::bail!("Unexpected function call: {fnc}"),
+		}
+	}
+
+	fn kv(&mut self) -> Result<&dyn KVStore> {
+		Ok(&*self.kv)
+	}
+}
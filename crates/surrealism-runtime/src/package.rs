@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, Read, Write};
 use std::path::PathBuf;
 
 use anyhow::Result;
@@ -31,6 +31,11 @@ impl SurrealismPackage {
 		SurrealismPackage::from_reader(archive_file)
 	}
 
+	/// Load a package held in memory, such as one fetched from object storage.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+		SurrealismPackage::from_reader(bytes)
+	}
+
 	pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
 		let zstd_decoder =
 			Decoder::new(BufReader::new(reader)).prefix_err(|| "Failed to create zstd decoder")?;
@@ -81,6 +86,11 @@ impl SurrealismPackage {
 		let config =
 			config.ok_or_else(|| anyhow::anyhow!("surrealism.toml not found in archive"))?;
 
+		// Packages built before checksums were recorded have no integrity section
+		if let Some(integrity) = &config.integrity {
+			integrity.verify(&wasm)?;
+		}
+
 		Ok(SurrealismPackage {
 			config,
 			wasm,
@@ -93,10 +103,22 @@ impl SurrealismPackage {
 			anyhow::bail!("Output file must have .surli extension");
 		}
 
-		// Create a new tar.zst archive
 		let file = File::create(&output).prefix_err(|| "Failed to create output file")?;
+		self.to_writer(file)
+	}
+
+	/// Serialize the package into memory, in the same format as [`SurrealismPackage::pack`].
+	pub fn to_bytes(&self) -> Result<Vec<u8>> {
+		let mut bytes = Vec::new();
+		self.to_writer(&mut bytes)?;
+		Ok(bytes)
+	}
+
+	/// Serialize the package to a writer, such as a stream uploading it to object storage.
+	pub fn to_writer<W: Write>(&self, writer: W) -> Result<()> {
+		// Create a new tar.zst archive
 		let encoder =
-			zstd::stream::Encoder::new(file, 0).prefix_err(|| "Failed to create zstd encoder")?;
+			zstd::stream::Encoder::new(writer, 0).prefix_err(|| "Failed to create zstd encoder")?;
 		let mut archive = tar::Builder::new(encoder);
 
 		// Add the WASM file
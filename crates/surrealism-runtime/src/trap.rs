@@ -0,0 +1,44 @@
+//! Explanations for WASM traps.
+//!
+//! When a module traps, wasmtime reports the trap code alongside a backtrace of unnamed WASM
+//! functions, which says little about what went wrong in the module's source. [`describe`]
+//! maps the common trap codes to an explanation of their likely cause, which
+//! [`Controller::try_invoke`](crate::controller::Controller::try_invoke) reports along with
+//! the name of the function which trapped.
+//!
+//! Rust checks integer division and array indexing itself, and panics rather than trapping,
+//! so in modules built with this crate most traps are panics. The other codes are reached by
+//! unsafe code, or by modules built from other languages.
+
+use wasmtime::Trap;
+
+/// Explain the likely cause of a trap, or `None` for a trap without a known explanation.
+pub fn describe(trap: Trap) -> Option<&'static str> {
+	let message = match trap {
+		Trap::UnreachableCodeReached => {
+			"the module panicked or reached unreachable code, a panic message is printed to stderr"
+		}
+		Trap::StackOverflow => {
+			"the module ran out of stack, likely from deep or unbounded recursion, the stack can be raised with max_wasm_stack"
+		}
+		Trap::MemoryOutOfBounds => {
+			"the module performed an out-of-bounds memory access, likely a pointer bug"
+		}
+		Trap::HeapMisaligned => "the module performed a misaligned atomic memory access",
+		Trap::TableOutOfBounds | Trap::IndirectCallToNull => {
+			"the module called an invalid function pointer"
+		}
+		Trap::BadSignature => "the module called a function pointer with the wrong signature",
+		Trap::IntegerDivisionByZero => "the module divided an integer by zero",
+		Trap::IntegerOverflow => {
+			"the module performed an integer division which overflowed, such as i64::MIN / -1"
+		}
+		Trap::BadConversionToInteger => {
+			"the module converted a NaN or out-of-range float to an integer"
+		}
+		Trap::Interrupt => "the call was interrupted before it finished",
+		Trap::OutOfFuel => "the module ran out of fuel before the call finished",
+		_ => return None,
+	};
+	Some(message)
+}
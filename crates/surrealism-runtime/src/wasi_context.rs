@@ -1,13 +1,97 @@
+//! WASI sandbox configuration for guest modules.
+//!
+//! By default a guest gets the most restrictive sandbox: stdout and stderr are inherited
+//! from the host process, but no environment variables, no arguments, and no filesystem
+//! access are exposed. Embedders opt in to anything more through [`WasiConfig`].
+
+use std::path::PathBuf;
+
 use anyhow::Result;
+use surrealism_types::err::PrefixError;
 use wasmtime_wasi::p2::WasiCtxBuilder;
 use wasmtime_wasi::preview1::WasiP1Ctx;
+use wasmtime_wasi::{DirPerms, FilePerms};
+
+/// Describes what a guest module may see of its host environment through WASI.
+#[derive(Debug, Clone, Default)]
+pub struct WasiConfig {
+	/// Environment variables exposed to the guest. Host variables are never inherited.
+	pub env: Vec<(String, String)>,
+	/// Command line arguments exposed to the guest.
+	pub args: Vec<String>,
+	/// Host directories made available to the guest.
+	pub preopened_dirs: Vec<PreopenedDir>,
+}
+
+/// A host directory exposed to the guest at `guest_path`.
+#[derive(Debug, Clone)]
+pub struct PreopenedDir {
+	pub host_path: PathBuf,
+	pub guest_path: String,
+	pub read_only: bool,
+}
+
+impl WasiConfig {
+	/// Create the default, most restrictive configuration.
+	pub fn new() -> Self {
+		Self::default()
+	}
 
-pub fn build() -> Result<WasiP1Ctx> {
+	/// Expose an environment variable with an explicit value.
+	pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.env.push((key.into(), value.into()));
+		self
+	}
+
+	/// Expose a host environment variable to the guest, if it is set on the host.
+	pub fn allow_env(self, key: impl Into<String>) -> Self {
+		let key = key.into();
+		match std::env::var(&key) {
+			Ok(value) => self.env(key, value),
+			Err(_) => self,
+		}
+	}
+
+	/// Append a command line argument.
+	pub fn arg(mut self, arg: impl Into<String>) -> Self {
+		self.args.push(arg.into());
+		self
+	}
+
+	/// Make a host directory available to the guest.
+	pub fn preopened_dir(
+		mut self,
+		host_path: impl Into<PathBuf>,
+		guest_path: impl Into<String>,
+		read_only: bool,
+	) -> Self {
+		self.preopened_dirs.push(PreopenedDir {
+			host_path: host_path.into(),
+			guest_path: guest_path.into(),
+			read_only,
+		});
+		self
+	}
+}
+
+pub(crate) fn build(config: &WasiConfig) -> Result<WasiP1Ctx> {
 	// Note: stdout/stderr would need to access context from StoreData
 	// For now, inherit from parent process
-	let ctx = WasiCtxBuilder::new().inherit_stdout().inherit_stderr().inherit_env().build_p1();
+	let mut builder = WasiCtxBuilder::new();
+	builder.inherit_stdout().inherit_stderr().envs(&config.env).args(&config.args);
+
+	for dir in &config.preopened_dirs {
+		let (dir_perms, file_perms) = if dir.read_only {
+			(DirPerms::READ, FilePerms::READ)
+		} else {
+			(DirPerms::all(), FilePerms::all())
+		};
+		builder
+			.preopened_dir(&dir.host_path, &dir.guest_path, dir_perms, file_perms)
+			.prefix_err(|| format!("Failed to preopen directory {}", dir.host_path.display()))?;
+	}
 
-	Ok(ctx)
+	Ok(builder.build_p1())
 }
 
 // TODO: Custom stdout/stderr that access context from StoreData
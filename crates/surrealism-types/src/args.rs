@@ -17,11 +17,44 @@
 //! - `Vec<T>` for variadic arguments
 //! - `()` for zero arguments
 //!
+//! # Date and Time Arguments
+//!
+//! Any type implementing [`SurrealValue`] can be used as an argument, including
+//! `chrono::DateTime<chrono::Utc>` (as `datetime`) and `std::time::Duration` (as
+//! `duration`). Both are carried with nanosecond precision, so values round-trip exactly
+//! between the host and the guest.
+//!
+//! # Decimal Arguments
+//!
+//! [`surrealdb_types::Decimal`] maps to the `decimal` kind and is carried losslessly, so
+//! a value written as `1.5dec` arrives on the guest with its exact scale. The runtime
+//! coerces other numbers (such as the float `1.5` or the int `2`) to a declared `decimal`
+//! when they can be represented exactly.
+//!
+//! # Binary Arguments
+//!
+//! Binary data is passed as `bytes::Bytes` or [`surrealdb_types::Bytes`], which map to
+//! the `bytes` kind. `Vec<u8>` does not implement [`SurrealValue`], as it would be
+//! ambiguous between `bytes` and an array of numbers, but the `#[surrealism]` macro passes
+//! `Vec<u8>` arguments and return values as `bytes` (see [`crate::binary`]). Use `Vec<i64>`
+//! when an array of numbers is intended.
+//! A fixed-length buffer, such as `[u8; 32]` for a hash, is also passed as `bytes`, and must
+//! have exactly that many bytes (see [`crate::array`]).
+//!
+//! # Union Arguments
+//!
+//! An argument accepting several kinds can be declared with [`surrealdb_types::Either2`]
+//! through `Either10`, which are described as a union such as `int | string`. Incoming
+//! values are matched against the variants in declaration order, so the first variant whose
+//! kind accepts the value is chosen.
+//!
 //! [`SurrealValue`]: surrealdb_types::SurrealValue
 
 use anyhow::Result;
 use surrealdb_types::SurrealValue;
 
+use crate::err::InvalidArgs;
+
 /// Trait for marshalling function arguments to and from [`surrealdb_types::Value`] vectors.
 ///
 /// This trait enables type-safe function signatures while maintaining a language-agnostic
@@ -74,7 +107,7 @@ pub trait Args: Sized {
 	/// # Errors
 	///
 	/// Returns an error if:
-	/// - The number of values doesn't match the expected argument count
+	/// - The number of values doesn't match the expected argument count, with [`InvalidArgs`]
 	/// - Any value cannot be converted to its expected type
 	///
 	/// # Example
@@ -117,7 +150,7 @@ macro_rules! impl_args {
 
                 fn from_values(values: Vec<surrealdb_types::Value>) -> Result<Self> {
                     if values.len() != $len {
-                        return Err(anyhow::anyhow!("Expected ({}), found other arguments", Self::kinds().iter().map(|k| k.to_string()).collect::<Vec<String>>().join(", ")));
+                        return Err(InvalidArgs { expected: $len, found: values.len() }.into());
                     }
 
                     let mut values = values;
@@ -160,10 +193,11 @@ impl Args for () {
 
 	fn from_values(values: Vec<surrealdb_types::Value>) -> Result<Self> {
 		if !values.is_empty() {
-			return Err(anyhow::anyhow!(
-				"Expected ({}), found other arguments",
-				Self::kinds().iter().map(|k| k.to_string()).collect::<Vec<String>>().join(", ")
-			));
+			return Err(InvalidArgs {
+				expected: 0,
+				found: values.len(),
+			}
+			.into());
 		}
 
 		Ok(())
@@ -0,0 +1,100 @@
+//! Fixed-length arrays.
+//!
+//! `SurrealValue` is not implemented for `[T; N]`, so this module provides [`ArrayOf`], an
+//! array of exactly `N` values described as `array<T, N>`, and [`BytesOf`], a buffer of
+//! exactly `N` bytes described as `bytes`. Values of any other length are rejected when
+//! received, rather than being truncated or padded.
+//!
+//! Functions may also take and return a `[T; N]` directly, which the `#[surrealism]` macro
+//! passes as an `ArrayOf<T, N>`, or as a `BytesOf<N>` for `[u8; N]`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[surrealism]
+//! fn verify(hash: [u8; 32]) -> bool {
+//!     // ...
+//! }
+//! ```
+
+use surrealdb_types::{ConversionError, Kind, SurrealValue, Value};
+
+/// An array of exactly `N` values, described as `array<T, N>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArrayOf<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> From<[T; N]> for ArrayOf<T, N> {
+	fn from(array: [T; N]) -> Self {
+		ArrayOf(array)
+	}
+}
+
+impl<T: SurrealValue, const N: usize> SurrealValue for ArrayOf<T, N> {
+	fn kind_of() -> Kind {
+		Kind::Array(Box::new(T::kind_of()), Some(N as u64))
+	}
+
+	fn is_value(value: &Value) -> bool {
+		match value {
+			Value::Array(array) => array.len() == N && array.iter().all(T::is_value),
+			_ => false,
+		}
+	}
+
+	fn into_value(self) -> Value {
+		Value::Array(self.0.into_iter().map(T::into_value).collect::<Vec<_>>().into())
+	}
+
+	fn from_value(value: Value) -> anyhow::Result<Self> {
+		let values = match value {
+			Value::Array(array) => array.into_inner(),
+			value => return Err(ConversionError::from_value(Self::kind_of(), &value).into()),
+		};
+		let len = values.len();
+		let values = values
+			.into_iter()
+			.map(T::from_value)
+			.collect::<anyhow::Result<Vec<T>>>()
+			.map_err(|e| anyhow::anyhow!("Failed to convert to {}: {}", Self::kind_of(), e))?;
+		values
+			.try_into()
+			.map(ArrayOf)
+			.map_err(|_| anyhow::anyhow!("Expected an array of {N} elements, found {len}"))
+	}
+}
+
+/// A buffer of exactly `N` bytes, described as `bytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BytesOf<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> From<[u8; N]> for BytesOf<N> {
+	fn from(bytes: [u8; N]) -> Self {
+		BytesOf(bytes)
+	}
+}
+
+impl<const N: usize> SurrealValue for BytesOf<N> {
+	fn kind_of() -> Kind {
+		Kind::Bytes
+	}
+
+	fn is_value(value: &Value) -> bool {
+		matches!(value, Value::Bytes(bytes) if bytes.len() == N)
+	}
+
+	fn into_value(self) -> Value {
+		Value::Bytes(self.0.to_vec().into())
+	}
+
+	fn from_value(value: Value) -> anyhow::Result<Self> {
+		match value {
+			Value::Bytes(bytes) => {
+				let len = bytes.len();
+				<[u8; N]>::try_from(&bytes[..])
+					.map(BytesOf)
+					.map_err(|_| anyhow::anyhow!("Expected {N} bytes, found {len}"))
+			}
+			value => Err(ConversionError::from_value(Self::kind_of(), &value).into()),
+		}
+	}
+}
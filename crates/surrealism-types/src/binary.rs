@@ -0,0 +1,40 @@
+//! Byte vectors.
+//!
+//! `SurrealValue` is not implemented for `Vec<u8>`, as it would be ambiguous between `bytes`
+//! and an array of numbers, so this module provides [`ByteVec`], a vector of bytes described
+//! as `bytes`. Functions may also take and return a `Vec<u8>` directly, which the
+//! `#[surrealism]` macro passes as a `ByteVec`. An array of numbers is declared as `Vec<i64>`
+//! instead.
+
+use surrealdb_types::{ConversionError, Kind, SurrealValue, Value};
+
+/// A vector of bytes, described as `bytes`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ByteVec(pub Vec<u8>);
+
+impl From<Vec<u8>> for ByteVec {
+	fn from(bytes: Vec<u8>) -> Self {
+		ByteVec(bytes)
+	}
+}
+
+impl SurrealValue for ByteVec {
+	fn kind_of() -> Kind {
+		Kind::Bytes
+	}
+
+	fn is_value(value: &Value) -> bool {
+		matches!(value, Value::Bytes(_))
+	}
+
+	fn into_value(self) -> Value {
+		Value::Bytes(self.0.into())
+	}
+
+	fn from_value(value: Value) -> anyhow::Result<Self> {
+		match value {
+			Value::Bytes(bytes) => Ok(ByteVec(bytes[..].to_vec())),
+			value => Err(ConversionError::from_value(Self::kind_of(), &value).into()),
+		}
+	}
+}
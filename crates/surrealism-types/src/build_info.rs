@@ -0,0 +1,21 @@
+//! Which build of a module is running.
+//!
+//! Modules export `__sr_build_info`, which describes the version of the crate they were built
+//! from, the version of `surrealism` they were built against, and when they were built, so
+//! that hosts can tell which build of a module is deployed.
+
+use surrealdb_types::SurrealValue;
+
+/// The build of a module, described as `{ crate_version, surrealism_version, built_at }`.
+#[derive(Debug, Clone, PartialEq, SurrealValue)]
+pub struct BuildInfo {
+	/// The version of the crate the module was built from, or `None` if it declares no
+	/// `#[surrealism]` functions.
+	pub crate_version: Option<String>,
+	/// The version of the `surrealism` crate the module was built against.
+	pub surrealism_version: String,
+	/// When the module was last compiled, as an RFC 3339 UTC timestamp, or `None` if it
+	/// declares no `#[surrealism]` functions. Set from `SOURCE_DATE_EPOCH` when it is defined,
+	/// for reproducible builds.
+	pub built_at: Option<String>,
+}
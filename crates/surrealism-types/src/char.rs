@@ -0,0 +1,51 @@
+//! Single characters.
+//!
+//! `SurrealValue` is not implemented for `char`, so this module provides [`Char`], a single
+//! character transferred as a one-character string. Functions may also take and return a
+//! `char` directly, which the `#[surrealism]` macro passes as a `Char`.
+
+use surrealdb_types::{ConversionError, Kind, SurrealValue, Value};
+
+/// A single character, described as `string`.
+///
+/// When converting from a value, only a string of exactly one character is accepted. The
+/// length is counted in Unicode scalar values, so `"é"` is accepted but `"ab"` and `""` are
+/// not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Char(pub char);
+
+impl From<char> for Char {
+	fn from(c: char) -> Self {
+		Char(c)
+	}
+}
+
+impl SurrealValue for Char {
+	fn kind_of() -> Kind {
+		Kind::String
+	}
+
+	fn is_value(value: &Value) -> bool {
+		matches!(value, Value::String(s) if s.chars().count() == 1)
+	}
+
+	fn into_value(self) -> Value {
+		Value::String(self.0.to_string())
+	}
+
+	fn from_value(value: Value) -> anyhow::Result<Self> {
+		match value {
+			Value::String(s) => {
+				let mut chars = s.chars();
+				match (chars.next(), chars.next()) {
+					(Some(c), None) => Ok(Char(c)),
+					_ => Err(anyhow::anyhow!(
+						"Expected a single character, found a string of {} characters",
+						s.chars().count()
+					)),
+				}
+			}
+			value => Err(ConversionError::from_value(Self::kind_of(), &value).into()),
+		}
+	}
+}
@@ -216,4 +216,16 @@ pub trait AsyncMemoryController: Send {
 	///
 	/// May panic if the pointer or length are out of bounds.
 	fn mut_mem(&mut self, ptr: u32, len: u32) -> Result<&mut [u8]>;
+
+	/// The largest payload, in bytes, accepted when receiving a value from the guest, or
+	/// `None` for no limit. The length is checked before any data is copied out of WASM memory.
+	fn max_transfer_bytes(&self) -> Option<u32> {
+		None
+	}
+
+	/// The most elements accepted in an array received from the guest, or `None` for no
+	/// limit. The declared count is checked before any elements are allocated.
+	fn max_array_len(&self) -> Option<u32> {
+		None
+	}
 }
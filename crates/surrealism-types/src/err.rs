@@ -1,7 +1,9 @@
 //! Error handling utilities for adding context to errors.
 //!
 //! This module provides the [`PrefixError`] trait, which extends [`Result`] and [`Option`]
-//! with a method to add contextual prefixes to error messages.
+//! with a method to add contextual prefixes to error messages, [`SqlError`], which
+//! categorises errors returned by SQL queries, [`InvalidArgs`], for functions called with
+//! the wrong number of arguments, and [`MemoryError`], for allocations which failed.
 
 use anyhow::Result;
 
@@ -70,3 +72,153 @@ impl<T> PrefixError<T> for Option<T> {
 		self.ok_or_else(|| anyhow::anyhow!(format!("{}: None", prefix())))
 	}
 }
+
+/// The category of a failed SQL query, for matching on common failures.
+///
+/// Errors cross the host boundary as their message, so the category is derived from the
+/// message text of the underlying SurrealDB error. Messages which match no known category
+/// are [`SqlErrorKind::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SqlErrorKind {
+	/// A record or unique index entry already exists.
+	AlreadyExists,
+	/// A namespace, database, table or other resource does not exist.
+	NotFound,
+	/// The query could not be parsed.
+	Parse,
+	/// A value did not match a field's type or failed an assertion.
+	Validation,
+	/// The query was not permitted.
+	Permission,
+	/// The transaction conflicted with another and could not be committed.
+	Conflict,
+	/// The query exceeded its timeout or was cancelled.
+	Timeout,
+	/// Any other failure.
+	Other,
+}
+
+impl SqlErrorKind {
+	/// Categorise a SurrealDB error message.
+	pub fn from_message(message: &str) -> Self {
+		let message = message.to_lowercase();
+		let contains = |patterns: &[&str]| patterns.iter().any(|p| message.contains(p));
+
+		if contains(&["already exists", "already contains"]) {
+			Self::AlreadyExists
+		} else if contains(&["parse error", "failed to parse"]) {
+			Self::Parse
+		} else if contains(&["does not exist", "not found"]) {
+			Self::NotFound
+		} else if contains(&["permission", "not allowed", "iam error"]) {
+			Self::Permission
+		} else if contains(&["for field", "field must conform", "assertion"]) {
+			Self::Validation
+		} else if contains(&["conflict"]) {
+			Self::Conflict
+		} else if contains(&["timeout", "timed out", "cancelled"]) {
+			Self::Timeout
+		} else {
+			Self::Other
+		}
+	}
+}
+
+/// An error returned by the host for a failed SQL query.
+///
+/// The full message is kept as returned by the host. Guest code can recover this type
+/// from an [`anyhow::Error`] with `downcast_ref` to match on its [`kind`](Self::kind):
+///
+/// ```rust,ignore
+/// match sql::<_, Value>("CREATE user:1") {
+///     Err(e) if e.downcast_ref::<SqlError>().is_some_and(|e| e.kind() == SqlErrorKind::AlreadyExists) => {
+///         // the user was created before
+///     }
+///     result => result?,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlError {
+	kind: SqlErrorKind,
+	message: String,
+}
+
+impl SqlError {
+	/// Create an error from a message, categorising it with [`SqlErrorKind::from_message`].
+	pub fn new(message: impl Into<String>) -> Self {
+		let message = message.into();
+		Self {
+			kind: SqlErrorKind::from_message(&message),
+			message,
+		}
+	}
+
+	/// The category of the failure.
+	pub fn kind(&self) -> SqlErrorKind {
+		self.kind
+	}
+
+	/// The full error message returned by the host.
+	pub fn message(&self) -> &str {
+		&self.message
+	}
+}
+
+impl std::fmt::Display for SqlError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.message)
+	}
+}
+
+impl std::error::Error for SqlError {}
+
+/// An error for a function called with the wrong number of arguments.
+///
+/// Returned by [`Args::from_values`](crate::args::Args::from_values), and recoverable from an
+/// [`anyhow::Error`] with `downcast_ref`, like [`SqlError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidArgs {
+	/// The number of arguments the function takes.
+	pub expected: usize,
+	/// The number of arguments it was called with.
+	pub found: usize,
+}
+
+impl std::fmt::Display for InvalidArgs {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let plural = if self.expected == 1 {
+			""
+		} else {
+			"s"
+		};
+		write!(f, "Expected {} argument{plural}, got {}", self.expected, self.found)
+	}
+}
+
+impl std::error::Error for InvalidArgs {}
+
+/// An error for a failed allocation in the guest.
+///
+/// Returned by the guest and host memory controllers when `__sr_alloc` fails, and recoverable
+/// from an [`anyhow::Error`] with `downcast_ref`, like [`InvalidArgs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryError {
+	/// The module ran out of memory, such as on reaching the host's memory limit, while
+	/// allocating `len` bytes.
+	OutOfMemory {
+		len: u32,
+	},
+}
+
+impl std::fmt::Display for MemoryError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			MemoryError::OutOfMemory {
+				len,
+			} => write!(f, "The module ran out of memory allocating {len} bytes"),
+		}
+	}
+}
+
+impl std::error::Error for MemoryError {}
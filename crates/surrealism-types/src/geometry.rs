@@ -0,0 +1,19 @@
+//! Geometry types described by their subtype.
+//!
+//! [`surrealdb_types::Geometry`] accepts any geometry, so it is described as a bare
+//! `geometry`. The types re-exported here each hold a single kind of geometry, and are
+//! described with their subtype, such as `geometry<point>` or `geometry<polygon>`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use surrealism::types::geometry::{Point, Polygon};
+//!
+//! #[surrealism]
+//! fn contains(area: Polygon, point: Point) -> bool {
+//!     // ...
+//! }
+//! ```
+
+pub use geo_types::{LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Polygon};
+pub use surrealdb_types::{Geometry, GeometryKind};
@@ -0,0 +1,59 @@
+//! 128-bit integers.
+//!
+//! SurrealDB numbers are 64-bit integers, floats or decimals, none of which hold every `u128`
+//! or `i128` exactly. This module provides [`U128`] and [`I128`], which are transferred as a
+//! string of decimal digits, such as `'340282366920938463463374607431768211455'`, so that any
+//! value round-trips losslessly. They are described as `string`.
+//!
+//! As they are strings on the SurrealDB side, these values do not behave as numbers there:
+//! they sort and compare as text, and arithmetic in a query does not apply to them. They suit
+//! values which are only stored and passed around, such as hashes or identifiers.
+//!
+//! Functions may also take and return a `u128` or `i128` directly, which the `#[surrealism]`
+//! macro passes as a `U128` or `I128`.
+
+use surrealdb_types::{ConversionError, Kind, SurrealValue, Value};
+
+macro_rules! impl_int128 {
+	($($name:ident => $int:ty),+ $(,)?) => {
+		$(
+			#[doc = concat!("A `", stringify!($int), "`, transferred as a string of decimal digits.")]
+			#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+			pub struct $name(pub $int);
+
+			impl From<$int> for $name {
+				fn from(value: $int) -> Self {
+					$name(value)
+				}
+			}
+
+			impl SurrealValue for $name {
+				fn kind_of() -> Kind {
+					Kind::String
+				}
+
+				fn is_value(value: &Value) -> bool {
+					matches!(value, Value::String(s) if s.parse::<$int>().is_ok())
+				}
+
+				fn into_value(self) -> Value {
+					Value::String(self.0.to_string())
+				}
+
+				fn from_value(value: Value) -> anyhow::Result<Self> {
+					match value {
+						Value::String(s) => s.parse().map($name).map_err(|e| {
+							anyhow::anyhow!(concat!("Expected a ", stringify!($int), " in '{}': {}"), s, e)
+						}),
+						value => Err(ConversionError::from_value(Self::kind_of(), &value).into()),
+					}
+				}
+			}
+		)+
+	};
+}
+
+impl_int128! {
+	U128 => u128,
+	I128 => i128,
+}
@@ -0,0 +1,113 @@
+//! Conversion between JSON and SurrealDB values, and the [`Serde`] wrapper which passes
+//! `serde` types across the boundary through their JSON form. Enabled by the `serde` feature.
+//!
+//! JSON has fewer types than SurrealQL, so values are converted as follows:
+//!
+//! - Integers which fit in an `i64` become ints, and any other number becomes a float. Ints
+//!   are coerced to a declared `float` or `decimal` as usual when a function is invoked.
+//! - Strings, booleans, `null`, arrays and objects convert to their SurrealQL counterpart,
+//!   recursively.
+//!
+//! The conversion back to JSON is lossy for values without a JSON counterpart:
+//!
+//! - `NONE` and `NULL` both become `null`, and sets become arrays.
+//! - Decimals become strings, so that their precision is kept, and floats which are NaN or
+//!   infinite become `null`.
+//! - Any other value, such as a record id, datetime, duration, uuid, geometry or bytes,
+//!   becomes a string of its SurrealQL representation, such as `user:tobie` or `1h`.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use surrealdb_types::{Array, Kind, Number, Object, SurrealValue, ToSql, Value};
+
+/// Convert a JSON value into a SurrealDB value.
+pub fn from_json(value: serde_json::Value) -> Value {
+	match value {
+		serde_json::Value::Null => Value::Null,
+		serde_json::Value::Bool(v) => Value::Bool(v),
+		serde_json::Value::Number(v) => match v.as_i64() {
+			Some(v) => Value::Number(Number::Int(v)),
+			None => Value::Number(Number::Float(v.as_f64().unwrap_or(f64::NAN))),
+		},
+		serde_json::Value::String(v) => Value::String(v),
+		serde_json::Value::Array(values) => {
+			Value::Array(Array::from(values.into_iter().map(from_json).collect::<Vec<_>>()))
+		}
+		serde_json::Value::Object(entries) => Value::Object(Object::from_iter(
+			entries.into_iter().map(|(key, value)| (key, from_json(value))),
+		)),
+	}
+}
+
+/// Convert a SurrealDB value into a JSON value, as described in the [module docs](self).
+pub fn to_json(value: Value) -> serde_json::Value {
+	match value {
+		Value::None | Value::Null => serde_json::Value::Null,
+		Value::Bool(v) => serde_json::Value::Bool(v),
+		Value::Number(Number::Int(v)) => serde_json::Value::from(v),
+		Value::Number(Number::Float(v)) => serde_json::Number::from_f64(v)
+			.map(serde_json::Value::Number)
+			.unwrap_or(serde_json::Value::Null),
+		Value::Number(Number::Decimal(v)) => serde_json::Value::String(v.to_string()),
+		Value::String(v) => serde_json::Value::String(v),
+		Value::Array(array) => {
+			serde_json::Value::Array(array.into_inner().into_iter().map(to_json).collect())
+		}
+		Value::Set(set) => serde_json::Value::Array(set.into_iter().map(to_json).collect()),
+		Value::Object(object) => serde_json::Value::Object(
+			object.into_iter().map(|(key, value)| (key, to_json(value))).collect(),
+		),
+		value => serde_json::Value::String(value.to_sql()),
+	}
+}
+
+/// Convert a `serde` type into a SurrealDB value through its JSON form.
+///
+/// # Errors
+/// If the value fails to serialize, such as a map with keys which are not strings.
+pub fn serialize<T: Serialize>(value: &T) -> anyhow::Result<Value> {
+	serde_json::to_value(value)
+		.map(from_json)
+		.map_err(|e| anyhow::anyhow!("Failed to serialize value: {e}"))
+}
+
+/// A `serde` type, passed across the boundary through its JSON form and described as `any`.
+///
+/// Structs become objects keyed by field name, unit enum variants become strings, and other
+/// variants become an object with a single key, following `serde_json`. Values received from
+/// SurrealDB are mapped to JSON as described in the [module docs](self), so a datetime or
+/// record id field is received as a string of its SurrealQL representation.
+///
+/// Functions marked `#[surrealism(serde)]` take their arguments through this wrapper, so their
+/// types only need to derive `Serialize` and `Deserialize`. Their results are converted with
+/// [`serialize`], so that a value which fails to serialize fails the call.
+///
+/// # Panics
+///
+/// Converting into a value panics if `T` fails to serialize, such as a map with keys which
+/// are not strings. Use [`serialize`] to handle the error instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Serde<T>(pub T);
+
+impl<T: Serialize + DeserializeOwned> SurrealValue for Serde<T> {
+	fn kind_of() -> Kind {
+		Kind::Any
+	}
+
+	fn is_value(value: &Value) -> bool {
+		serde_json::from_value::<T>(to_json(value.clone())).is_ok()
+	}
+
+	fn into_value(self) -> Value {
+		match serialize(&self.0) {
+			Ok(value) => value,
+			Err(e) => panic!("{e}"),
+		}
+	}
+
+	fn from_value(value: Value) -> anyhow::Result<Self> {
+		serde_json::from_value(to_json(value))
+			.map(Serde)
+			.map_err(|e| anyhow::anyhow!("Failed to deserialize value: {e}"))
+	}
+}
@@ -16,6 +16,8 @@
 //!
 //! - `host`: Enables async traits for host-side (runtime) implementations. Without this flag, all
 //!   operations are synchronous, suitable for WASM guest modules.
+//! - `serde`: Enables the `json` module, converting between JSON and SurrealDB values and
+//!   passing `serde` types across the boundary through their JSON form.
 //!
 //! ## Dual-Mode Architecture
 //!
@@ -53,17 +55,83 @@
 /// Wrapper type for function arguments that implement [`surrealdb_types::SurrealValue`].
 pub mod arg;
 
+/// The crate version, `surrealism` version and build time of a module.
+pub mod build_info;
+
+/// Fixed-length arrays and byte buffers, described as `array<T, N>` and `bytes`.
+pub mod array;
+
 /// Traits for marshalling function arguments to and from [`surrealdb_types::Value`] vectors.
 pub mod args;
 
+/// Byte vectors, described as `bytes`.
+pub mod binary;
+
+/// Single characters, transferred as a one-character string.
+pub mod char;
+
 /// Memory management abstractions for WASM linear memory allocation and deallocation.
 pub mod controller;
 
 /// Error handling utilities for adding context to errors.
 pub mod err;
 
+/// Geometry types described by their subtype, such as `geometry<point>`.
+pub mod geometry;
+
+/// 128-bit integers, transferred losslessly as strings of decimal digits.
+pub mod int128;
+
+/// Conversion between JSON and SurrealDB values, and `serde` types passed through JSON.
+#[cfg(feature = "serde")]
+pub mod json;
+
+/// Log levels for structured logging from WASM modules.
+pub mod log;
+
+/// Typed access to the fields of an object.
+pub mod object;
+
+/// The status and output of each statement in a SQL script.
+pub mod query;
+
+/// Record ids constrained to a table, described as `record<table>`.
+pub mod record;
+
+/// Typed set values which keep set semantics across the WASM boundary.
+pub mod set;
+
 /// Core serialization traits and implementations for the binary wire format.
 pub mod serialize;
 
+/// Statement detection for SurrealQL queries, without a parser.
+pub mod statement;
+
 /// Memory transfer traits for moving data across WASM boundaries.
 pub mod transfer;
+
+/// The version of the ABI between guest modules and the host, covering the exports,
+/// imports and wire format they rely on. Guests export it through `__sr_abi_version`, and
+/// the host refuses to load modules built against another version.
+pub const ABI_VERSION: u32 = 1;
+
+/// The most random bytes a host returns from one call to `__sr_random`. Guests request
+/// larger buffers in chunks of this size, and the host rejects longer requests rather than
+/// allocating whatever length a module asks for.
+pub const MAX_RANDOM_BYTES: u64 = 64 * 1024;
+
+/// The largest file a module may read through `__sr_fs_read`. The host rejects larger files
+/// rather than transferring whatever a module asks for into its memory.
+pub const MAX_FS_READ_BYTES: u64 = 16 * 1024 * 1024;
+
+/// A number which may be an `int`, `float` or `decimal`, described as `number`.
+///
+/// Accepting a [`Number`] lets a function take any numeric argument without it being coerced
+/// to a single kind.
+pub use surrealdb_types::Number;
+
+/// Any SurrealDB value, described as `any`.
+///
+/// Returning a [`Value`] lets a function decide at runtime which kind of value it returns,
+/// such as a number for some arguments and a string for others.
+pub use surrealdb_types::Value;
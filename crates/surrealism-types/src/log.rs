@@ -0,0 +1,66 @@
+//! Log levels for structured logging from WASM modules.
+//!
+//! Modules log through the host rather than writing to stdout or stderr, so that hosts can
+//! route the records into their own logging or telemetry, filtered by [`LogLevel`].
+
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::serialize::{Serializable, Serialized};
+
+/// The severity of a log record, from most to least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+	Error,
+	Warn,
+	Info,
+	Debug,
+}
+
+impl LogLevel {
+	/// The lowercase name of the level, as used in log output.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Error => "error",
+			Self::Warn => "warn",
+			Self::Info => "info",
+			Self::Debug => "debug",
+		}
+	}
+}
+
+impl fmt::Display for LogLevel {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// [`LogLevel`] serialization.
+///
+/// Wire format: 1 byte (0 = error, 1 = warn, 2 = info, 3 = debug)
+/// ```text
+/// [1 byte: 0x00..=0x03]
+/// ```
+impl Serializable for LogLevel {
+	fn serialize(self) -> Result<Serialized> {
+		let tag: u8 = match self {
+			Self::Error => 0,
+			Self::Warn => 1,
+			Self::Info => 2,
+			Self::Debug => 3,
+		};
+		Ok(Serialized(vec![tag].into()))
+	}
+
+	fn deserialize(serialized: Serialized) -> Result<Self> {
+		match serialized.0.first() {
+			Some(0) => Ok(Self::Error),
+			Some(1) => Ok(Self::Warn),
+			Some(2) => Ok(Self::Info),
+			Some(3) => Ok(Self::Debug),
+			Some(tag) => Err(anyhow::anyhow!("Invalid log level byte: {tag}")),
+			None => Err(anyhow::anyhow!("Missing log level byte")),
+		}
+	}
+}
@@ -0,0 +1,44 @@
+//! Typed access to object fields.
+//!
+//! A function taking an [`Object`] receives each field as a generic [`Value`]. When only a
+//! few fields are needed, [`ObjectExt::get_typed`] reads a single field into a type, without
+//! declaring a struct for the whole object.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use surrealism::types::object::ObjectExt;
+//!
+//! #[surrealism]
+//! fn greet(user: Object) -> Result<String> {
+//!     let name: String = user.get_typed("name")?.unwrap_or_default();
+//!     let age: Option<i64> = user.get_typed("age")?;
+//!     // ...
+//! }
+//! ```
+
+use surrealdb_types::{Object, SurrealValue, Value};
+
+/// Extension methods for [`Object`].
+pub trait ObjectExt {
+	/// Read the field `key` as a `T`, or `None` if the object has no such field.
+	///
+	/// A field holding `NONE` is treated as missing, as SurrealDB does not keep `NONE` fields
+	/// in objects. The field is cloned, so the object can be read again afterwards.
+	///
+	/// # Errors
+	/// If the field cannot be converted into `T`. The error names the field and the kind
+	/// it was expected to be.
+	fn get_typed<T: SurrealValue>(&self, key: &str) -> anyhow::Result<Option<T>>;
+}
+
+impl ObjectExt for Object {
+	fn get_typed<T: SurrealValue>(&self, key: &str) -> anyhow::Result<Option<T>> {
+		match self.get(key) {
+			None | Some(Value::None) => Ok(None),
+			Some(value) => T::from_value(value.clone()).map(Some).map_err(|e| {
+				anyhow::anyhow!("Failed to read field '{key}' as {}: {e}", T::kind_of())
+			}),
+		}
+	}
+}
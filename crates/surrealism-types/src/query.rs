@@ -0,0 +1,63 @@
+//! The result of each statement in a SQL script.
+//!
+//! SurrealDB runs every statement of a script and reports each one separately, with its own
+//! status, so a later statement can fail while the earlier ones succeed. [`QueryResult`]
+//! carries one such report across the WASM boundary, letting a module find which statement
+//! in a batch failed and still read the results of the others.
+
+use surrealdb_types::{Duration, SurrealValue, ToSql, Value};
+
+use crate::err::SqlError;
+
+/// The status of a statement which succeeded.
+pub const STATUS_OK: &str = "OK";
+
+/// The status of a statement which failed.
+pub const STATUS_ERR: &str = "ERR";
+
+/// The result of a single statement, described as `{ status, time, result }`.
+#[derive(Debug, Clone, SurrealValue)]
+pub struct QueryResult {
+	/// [`STATUS_OK`] if the statement succeeded, or [`STATUS_ERR`] if it failed.
+	pub status: String,
+	/// How long the statement took to run, if the host measured it.
+	pub time: Option<Duration>,
+	/// The output of the statement, or its error message if it failed.
+	pub result: Value,
+}
+
+impl QueryResult {
+	/// The result of a statement which succeeded with `result`.
+	pub fn ok(result: Value) -> Self {
+		Self {
+			status: STATUS_OK.to_string(),
+			time: None,
+			result,
+		}
+	}
+
+	/// The result of a statement which failed with `message`.
+	pub fn err(message: impl Into<String>) -> Self {
+		Self {
+			status: STATUS_ERR.to_string(),
+			time: None,
+			result: Value::String(message.into()),
+		}
+	}
+
+	/// Whether the statement succeeded.
+	pub fn is_ok(&self) -> bool {
+		self.status == STATUS_OK
+	}
+
+	/// The output of the statement, or a [`SqlError`] carrying its error message.
+	pub fn into_result(self) -> Result<Value, SqlError> {
+		if self.is_ok() {
+			return Ok(self.result);
+		}
+		match self.result {
+			Value::String(message) => Err(SqlError::new(message)),
+			other => Err(SqlError::new(other.to_sql())),
+		}
+	}
+}
@@ -0,0 +1,126 @@
+//! Record ids constrained to a table.
+//!
+//! [`surrealdb_types::RecordId`] is described as a bare `record`, so the table a function
+//! accepts or returns is lost from its signature. This module provides [`Record`], a record
+//! id tied to a table through a [`TableName`] marker, which is described as `record<table>`.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use surrealdb_types::{ConversionError, Kind, RecordId, RecordIdKey, SurrealValue, Table, Value};
+
+/// Marker for a table, used to constrain a [`Record`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use surrealism_types::record::{Record, TableName};
+///
+/// struct User;
+///
+/// impl TableName for User {
+///     const NAME: &'static str = "user";
+/// }
+///
+/// #[surrealism]
+/// fn owner(id: Record<User>) -> Record<User> {
+///     id
+/// }
+/// ```
+pub trait TableName {
+	/// The name of the table.
+	const NAME: &'static str;
+}
+
+/// A record id in the table `T`, described as `record<T::NAME>`.
+///
+/// Converting from a value fails if the record belongs to any other table.
+pub struct Record<T: TableName> {
+	id: RecordId,
+	table: PhantomData<T>,
+}
+
+impl<T: TableName> Record<T> {
+	/// Create a record id in the table `T` with the given key.
+	pub fn new(key: impl Into<RecordIdKey>) -> Self {
+		Self {
+			id: RecordId::new(T::NAME, key),
+			table: PhantomData,
+		}
+	}
+
+	/// Unwrap the underlying record id.
+	pub fn into_inner(self) -> RecordId {
+		self.id
+	}
+}
+
+impl<T: TableName> TryFrom<RecordId> for Record<T> {
+	type Error = anyhow::Error;
+
+	fn try_from(id: RecordId) -> anyhow::Result<Self> {
+		if id.table.as_str() != T::NAME {
+			anyhow::bail!(
+				"Expected a record in table '{}', found '{}'",
+				T::NAME,
+				id.table.as_str()
+			);
+		}
+		Ok(Self {
+			id,
+			table: PhantomData,
+		})
+	}
+}
+
+impl<T: TableName> Deref for Record<T> {
+	type Target = RecordId;
+	fn deref(&self) -> &Self::Target {
+		&self.id
+	}
+}
+
+impl<T: TableName> Clone for Record<T> {
+	fn clone(&self) -> Self {
+		Self {
+			id: self.id.clone(),
+			table: PhantomData,
+		}
+	}
+}
+
+impl<T: TableName> fmt::Debug for Record<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(&self.id, f)
+	}
+}
+
+impl<T: TableName> PartialEq for Record<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.id == other.id
+	}
+}
+
+impl<T: TableName> Eq for Record<T> {}
+
+impl<T: TableName> SurrealValue for Record<T> {
+	fn kind_of() -> Kind {
+		Kind::Record(vec![Table::new(T::NAME)])
+	}
+
+	fn is_value(value: &Value) -> bool {
+		matches!(value, Value::RecordId(id) if id.table.as_str() == T::NAME)
+	}
+
+	fn into_value(self) -> Value {
+		Value::RecordId(self.id)
+	}
+
+	fn from_value(value: Value) -> anyhow::Result<Self> {
+		match value {
+			Value::RecordId(id) => Self::try_from(id),
+			value => Err(ConversionError::from_value(Self::kind_of(), &value).into()),
+		}
+	}
+}
@@ -18,9 +18,13 @@
 //! [4-byte length (u32, LE)][serialized data]
 //! ```
 //!
+//! Empty values, such as `""`, `vec![]` or empty `Bytes`, are transferred as a zero length
+//! with no data, so every transfer allocates at least the 4-byte prefix.
+//!
 //! ## Type Formats
 //!
 //! - **Primitives**: Direct byte encoding (String: UTF-8, numbers: LE bytes, bool: 0/1)
+//! - **Binary**: Raw bytes copied as a single block (`Bytes`, `Vec<u8>`)
 //! - **Enums**: Tag byte + optional payload (Option, Result, Bound)
 //! - **Collections**: Length-prefixed elements (Vec, tuples)
 //! - **Complex**: FlatBuffers protocol (Value, Kind)
@@ -53,6 +57,10 @@ use crate::transfer::{Ptr, Transfer};
 /// ```text
 /// [4-byte length (u32, LE)][data bytes]
 /// ```
+///
+/// The length prefix and all payloads are read and written as byte slices, never by
+/// casting a pointer to a typed reference, so reads are alignment-independent and the
+/// layout is identical on a 32-bit guest and a 64-bit host.
 pub struct Serialized(pub bytes::Bytes);
 
 // Guest side implementation (sync)
@@ -69,9 +77,9 @@ impl Transfer for Serialized {
 	fn receive(ptr: Ptr, controller: &mut dyn MemoryController) -> Result<Self> {
 		let mem = controller.mut_mem(*ptr, 4);
 		let len = u32::from_le_bytes(mem[0..4].try_into()?);
-		let data = controller.mut_mem(*ptr + 4, len).to_vec();
-		#[allow(clippy::unnecessary_cast)]
-		controller.free(*ptr, 4 + len as u32)?;
+		let (start, size) = payload_bounds(*ptr, len)?;
+		let data = controller.mut_mem(start, len).to_vec();
+		controller.free(*ptr, size)?;
 		Ok(Serialized(data.into()))
 	}
 }
@@ -93,13 +101,29 @@ impl AsyncTransfer for Serialized {
 	async fn receive(ptr: Ptr, controller: &mut dyn AsyncMemoryController) -> Result<Self> {
 		let mem = controller.mut_mem(*ptr, 4)?;
 		let len = u32::from_le_bytes(mem[0..4].try_into()?);
-		let data = controller.mut_mem(*ptr + 4, len)?.to_vec();
-		#[allow(clippy::unnecessary_cast)]
-		controller.free(*ptr, 4 + len as u32).await?;
+		if let Some(max) = controller.max_transfer_bytes()
+			&& len > max
+		{
+			anyhow::bail!("Transfer of {len} bytes exceeds the limit of {max} bytes");
+		}
+		let (start, size) = payload_bounds(*ptr, len)?;
+		let data = controller.mut_mem(start, len)?.to_vec();
+		controller.free(*ptr, size).await?;
 		Ok(Serialized(data.into()))
 	}
 }
 
+/// The address of the payload following the length prefix at `ptr`, and the size of the whole
+/// allocation. A length read from memory is untrusted, so this fails rather than wrapping
+/// around when the payload would extend past the 32-bit address space.
+fn payload_bounds(ptr: u32, len: u32) -> Result<(u32, u32)> {
+	let overflow = || anyhow::anyhow!("Transfer of {len} bytes at {ptr} overflows WASM memory");
+	let start = ptr.checked_add(4).ok_or_else(overflow)?;
+	start.checked_add(len).ok_or_else(overflow)?;
+	let size = len.checked_add(4).ok_or_else(overflow)?;
+	Ok((start, size))
+}
+
 // Guest side implementation for Serializable (sync)
 impl<T: Serializable> Transfer for T {
 	fn transfer(self, controller: &mut dyn MemoryController) -> Result<Ptr> {
@@ -120,7 +144,14 @@ impl<T: Serializable + Send> AsyncTransfer for T {
 	}
 
 	async fn receive(ptr: Ptr, controller: &mut dyn AsyncMemoryController) -> Result<Self> {
-		Self::deserialize(AsyncTransfer::receive(ptr, controller).await?)
+		let serialized: Serialized = AsyncTransfer::receive(ptr, controller).await?;
+		if let Some(max) = controller.max_array_len()
+			&& let Some(len) = Self::array_len(&serialized)
+			&& len > max
+		{
+			anyhow::bail!("Array of {len} elements exceeds the limit of {max} elements");
+		}
+		Self::deserialize(serialized)
 	}
 }
 
@@ -172,6 +203,12 @@ pub trait Serializable: Sized {
 	/// - The data doesn't match the expected format
 	/// - Type-specific validation fails
 	fn deserialize(serialized: Serialized) -> Result<Self>;
+
+	/// The element count declared by a serialized array, read without decoding its elements,
+	/// so a limit can be enforced before anything is allocated. `None` for other types.
+	fn array_len(_serialized: &Serialized) -> Option<u32> {
+		None
+	}
 }
 
 impl<T: SurrealValue> Serializable for SerializableArg<T> {
@@ -196,15 +233,50 @@ impl<T: SurrealValue> Serializable for SerializableArg<T> {
 /// ```
 impl Serializable for String {
 	fn serialize(self) -> Result<Serialized> {
-		Ok(Serialized(self.as_bytes().to_vec().into()))
+		Ok(Serialized(self.into_bytes().into()))
 	}
 
 	fn deserialize(serialized: Serialized) -> Result<Self> {
-		String::from_utf8(serialized.0.to_vec())
+		String::from_utf8(serialized.0.into())
 			.map_err(|e| anyhow::anyhow!("Invalid UTF-8 string: {}", e))
 	}
 }
 
+/// [`bytes::Bytes`] serialization.
+///
+/// Wire format: Raw bytes (no per-element framing)
+/// ```text
+/// [bytes...]
+/// ```
+///
+/// Unlike the generic [`Vec<T>`] encoding, binary payloads are written as a single
+/// contiguous block, so large buffers are moved with one `memcpy` in each direction.
+impl Serializable for bytes::Bytes {
+	fn serialize(self) -> Result<Serialized> {
+		Ok(Serialized(self))
+	}
+
+	fn deserialize(serialized: Serialized) -> Result<Self> {
+		Ok(serialized.0)
+	}
+}
+
+/// [`Vec<u8>`] serialization.
+///
+/// Wire format: Same as [`bytes::Bytes`]
+/// ```text
+/// [bytes...]
+/// ```
+impl Serializable for Vec<u8> {
+	fn serialize(self) -> Result<Serialized> {
+		Ok(Serialized(self.into()))
+	}
+
+	fn deserialize(serialized: Serialized) -> Result<Self> {
+		Ok(serialized.0.into())
+	}
+}
+
 /// f64 (64-bit floating point) serialization.
 ///
 /// Wire format: 8 bytes, little-endian IEEE 754
@@ -311,6 +383,10 @@ impl Serializable for surrealdb_types::Kind {
 ///
 /// This leverages the `surrealdb-protocol` FlatBuffers schema, which supports
 /// all SurrealDB types including records, geometries, durations, etc.
+///
+/// Objects are keyed maps, so their key order is not preserved: keys are always
+/// received in sorted order. If an encoded object contains the same key more than
+/// once, the last entry wins.
 impl Serializable for surrealdb_types::Value {
 	fn serialize(self) -> Result<Serialized> {
 		let x = surrealdb_types::encode(&self)?;
@@ -318,7 +394,10 @@ impl Serializable for surrealdb_types::Value {
 	}
 
 	fn deserialize(serialized: Serialized) -> Result<Self> {
+		// Keep the underlying cause, such as an out of range datetime, in the message, as
+		// errors which cross the WASM boundary are passed on as strings
 		surrealdb_types::decode(&serialized.0)
+			.map_err(|e| anyhow::anyhow!("Failed to decode value: {e:#}"))
 	}
 }
 
@@ -378,14 +457,15 @@ impl<T: Serializable, E: Serializable> Serializable for Result<T, E> {
 
 /// [`anyhow::Result<T>`] serialization.
 ///
-/// Wire format: Same as `Result<T, String>` (error converted to string)
+/// Wire format: Same as `Result<T, String>` (error converted to string, including its causes)
 /// ```text
 /// Ok(T):        [0x00][serialized T]
 /// Err(String):  [0x01][error message as UTF-8]
 /// ```
 impl<T: Serializable> Serializable for anyhow::Result<T> {
 	fn serialize(self) -> Result<Serialized> {
-		self.map_err(|e| e.to_string()).serialize()
+		// Include the full chain of causes, not only the outermost context
+		self.map_err(|e| format!("{e:#}")).serialize()
 	}
 
 	fn deserialize(serialized: Serialized) -> Result<Self> {
@@ -444,6 +524,13 @@ impl<T: Serializable> Serializable for Option<T> {
 /// [4-byte len2 (u32 LE)][element2 data]
 /// ...
 /// ```
+///
+/// As every element has a 4-byte length prefix, a count which could not fit in the received
+/// data is rejected before anything is allocated for it. On the host, the count is also checked
+/// against [`AsyncMemoryController::max_array_len`] when the array is received.
+///
+/// `u8` is not [`Serializable`], so `Vec<u8>` does not use this format, and is instead
+/// copied as a single block like [`bytes::Bytes`].
 impl<T: Serializable> Serializable for Vec<T> {
 	fn serialize(self) -> Result<Serialized> {
 		let mut result = (self.len() as u32).to_le_bytes().to_vec();
@@ -460,18 +547,38 @@ impl<T: Serializable> Serializable for Vec<T> {
 			return Err(anyhow::anyhow!("Empty serialized data"));
 		}
 
-		let len = u32::from_le_bytes(serialized.0[0..4].try_into()?) as usize;
+		let data = &serialized.0;
+		let len = u32::from_le_bytes(
+			data.get(0..4).ok_or_else(|| anyhow::anyhow!("Missing array length"))?.try_into()?,
+		) as usize;
+		if len > (data.len() - 4) / 4 {
+			anyhow::bail!("Array length {len} exceeds the {} bytes received", data.len());
+		}
 		let mut result = Vec::with_capacity(len);
 		let mut pos = 4;
 		for _ in 0..len {
-			let value_len = u32::from_le_bytes(serialized.0[pos..pos + 4].try_into()?) as usize;
-			let value_bytes = Serialized(serialized.0.slice(pos + 4..pos + 4 + value_len));
+			let value_len = u32::from_le_bytes(
+				data.get(pos..pos + 4)
+					.ok_or_else(|| anyhow::anyhow!("Missing array element length"))?
+					.try_into()?,
+			) as usize;
+			let end = (pos + 4)
+				.checked_add(value_len)
+				.filter(|end| *end <= data.len())
+				.ok_or_else(|| {
+					anyhow::anyhow!("Array element of {value_len} bytes exceeds the data received")
+				})?;
+			let value_bytes = Serialized(data.slice(pos + 4..end));
 			let value = T::deserialize(value_bytes)?;
 			result.push(value);
-			pos += 4 + value_len;
+			pos = end;
 		}
 		Ok(result)
 	}
+
+	fn array_len(serialized: &Serialized) -> Option<u32> {
+		Some(u32::from_le_bytes(serialized.0.get(0..4)?.try_into().ok()?))
+	}
 }
 
 /// [`std::ops::Bound<T>`] serialization for range bounds.
@@ -509,7 +616,7 @@ impl<T: Serializable> Serializable for Bound<T> {
 
 	fn deserialize(serialized: Serialized) -> Result<Self> {
 		let bytes = serialized.0;
-		match bytes[0] {
+		match bytes.first().ok_or_else(|| anyhow::anyhow!("Missing Bound variant byte"))? {
 			0 => Ok(Bound::Unbounded),
 			1 => {
 				let value = T::deserialize(Serialized(bytes.slice(1..)))?;
@@ -573,10 +680,20 @@ impl<T: Serializable> Serializable for SerializableRange<T> {
 
 	fn deserialize(serialized: Serialized) -> Result<Self> {
 		let bytes = serialized.0;
+		if bytes.len() < 8 {
+			anyhow::bail!("Range is truncated: expected at least 8 bytes, found {}", bytes.len());
+		}
 		let beg_len = u32::from_le_bytes(bytes[0..4].try_into()?) as usize;
 		let end_len = u32::from_le_bytes(bytes[4..8].try_into()?) as usize;
+		// The lengths are untrusted, and their sum overflows a 32-bit `usize` such as on WASM
+		let Some(len) = beg_len.checked_add(end_len).and_then(|len| len.checked_add(8)) else {
+			anyhow::bail!("Range length overflows: bounds of {beg_len} and {end_len} bytes");
+		};
+		if bytes.len() != len {
+			anyhow::bail!("Range length mismatch: bounds need {len} bytes, found {}", bytes.len());
+		}
 		let beg = Bound::deserialize(Serialized(bytes.slice(8..8 + beg_len)))?;
-		let end = Bound::deserialize(Serialized(bytes.slice(8 + beg_len..8 + beg_len + end_len)))?;
+		let end = Bound::deserialize(Serialized(bytes.slice(8 + beg_len..)))?;
 		let range = SerializableRange {
 			beg,
 			end,
@@ -0,0 +1,76 @@
+//! Typed set values.
+//!
+//! [`surrealdb_types::Set`] is untyped, so a function returning it is described as `set`
+//! rather than, for example, `set<int>`. This module provides [`SetOf`], a typed set which
+//! reports its element kind and keeps set semantics across the WASM boundary.
+
+use std::collections::BTreeSet;
+
+use surrealdb_types::{ConversionError, Kind, Set, SurrealValue, Value};
+
+/// A set of values of a single type, described as `set<T>`.
+///
+/// Elements are deduplicated and ordered, both when the set is built on the guest and when
+/// it is received from the host. When converting from a value, an array is accepted as well
+/// and collapsed into a set, so callers can pass `[1, 2, 2]` where a `set<int>` is declared.
+///
+/// Functions may also take and return a `BTreeSet<T>` or `HashSet<T>` directly, which the
+/// `#[surrealism]` macro passes as a `SetOf<T>`. Either way, elements are transferred in
+/// sorted order, so even a returned `HashSet` produces the same set value on every call.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use surrealism_types::set::SetOf;
+///
+/// #[surrealism]
+/// fn unique(values: Vec<i64>) -> SetOf<i64> {
+///     values.into_iter().collect()
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SetOf<T: Ord>(pub BTreeSet<T>);
+
+impl<T: Ord> FromIterator<T> for SetOf<T> {
+	fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+		SetOf(iter.into_iter().collect())
+	}
+}
+
+impl<T: Ord> From<BTreeSet<T>> for SetOf<T> {
+	fn from(set: BTreeSet<T>) -> Self {
+		SetOf(set)
+	}
+}
+
+impl<T: SurrealValue + Ord> SurrealValue for SetOf<T> {
+	fn kind_of() -> Kind {
+		Kind::Set(Box::new(T::kind_of()), None)
+	}
+
+	fn is_value(value: &Value) -> bool {
+		match value {
+			Value::Set(set) => set.iter().all(T::is_value),
+			_ => false,
+		}
+	}
+
+	fn into_value(self) -> Value {
+		Value::Set(Set::from(self.0.into_iter().map(T::into_value).collect::<Vec<_>>()))
+	}
+
+	fn from_value(value: Value) -> anyhow::Result<Self> {
+		let values: Vec<Value> = match value {
+			Value::Set(set) => set.into_iter().collect(),
+			Value::Array(array) => array.into_inner(),
+			value => return Err(ConversionError::from_value(Self::kind_of(), &value).into()),
+		};
+
+		values
+			.into_iter()
+			.map(T::from_value)
+			.collect::<anyhow::Result<BTreeSet<T>>>()
+			.map(SetOf)
+			.map_err(|e| anyhow::anyhow!("Failed to convert to {}: {}", Self::kind_of(), e))
+	}
+}
@@ -0,0 +1,310 @@
+//! Statement detection for queries run by modules.
+//!
+//! Neither modules nor the runtime can depend on the SurrealQL parser, so queries are scanned
+//! for statement keywords instead. Hosts which have a parser can classify queries themselves,
+//! see `InvocationContext::statements` in `surrealism-runtime`.
+//!
+//! Strings, comments and escaped identifiers are skipped, and a keyword counts as a statement
+//! wherever one could begin. The scan fails closed: a keyword is only passed over after `.` or
+//! `$`, before `:` as an object key or table name, in a permission list, after a string or
+//! escaped identifier, and after a word which cannot be followed by a value. So a field which
+//! shares its name with a statement keyword is reported in most positions, such as after `,`
+//! in `SELECT id, delete FROM t`. Queries the scanner cannot split reliably are rejected:
+//! those with an unterminated string, comment or identifier, and those using `/` outside of a
+//! comment, which starts either a division or a regex that may hide anything up to the next
+//! `/`.
+
+use std::fmt;
+
+use anyhow::Result;
+
+/// The type of a SurrealQL statement, named by its leading keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum StatementKind {
+	Access,
+	Alter,
+	Begin,
+	Break,
+	Cancel,
+	Commit,
+	Continue,
+	Create,
+	Define,
+	Delete,
+	For,
+	If,
+	Info,
+	Insert,
+	Kill,
+	Let,
+	Live,
+	Option,
+	Rebuild,
+	Relate,
+	Remove,
+	Return,
+	Select,
+	Show,
+	Sleep,
+	Throw,
+	Update,
+	Upsert,
+	Use,
+}
+
+impl StatementKind {
+	/// The statements a module may run when it is not allowed arbitrary queries and does not
+	/// configure its own: reading data, and control flow which cannot modify anything.
+	pub const SAFE: &[StatementKind] = &[
+		StatementKind::Begin,
+		StatementKind::Break,
+		StatementKind::Cancel,
+		StatementKind::Commit,
+		StatementKind::Continue,
+		StatementKind::For,
+		StatementKind::If,
+		StatementKind::Let,
+		StatementKind::Return,
+		StatementKind::Select,
+		StatementKind::Throw,
+	];
+
+	fn from_keyword(word: &str) -> Option<Self> {
+		let kind = match word.to_ascii_uppercase().as_str() {
+			"ACCESS" => StatementKind::Access,
+			"ALTER" => StatementKind::Alter,
+			"BEGIN" => StatementKind::Begin,
+			"BREAK" => StatementKind::Break,
+			"CANCEL" => StatementKind::Cancel,
+			"COMMIT" => StatementKind::Commit,
+			"CONTINUE" => StatementKind::Continue,
+			"CREATE" => StatementKind::Create,
+			"DEFINE" => StatementKind::Define,
+			"DELETE" => StatementKind::Delete,
+			"FOR" => StatementKind::For,
+			"IF" => StatementKind::If,
+			"INFO" => StatementKind::Info,
+			"INSERT" => StatementKind::Insert,
+			"KILL" => StatementKind::Kill,
+			"LET" => StatementKind::Let,
+			"LIVE" => StatementKind::Live,
+			"OPTION" => StatementKind::Option,
+			"REBUILD" => StatementKind::Rebuild,
+			"RELATE" => StatementKind::Relate,
+			"REMOVE" => StatementKind::Remove,
+			"RETURN" => StatementKind::Return,
+			"SELECT" => StatementKind::Select,
+			"SHOW" => StatementKind::Show,
+			"SLEEP" => StatementKind::Sleep,
+			"THROW" => StatementKind::Throw,
+			"UPDATE" => StatementKind::Update,
+			"UPSERT" => StatementKind::Upsert,
+			"USE" => StatementKind::Use,
+			_ => return None,
+		};
+		Some(kind)
+	}
+}
+
+impl fmt::Display for StatementKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&format!("{self:?}").to_ascii_uppercase())
+	}
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+	Word(&'a str),
+	Punct(char),
+	Other,
+	/// Input which cannot be split into statements reliably, with the reason why.
+	Ambiguous(&'static str),
+}
+
+/// Split a query into words and punctuation, skipping whitespace, comments, strings and
+/// escaped identifiers. Unterminated strings and comments run to the end of the query, and
+/// are reported as ambiguous, as is `/`.
+fn tokenize(query: &str) -> Vec<Token<'_>> {
+	let mut tokens = Vec::new();
+	let mut chars = query.char_indices().peekable();
+	while let Some((start, c)) = chars.next() {
+		match c {
+			c if c.is_whitespace() => {}
+			'-' if chars.next_if(|(_, c)| *c == '-').is_some() => skip_line(&mut chars),
+			'/' if chars.next_if(|(_, c)| *c == '/').is_some() => skip_line(&mut chars),
+			'#' => skip_line(&mut chars),
+			'/' if chars.next_if(|(_, c)| *c == '*').is_some() => {
+				let mut last = ' ';
+				let mut closed = false;
+				for (_, c) in chars.by_ref() {
+					if last == '*' && c == '/' {
+						closed = true;
+						break;
+					}
+					last = c;
+				}
+				if !closed {
+					tokens.push(Token::Ambiguous("an unterminated comment"));
+				}
+			}
+			'/' => tokens.push(Token::Ambiguous("a `/`, which may start a regex")),
+			'\'' | '"' | '`' => {
+				let mut closed = false;
+				while let Some((_, next)) = chars.next() {
+					match next {
+						'\\' => {
+							chars.next();
+						}
+						next if next == c => {
+							closed = true;
+							break;
+						}
+						_ => {}
+					}
+				}
+				tokens.push(match closed {
+					true => Token::Other,
+					false => Token::Ambiguous("an unterminated string or identifier"),
+				});
+			}
+			'⟨' => {
+				let closed = chars.by_ref().any(|(_, c)| c == '⟩');
+				tokens.push(match closed {
+					true => Token::Other,
+					false => Token::Ambiguous("an unterminated identifier"),
+				});
+			}
+			c if c.is_alphanumeric() || c == '_' => {
+				let mut end = start + c.len_utf8();
+				while let Some((i, c)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_') {
+					end = i + c.len_utf8();
+				}
+				tokens.push(Token::Word(&query[start..end]));
+			}
+			c => tokens.push(Token::Punct(c)),
+		}
+	}
+	tokens
+}
+
+fn skip_line(chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>) {
+	for (_, c) in chars.by_ref() {
+		if c == '\n' {
+			break;
+		}
+	}
+}
+
+/// The number of top-level statements in a query, separated by `;`. Statements nested in
+/// blocks, subqueries and arrays are part of the statement containing them, and empty
+/// statements are not counted.
+pub fn count(query: &str) -> usize {
+	let mut count = 0;
+	let mut depth = 0usize;
+	let mut empty = true;
+	for token in tokenize(query) {
+		match token {
+			Token::Punct(';') if depth == 0 => {
+				count += usize::from(!empty);
+				empty = true;
+				continue;
+			}
+			Token::Punct('(' | '[' | '{') => depth += 1,
+			Token::Punct(')' | ']' | '}') => depth = depth.saturating_sub(1),
+			_ => {}
+		}
+		empty = false;
+	}
+	count + usize::from(!empty)
+}
+
+/// The statements in a query, in the order they appear, including nested statements such
+/// as subqueries and the statements in blocks.
+///
+/// # Errors
+/// If the query cannot be split into statements reliably, see the [module docs](self).
+pub fn statements(query: &str) -> Result<Vec<StatementKind>> {
+	let tokens = tokenize(query);
+	let mut kinds = Vec::new();
+	// Permission lists, such as `FOR select, delete`, name statements without running them
+	let mut in_permissions = false;
+	for (i, token) in tokens.iter().enumerate() {
+		let previous = i.checked_sub(1).map(|i| &tokens[i]);
+		if let Token::Ambiguous(reason) = token {
+			anyhow::bail!("The query cannot be checked for statements, as it contains {reason}");
+		}
+		let Token::Word(word) = token else {
+			in_permissions &= *token == Token::Punct(',');
+			continue;
+		};
+		let Some(kind) = StatementKind::from_keyword(word) else {
+			in_permissions = false;
+			continue;
+		};
+		let permission = matches!(
+			kind,
+			StatementKind::Select
+				| StatementKind::Create
+				| StatementKind::Update
+				| StatementKind::Delete
+		);
+		let after_for = matches!(previous, Some(Token::Word(w)) if w.eq_ignore_ascii_case("FOR"));
+		in_permissions = permission && (in_permissions || after_for);
+		if in_permissions {
+			continue;
+		}
+		let statement_position = match previous {
+			None => true,
+			Some(Token::Punct(c)) => !matches!(c, '.' | '$'),
+			Some(Token::Word(w)) => {
+				OPERAND_KEYWORDS.iter().any(|keyword| w.eq_ignore_ascii_case(keyword))
+			}
+			Some(Token::Other | Token::Ambiguous(_)) => false,
+		};
+		let object_key = tokens.get(i + 1) == Some(&Token::Punct(':'));
+		if statement_position && !object_key {
+			kinds.push(kind);
+		}
+	}
+	Ok(kinds)
+}
+
+/// The keywords which can be followed by a value, and so by a statement: clauses taking a
+/// value or a target, and keyword operators.
+const OPERAND_KEYWORDS: &[&str] = &[
+	"RETURN",
+	"THEN",
+	"ELSE",
+	"IF",
+	"THROW",
+	"FROM",
+	"ONLY",
+	"VALUE",
+	"WHERE",
+	"SET",
+	"CONTENT",
+	"MERGE",
+	"PATCH",
+	"REPLACE",
+	"LIMIT",
+	"START",
+	"AND",
+	"OR",
+	"NOT",
+	"IS",
+	"IN",
+	"INSIDE",
+	"OUTSIDE",
+	"INTERSECTS",
+	"CONTAINS",
+	"CONTAINSALL",
+	"CONTAINSANY",
+	"CONTAINSNONE",
+	"CONTAINSNOT",
+	"ALLINSIDE",
+	"ANYINSIDE",
+	"NONEINSIDE",
+	"NOTINSIDE",
+];
@@ -181,8 +181,17 @@ pub trait AsyncTransfer: Send {
 /// While this type provides type safety at the Rust level, it does not guarantee
 /// memory safety. The underlying pointer must be valid within the WASM linear memory,
 /// and the memory region it points to must contain properly formatted data.
+///
+/// # Layout
+///
+/// A `Ptr` is always exactly 4 bytes, regardless of the pointer width of the side
+/// holding it. The guest is a 32-bit WASM target while the host is usually 64-bit, so
+/// nothing that crosses the boundary may depend on `usize` or native struct layout.
 pub struct Ptr(u32);
 
+// The wire protocol relies on pointers and length prefixes being 4 bytes on both sides.
+const _: () = assert!(std::mem::size_of::<Ptr>() == 4);
+
 impl Deref for Ptr {
 	type Target = u32;
 
@@ -0,0 +1,11 @@
+//! The ABI marker checked by the host when it loads a module.
+
+use surrealism_types::ABI_VERSION;
+
+/// Exports the version of the ABI the module was built against. The host rejects modules
+/// which do not export it, such as plain WASM modules not built with this crate, and modules
+/// built against another version.
+#[unsafe(no_mangle)]
+pub extern "C" fn __sr_abi_version() -> u32 {
+	ABI_VERSION
+}
@@ -0,0 +1,64 @@
+//! The build information a module exports through `__sr_build_info`.
+//!
+//! Every `#[surrealism]` function submits a [`BuildInfoEntry`] with the version of the crate
+//! it is declared in and the time it was compiled, as only the macro expands within the
+//! module's crate. The version of `surrealism` is known to this crate itself.
+//!
+//! Builds which set `SOURCE_DATE_EPOCH` report that time instead, so that reproducible builds
+//! embed a fixed time. The macro reads it through `option_env!`, so cargo rebuilds the module
+//! when it changes.
+
+use surrealism_types::arg::SerializableArg;
+use surrealism_types::build_info::BuildInfo;
+use surrealism_types::transfer::Transfer;
+
+use crate::Controller;
+
+/// The crate version and compile time of a `#[surrealism]` function. Not public API, used by
+/// generated code.
+#[doc(hidden)]
+pub struct BuildInfoEntry {
+	pub crate_version: &'static str,
+	/// `SOURCE_DATE_EPOCH` when the function was compiled, if it was set.
+	pub source_date_epoch: Option<&'static str>,
+	/// When the function was compiled, in seconds since the Unix epoch.
+	pub compiled_at: u64,
+}
+
+impl BuildInfoEntry {
+	/// When the function was built, in seconds since the Unix epoch, taken from
+	/// `SOURCE_DATE_EPOCH` when it was set to a valid time.
+	fn built_at(&self) -> u64 {
+		self.source_date_epoch.and_then(|epoch| epoch.parse().ok()).unwrap_or(self.compiled_at)
+	}
+}
+
+inventory::collect!(BuildInfoEntry);
+
+/// Exports the build information to the host. Functions compiled at different times report
+/// the latest time, which is when the module was last compiled.
+#[unsafe(no_mangle)]
+pub extern "C" fn __sr_build_info() -> i32 {
+	let entries = || inventory::iter::<BuildInfoEntry>.into_iter();
+	let info = BuildInfo {
+		crate_version: entries().next().map(|entry| entry.crate_version.to_string()),
+		surrealism_version: env!("CARGO_PKG_VERSION").to_string(),
+		built_at: entries().map(BuildInfoEntry::built_at).max().and_then(timestamp),
+	};
+	let mut controller = Controller {};
+	match SerializableArg(info).transfer(&mut controller) {
+		Ok(ptr) => (*ptr).try_into().unwrap_or(-1),
+		Err(e) => {
+			eprintln!("Transfer error: {}", e);
+			-1
+		}
+	}
+}
+
+/// A time in seconds since the Unix epoch, as an RFC 3339 UTC timestamp, or `None` when it is
+/// out of range.
+fn timestamp(secs: u64) -> Option<String> {
+	let secs = i64::try_from(secs).ok()?;
+	let time = chrono::DateTime::from_timestamp(secs, 0)?;
+	Some(time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use surrealism_types::controller::MemoryController;
+use surrealism_types::err::MemoryError;
 
 use crate::memory::{__sr_alloc, __sr_free};
 
@@ -26,12 +27,14 @@ impl MemoryController for Controller {
 	/// on success, or an error if allocation fails.
 	///
 	/// # Errors
-	/// Returns an error if the underlying allocation function fails (though in this
-	/// implementation, it assumes success and wraps the result in `Ok`).
+	/// Returns [`MemoryError::OutOfMemory`] if the underlying allocation function fails.
 	fn alloc(&mut self, len: u32) -> Result<u32> {
 		let result = __sr_alloc(len);
 		if result == 0 {
-			anyhow::bail!("Memory allocation failed");
+			return Err(MemoryError::OutOfMemory {
+				len,
+			}
+			.into());
 		}
 		Ok(result)
 	}
@@ -76,7 +79,19 @@ impl MemoryController for Controller {
 	/// This function is unsafe because it assumes the pointer is valid, properly aligned,
 	/// and that the memory region is mutable and not accessed concurrently. Incorrect
 	/// usage may lead to undefined behavior, such as memory corruption or data races.
+	///
+	/// # Panics
+	/// Panics if the region extends past the end of linear memory.
 	fn mut_mem(&mut self, ptr: u32, len: u32) -> &mut [u8] {
+		// Never build a slice over memory which does not exist, as a length read from a
+		// transfer may be corrupt or hostile
+		#[cfg(target_arch = "wasm32")]
+		{
+			let size = core::arch::wasm32::memory_size(0) as u64 * 65536;
+			if ptr as u64 + len as u64 > size {
+				panic!("Memory access out of bounds: ptr={ptr}, len={len}, memory size is {size}");
+			}
+		}
 		unsafe {
 			let ptr = ptr as usize as *mut u8;
 			std::slice::from_raw_parts_mut(ptr, len as usize)
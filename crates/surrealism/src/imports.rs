@@ -1,7 +1,11 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use surrealdb_types::SurrealValue;
 use surrealism_types::arg::SerializableArg;
 use surrealism_types::args::Args;
+use surrealism_types::err::{PrefixError, SqlError};
+use surrealism_types::query::QueryResult;
 use surrealism_types::transfer::Transfer;
 
 use crate::Controller;
@@ -20,14 +24,18 @@ use crate::Controller;
 unsafe extern "C" {
 	/// Executes a SQL query using pointers to the query string and variables.
 	unsafe fn __sr_sql(sql_ptr: u32, vars_ptr: u32) -> i32;
+	/// Executes a SQL script, returning the status and result of each statement.
+	unsafe fn __sr_sql_results(sql_ptr: u32, vars_ptr: u32) -> i32;
 	/// Runs a named function with optional version and arguments via pointers.
 	unsafe fn __sr_run(fnc_ptr: u32, version_ptr: u32, vars_ptr: u32) -> i32;
+	/// Retrieves the metadata of the package the module was loaded from.
+	unsafe fn __sr_meta() -> i32;
 }
 
 /// Executes a SurrealDB SQL query without variables.
 ///
-/// This is a convenience wrapper around `sql_with_vars` that passes an empty
-/// vector for variables.
+/// This is a convenience wrapper around `sql_with_vars` that passes no
+/// variables.
 ///
 /// # Type Parameters
 /// - `S`: A type that can be converted into a `String` (e.g., `String`, `&str`).
@@ -55,17 +63,20 @@ where
 /// Executes a SurrealDB SQL query with optional variables.
 ///
 /// This function prepares the SQL query and variables, transfers them to the
-/// runtime via FFI, executes the query, and deserializes the result.
+/// runtime via FFI, executes the query, and deserializes the result. Rows can be
+/// read directly into structs deriving `SurrealValue`, for example as a `Vec<User>`
+/// from a `SELECT` statement.
 ///
 /// # Type Parameters
 /// - `S`: A type that can be converted into a `String` (e.g., `String`, `&str`).
-/// - `V`: An iterator yielding pairs of `(String, sql::Value)` for query variables.
+/// - `V`: An iterator yielding pairs of `(String, surrealdb_types::Value)` for query variables.
 /// - `R`: A type that implements `Transferrable<Value>`, representing the expected return type
 ///   after deserialization from the raw `Value`.
 ///
 /// # Parameters
 /// - `sql`: The SQL query to execute.
-/// - `vars`: An iterator of key-value pairs for query variables.
+/// - `vars`: An iterator of key-value pairs for query variables. If a name is repeated,
+///   the last value is used.
 ///
 /// # Returns
 /// A `Result` containing the deserialized return value `R` on success, or an error.
@@ -74,7 +85,10 @@ where
 /// - If the SQL query is empty after trimming.
 /// - If converting or transferring data fails.
 /// - If the FFI call or result reception encounters an issue.
-/// - If deserializing the result into `R` fails.
+/// - If the query fails. The error is a [`SqlError`] carrying the full message from the
+///   host, which can be recovered with `downcast_ref` to match on its category.
+/// - If deserializing the result into `R` fails, such as when a row does not match the
+///   shape of the target struct. The error names the expected kind.
 pub fn sql_with_vars<S, V, R>(sql: S, vars: V) -> Result<R>
 where
 	S: Into<String>,
@@ -91,7 +105,224 @@ where
 	let vars = vars.into_iter().collect::<Vec<_>>().transfer(&mut controller)?;
 
 	let result = unsafe { __sr_sql(*sql, *vars) };
-	Result::<SerializableArg<R>>::receive(result.try_into()?, &mut controller)?.map(|x| x.0)
+	let value =
+		Result::<surrealdb_types::Value, String>::receive(result.try_into()?, &mut controller)?
+			.map_err(SqlError::new)?;
+	R::from_value(value)
+		.prefix_err(|| format!("Failed to convert SQL query result into {}", R::kind_of()))
+}
+
+/// Executes a SurrealDB SQL query with positional bind parameters.
+///
+/// Each bind is passed as a variable named after its 1-based position, so the first
+/// bind is available to the query as `$_1`, the second as `$_2`, and so on. SurrealQL
+/// parameters must start with a letter or underscore, which is why the names are
+/// prefixed. The query text is forwarded unchanged.
+///
+/// # Type Parameters
+/// - `S`: A type that can be converted into a `String` (e.g., `String`, `&str`).
+/// - `B`: An iterator yielding the bind values in positional order.
+/// - `R`: A type that implements `SurrealValue`, representing the expected return type.
+///
+/// # Parameters
+/// - `sql`: The SQL query to execute, referencing binds as `$_1` to `$_n`.
+/// - `binds`: The values to bind, in order.
+///
+/// # Returns
+/// A `Result` containing the deserialized return value `R` on success, or an error.
+///
+/// # Errors
+/// - If the SQL query is empty after trimming.
+/// - If converting or transferring data fails.
+/// - If deserializing the result into `R` fails.
+pub fn sql_with_binds<S, B, R>(sql: S, binds: B) -> Result<R>
+where
+	S: Into<String>,
+	B: IntoIterator<Item = surrealdb_types::Value>,
+	R: SurrealValue,
+{
+	let vars = binds.into_iter().enumerate().map(|(i, value)| (format!("_{}", i + 1), value));
+	sql_with_vars(sql, vars)
+}
+
+/// Executes a SurrealDB SQL query without variables, reading each row into a `T`.
+///
+/// This is a convenience wrapper around `sql_typed_with_vars` that passes no
+/// variables.
+///
+/// # Parameters
+/// - `sql`: The SQL query to execute, such as a `SELECT` statement.
+///
+/// # Returns
+/// A `Result` containing one `T` per row, in the order they were returned, or an error.
+///
+/// # Errors
+/// - If the SQL query is empty after trimming.
+/// - If data transfer or reception fails.
+/// - If the query does not return an array of rows.
+/// - If a row cannot be read into `T`. The error names the index of the row.
+pub fn sql_typed<S, T>(sql: S) -> Result<Vec<T>>
+where
+	S: Into<String>,
+	T: SurrealValue,
+{
+	sql_typed_with_vars(sql, surrealdb_types::Variables::new())
+}
+
+/// Executes a SurrealDB SQL query with optional variables, reading each row into a `T`.
+///
+/// Unlike reading the result with `sql_with_vars` as a `Vec<T>`, each row is converted on
+/// its own, so a row which does not match the shape of `T` is reported by its index. When
+/// `T` derives `SurrealValue`, the error also names the field which failed, such as a
+/// required field missing from the row:
+///
+/// ```text
+/// Failed to read row 1 as my_module::User: Failed to deserialize field 'age' on type 'User': ...
+/// ```
+///
+/// # Parameters
+/// - `sql`: The SQL query to execute, such as a `SELECT` statement.
+/// - `vars`: An iterator of key-value pairs for query variables.
+///
+/// # Returns
+/// A `Result` containing one `T` per row, in the order they were returned, or an error.
+///
+/// # Errors
+/// - If the SQL query is empty after trimming.
+/// - If converting or transferring data fails.
+/// - If the query fails, with a [`SqlError`] as for `sql_with_vars`.
+/// - If the query does not return an array of rows.
+/// - If a row cannot be read into `T`. The error names the index of the row.
+pub fn sql_typed_with_vars<S, V, T>(sql: S, vars: V) -> Result<Vec<T>>
+where
+	S: Into<String>,
+	V: IntoIterator<Item = (String, surrealdb_types::Value)>,
+	T: SurrealValue,
+{
+	let rows = match sql_with_vars(sql, vars)? {
+		surrealdb_types::Value::Array(rows) => rows.into_inner(),
+		other => anyhow::bail!("Expected an array of rows, found {}", other.kind()),
+	};
+	let name = std::any::type_name::<T>();
+	rows.into_iter()
+		.enumerate()
+		.map(|(i, row)| {
+			T::from_value(row).prefix_err(|| format!("Failed to read row {i} as {name}"))
+		})
+		.collect()
+}
+
+/// Executes a multi-statement SurrealDB SQL script without variables.
+///
+/// This is a convenience wrapper around `sql_many_with_vars` that passes no
+/// variables.
+///
+/// # Parameters
+/// - `sql`: The SQL script to execute.
+///
+/// # Returns
+/// A `Result` containing one value per statement, in statement order, or an error.
+///
+/// # Errors
+/// - If the SQL script is empty after trimming.
+/// - If data transfer or reception fails.
+/// - If a statement fails, with a [`SqlError`] as for `sql_many_with_vars`.
+pub fn sql_many<S>(sql: S) -> Result<Vec<surrealdb_types::Value>>
+where
+	S: Into<String>,
+{
+	sql_many_with_vars(sql, surrealdb_types::Variables::new())
+}
+
+/// Executes a multi-statement SurrealDB SQL script with optional variables.
+///
+/// The script is run through [`sql_results_with_vars`], so the host reports the result of
+/// each statement separately, and each result can be read individually, for example the
+/// output of a `DEFINE` followed by a `SELECT`. A script of a single statement returns a
+/// single result, even when that result is an array of rows.
+///
+/// # Parameters
+/// - `sql`: The SQL script to execute.
+/// - `vars`: An iterator of key-value pairs for query variables.
+///
+/// # Returns
+/// A `Result` containing one value per statement, in statement order, or an error.
+///
+/// # Errors
+/// - If the SQL script is empty after trimming.
+/// - If converting or transferring data fails.
+/// - If a statement fails. The error is a [`SqlError`] carrying the message of the first
+///   statement which failed.
+pub fn sql_many_with_vars<S, V>(sql: S, vars: V) -> Result<Vec<surrealdb_types::Value>>
+where
+	S: Into<String>,
+	V: IntoIterator<Item = (String, surrealdb_types::Value)>,
+{
+	sql_results_with_vars(sql, vars)?
+		.into_iter()
+		.map(|result| result.into_result().map_err(anyhow::Error::from))
+		.collect()
+}
+
+/// Executes a multi-statement SurrealDB SQL script without variables, returning the status
+/// and result of each statement.
+///
+/// This is a convenience wrapper around `sql_results_with_vars` that passes no
+/// variables.
+///
+/// # Parameters
+/// - `sql`: The SQL script to execute.
+///
+/// # Returns
+/// A `Result` containing one [`QueryResult`] per statement, in statement order, or an error.
+///
+/// # Errors
+/// - If the SQL script is empty after trimming.
+/// - If data transfer or reception fails.
+pub fn sql_results<S>(sql: S) -> Result<Vec<QueryResult>>
+where
+	S: Into<String>,
+{
+	sql_results_with_vars(sql, surrealdb_types::Variables::new())
+}
+
+/// Executes a multi-statement SurrealDB SQL script with optional variables, returning the
+/// status and result of each statement.
+///
+/// Unlike [`sql_many_with_vars`], a failed statement does not fail the whole call. Its
+/// [`QueryResult`] has an `ERR` status and the error message as its result, while the
+/// results of the other statements can still be read. [`QueryResult::into_result`] turns
+/// a statement's result into a `Result` carrying a [`SqlError`].
+///
+/// # Parameters
+/// - `sql`: The SQL script to execute.
+/// - `vars`: An iterator of key-value pairs for query variables.
+///
+/// # Returns
+/// A `Result` containing one [`QueryResult`] per statement, in statement order, or an error.
+///
+/// # Errors
+/// - If the SQL script is empty after trimming.
+/// - If converting or transferring data fails.
+/// - If the script could not be run at all, such as when the module is not allowed to run
+///   one of its statements.
+pub fn sql_results_with_vars<S, V>(sql: S, vars: V) -> Result<Vec<QueryResult>>
+where
+	S: Into<String>,
+	V: IntoIterator<Item = (String, surrealdb_types::Value)>,
+{
+	let sql = sql.into();
+	if sql.trim().is_empty() {
+		anyhow::bail!("SQL query cannot be empty");
+	}
+
+	let mut controller = Controller {};
+	let sql = sql.transfer(&mut controller)?;
+	let vars = vars.into_iter().collect::<Vec<_>>().transfer(&mut controller)?;
+
+	let result = unsafe { __sr_sql_results(*sql, *vars) };
+	Result::<SerializableArg<Vec<QueryResult>>>::receive(result.try_into()?, &mut controller)?
+		.map(|x| x.0)
 }
 
 /// Runs a named function in the SurrealDB runtime with optional version and arguments.
@@ -133,6 +364,134 @@ where
 	Result::<SerializableArg<R>>::receive(result.try_into()?, &mut controller)?.map(|x| x.0)
 }
 
+/// How [`run_with_retry`] retries a function call which fails.
+///
+/// The delay before each retry starts at `initial_backoff`, and is multiplied by
+/// `multiplier` after every retry, up to `max_backoff`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+	/// The most attempts made, including the first. At least one attempt is always made.
+	pub attempts: u32,
+	/// The delay before the first retry.
+	pub initial_backoff: Duration,
+	/// The factor the delay grows by after each retry.
+	pub multiplier: u32,
+	/// The longest delay between attempts.
+	pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self {
+			attempts: 3,
+			initial_backoff: Duration::from_millis(100),
+			multiplier: 2,
+			max_backoff: Duration::from_secs(5),
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Create the default policy with the given number of attempts.
+	pub fn new(attempts: u32) -> Self {
+		Self {
+			attempts,
+			..Self::default()
+		}
+	}
+
+	/// Set the delay before the first retry.
+	pub fn with_backoff(mut self, initial_backoff: Duration) -> Self {
+		self.initial_backoff = initial_backoff;
+		self
+	}
+
+	/// Set the factor the delay grows by after each retry.
+	pub fn with_multiplier(mut self, multiplier: u32) -> Self {
+		self.multiplier = multiplier;
+		self
+	}
+
+	/// Set the longest delay between attempts.
+	pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+		self.max_backoff = max_backoff;
+		self
+	}
+}
+
+/// Runs a SurrealDB function like [`run`], retrying it while it fails.
+///
+/// Every error is retried, whether it was returned by the function or occurred while
+/// transferring its arguments or result, until `policy.attempts` attempts have been made.
+///
+/// # Parameters
+/// - `fnc`: The name of the function to run.
+/// - `version`: An optional version string for the function.
+/// - `args`: Arguments to pass to the function, cloned for each attempt.
+/// - `policy`: How many attempts to make, and how long to wait between them.
+///
+/// # Returns
+/// A `Result` containing the return value of the first successful attempt.
+///
+/// # Errors
+/// The error of the last attempt, if every attempt failed.
+pub fn run_with_retry<F, A, R>(
+	fnc: F,
+	version: Option<String>,
+	args: A,
+	policy: RetryPolicy,
+) -> Result<R>
+where
+	F: Into<String>,
+	A: Args + Clone,
+	R: SurrealValue,
+{
+	let fnc = fnc.into();
+	let mut backoff = policy.initial_backoff;
+	let mut attempt = 1;
+	loop {
+		match run(fnc.clone(), version.clone(), args.clone()) {
+			Ok(result) => return Ok(result),
+			Err(e) if attempt >= policy.attempts => return Err(e),
+			Err(_) => {
+				if !backoff.is_zero() {
+					std::thread::sleep(backoff);
+				}
+				backoff = backoff.saturating_mul(policy.multiplier).min(policy.max_backoff);
+				attempt += 1;
+			}
+		}
+	}
+}
+
+/// Metadata of the package a module was loaded from.
+#[derive(Debug, Clone, SurrealValue)]
+pub struct PackageMeta {
+	/// The organisation publishing the package.
+	pub organisation: String,
+	/// The name of the package.
+	pub name: String,
+	/// The semantic version of the package.
+	pub version: String,
+}
+
+/// Retrieves the metadata of the package this module was loaded from.
+///
+/// The metadata is supplied by the runtime from the package's `surrealism.toml`.
+///
+/// # Returns
+/// A `Result` containing the [`PackageMeta`] on success, or an error.
+///
+/// # Errors
+/// - If the FFI call or result reception encounters an issue.
+/// - If deserializing the result into [`PackageMeta`] fails.
+pub fn meta() -> Result<PackageMeta> {
+	let mut controller = Controller {};
+	let result = unsafe { __sr_meta() };
+	Result::<SerializableArg<PackageMeta>>::receive(result.try_into()?, &mut controller)?
+		.map(|x| x.0)
+}
+
 /// Module containing key-value store operations.
 ///
 /// This module provides utilities for interacting with a key-value store in a
@@ -185,6 +544,8 @@ pub mod kv {
 		unsafe fn __sr_kv_entries(range_ptr: u32) -> i32;
 		/// Counts the number of key-value pairs within a specified range.
 		unsafe fn __sr_kv_count(range_ptr: u32) -> i32;
+		/// Retrieves a page of key-value pairs within a range, after a given key.
+		unsafe fn __sr_kv_scan(range_ptr: u32, limit_ptr: u32, after_ptr: u32) -> i32;
 	}
 
 	/// Retrieves a value from the key-value store by key.
@@ -204,6 +565,11 @@ pub mod kv {
 	/// A `Result` containing `Some(R)` if the key exists, `None` if it doesn't,
 	/// or an error if the operation fails.
 	///
+	/// A key which holds `NULL` or `NONE` exists, so it is not reported as absent: read as a
+	/// `Value`, it returns `Some(Value::Null)` or `Some(Value::None)`, while a key which was
+	/// never set, or has been deleted, returns `None`. To store an optional value, read it
+	/// back as an `Option<T>`, which returns `Some(None)` for a stored `NONE`.
+	///
 	/// # Errors
 	/// - If transferring data fails.
 	/// - If the FFI call or result reception encounters an issue.
@@ -292,6 +658,44 @@ pub mod kv {
 		Result::<bool>::receive(result.try_into()?, &mut controller)?
 	}
 
+	/// Retrieves a value from the key-value store, inserting it first if the key is absent.
+	///
+	/// The value is read with a single `get`. Only when the key is absent is `f` called, on
+	/// the guest, and its result stored with a single `set`, so at most two calls cross into
+	/// the host. As with [`get`], a key which holds `NULL` or `NONE` exists, so it is returned
+	/// rather than replaced.
+	///
+	/// # Type Parameters
+	/// - `K`: A type that can be converted into a `String` (e.g., the key).
+	/// - `R`: A type that implements `SurrealValue`, representing the stored value.
+	/// - `F`: A closure computing the value to insert.
+	///
+	/// # Parameters
+	/// - `key`: The key to look up in the store.
+	/// - `f`: Computes the value to store when the key is absent.
+	///
+	/// # Returns
+	/// A `Result` containing the existing value, or the newly inserted one.
+	///
+	/// # Errors
+	/// - If transferring data fails.
+	/// - If the FFI call or result reception encounters an issue.
+	/// - If deserializing the existing value into `R` fails.
+	pub fn get_or_insert_with<K, R, F>(key: K, f: F) -> Result<R>
+	where
+		K: Into<String>,
+		R: SurrealValue + Clone,
+		F: FnOnce() -> R,
+	{
+		let key = key.into();
+		if let Some(value) = get(key.as_str())? {
+			return Ok(value);
+		}
+		let value = f();
+		set(key, value.clone())?;
+		Ok(value)
+	}
+
 	/// Deletes all key-value pairs within a specified range.
 	///
 	/// This function transfers the range bounds to the runtime via FFI and
@@ -301,19 +705,21 @@ pub mod kv {
 	/// - `R`: A type that implements `RangeBounds<String>` for defining the key range.
 	///
 	/// # Parameters
-	/// - `range`: The range of keys to delete (e.g., `"a".."z"` or `.."prefix"`).
+	/// - `range`: The range of keys to delete (e.g., `"a".."z"` or `.."prefix"`). The end
+	///   bound is respected, so `"a"..="c"` deletes `"c"` while `"a".."c"` does not.
 	///
 	/// # Returns
-	/// A `Result` containing `()` on success, or an error if the operation fails.
+	/// A `Result` containing the number of key-value pairs deleted, or an error if the
+	/// operation fails.
 	///
 	/// # Errors
 	/// - If transferring data fails.
 	/// - If the FFI call or result reception encounters an issue.
-	pub fn del_rng<R: RangeBounds<String>>(range: R) -> Result<()> {
+	pub fn del_rng<R: RangeBounds<String>>(range: R) -> Result<u64> {
 		let mut controller = Controller {};
 		let range = SerializableRange::from_range_bounds(range)?.transfer(&mut controller)?;
 		let result = unsafe { __sr_kv_del_rng(*range) };
-		Result::<()>::receive(result.try_into()?, &mut controller)?
+		Result::<u64>::receive(result.try_into()?, &mut controller)?
 	}
 
 	/// Retrieves multiple values from the key-value store in a single operation.
@@ -531,4 +937,304 @@ pub mod kv {
 		let result = unsafe { __sr_kv_count(*range) };
 		Result::<u64>::receive(result.try_into()?, &mut controller)?
 	}
+
+	/// Retrieves a page of key-value pairs within a key range, without loading the whole range.
+	///
+	/// Returns at most `limit` entries whose keys come after `after`, along with the key to
+	/// pass as `after` for the next page, or `None` once the range is exhausted.
+	///
+	/// # Type Parameters
+	/// - `R`: A type that implements `RangeBounds<String>` for defining the key range.
+	/// - `T`: A type that implements `SurrealValue`, representing the expected value type.
+	///
+	/// # Parameters
+	/// - `range`: The range of keys to scan (e.g., `"a".."z"` or `.."prefix"`).
+	/// - `limit`: The largest number of entries to return, which must be greater than zero.
+	/// - `after`: The continuation key returned with the previous page, or `None` to start.
+	///
+	/// # Errors
+	/// - If transferring data fails.
+	/// - If the FFI call or result reception encounters an issue.
+	/// - If deserializing any result into `T` fails.
+	///
+	/// # Example
+	/// ```rust,ignore
+	/// let mut after = None;
+	/// loop {
+	///     let (page, next) = kv::scan::<_, i64>(.., 100, after)?;
+	///     for (key, value) in page {
+	///         // ...
+	///     }
+	///     match next {
+	///         Some(next) => after = Some(next),
+	///         None => break,
+	///     }
+	/// }
+	/// ```
+	#[allow(clippy::type_complexity)]
+	pub fn scan<R: RangeBounds<String>, T: SurrealValue>(
+		range: R,
+		limit: u64,
+		after: Option<String>,
+	) -> Result<(Vec<(String, T)>, Option<String>)> {
+		let mut controller = Controller {};
+		let range = SerializableRange::from_range_bounds(range)?.transfer(&mut controller)?;
+		let limit = limit.transfer(&mut controller)?;
+		let after = after.transfer(&mut controller)?;
+		let result = unsafe { __sr_kv_scan(*range, *limit, *after) };
+		Result::<(Vec<(String, SerializableArg<T>)>, Option<String>)>::receive(
+			result.try_into()?,
+			&mut controller,
+		)?
+		.map(|(page, next)| (page.into_iter().map(|x| (x.0, x.1.0)).collect(), next))
+	}
+}
+
+/// Module containing random number generation backed by the host.
+///
+/// Randomness is provided by the runtime rather than by WASI, so that hosts can
+/// control the entropy source centrally and, for example, seed it for reproducible runs.
+pub mod rng {
+	use anyhow::Result;
+	use surrealism_types::transfer::Transfer;
+
+	use crate::Controller;
+
+	// Declares external C functions for random number generation.
+	//
+	// # Safety
+	// Assumes valid pointers and correct external implementation.
+	unsafe extern "C" {
+		/// Retrieves a number of random bytes, using a pointer to the requested length.
+		unsafe fn __sr_random(len_ptr: u32) -> i32;
+	}
+
+	/// Fills the provided buffer with random bytes from the host.
+	///
+	/// Buffers longer than [`MAX_RANDOM_BYTES`](surrealism_types::MAX_RANDOM_BYTES) are
+	/// filled with one host call per chunk of that size.
+	///
+	/// # Parameters
+	/// - `dest`: The buffer to fill.
+	///
+	/// # Returns
+	/// A `Result` containing `()` on success, or an error if the operation fails.
+	///
+	/// # Errors
+	/// - If transferring data fails.
+	/// - If the FFI call or result reception encounters an issue.
+	/// - If the host returns a different number of bytes than requested.
+	pub fn fill_bytes(dest: &mut [u8]) -> Result<()> {
+		let mut controller = Controller {};
+		for chunk in dest.chunks_mut(surrealism_types::MAX_RANDOM_BYTES as usize) {
+			let len = (chunk.len() as u64).transfer(&mut controller)?;
+			let result = unsafe { __sr_random(*len) };
+			let bytes = Result::<Vec<u8>>::receive(result.try_into()?, &mut controller)??;
+			if bytes.len() != chunk.len() {
+				anyhow::bail!("Expected {} random bytes, received {}", chunk.len(), bytes.len());
+			}
+			chunk.copy_from_slice(&bytes);
+		}
+		Ok(())
+	}
+
+	/// Returns a random `u64` from the host.
+	///
+	/// # Errors
+	/// - If transferring data fails.
+	/// - If the FFI call or result reception encounters an issue.
+	pub fn next_u64() -> Result<u64> {
+		let mut buf = [0; 8];
+		fill_bytes(&mut buf)?;
+		Ok(u64::from_le_bytes(buf))
+	}
+}
+
+/// Module containing time operations backed by the host.
+///
+/// The clock is provided by the runtime rather than by WASI, so that it remains
+/// available in a restrictive sandbox and hosts can fix or virtualize it for testing.
+pub mod time {
+	use anyhow::Result;
+	use surrealism_types::arg::SerializableArg;
+	use surrealism_types::transfer::Transfer;
+
+	use crate::Controller;
+
+	// Declares external C functions for time operations.
+	//
+	// # Safety
+	// Assumes valid pointers and correct external implementation.
+	unsafe extern "C" {
+		/// Retrieves the current time from the host.
+		unsafe fn __sr_now() -> i32;
+		/// Retrieves the time remaining before the call's deadline from the host.
+		unsafe fn __sr_deadline_remaining() -> i32;
+	}
+
+	/// Returns the current time according to the host.
+	///
+	/// # Errors
+	/// - If the FFI call or result reception encounters an issue.
+	/// - If the host fails to provide the current time.
+	pub fn now() -> Result<surrealdb_types::Datetime> {
+		let mut controller = Controller {};
+		let result = unsafe { __sr_now() };
+		Result::<SerializableArg<surrealdb_types::Datetime>>::receive(
+			result.try_into()?,
+			&mut controller,
+		)?
+		.map(|x| x.0)
+	}
+
+	/// Returns the time remaining before the host's deadline for the current call, or `None`
+	/// if the host did not set one. Once the deadline has passed, this is zero.
+	///
+	/// The deadline is advisory, so that long-running work, such as generating tokens, can
+	/// stop early and return what it has so far. Hosts may still interrupt a call which runs
+	/// for too long, whether or not it checks the deadline.
+	///
+	/// # Errors
+	/// - If the FFI call or result reception encounters an issue.
+	pub fn deadline_remaining() -> Result<Option<std::time::Duration>> {
+		let mut controller = Controller {};
+		let result = unsafe { __sr_deadline_remaining() };
+		Result::<SerializableArg<Option<surrealdb_types::Duration>>>::receive(
+			result.try_into()?,
+			&mut controller,
+		)?
+		.map(|x| x.0.map(Into::into))
+	}
+}
+
+/// Module containing structured logging backed by the host.
+///
+/// Records are passed to the host with their level and attributed to the function being
+/// invoked, so that hosts can route them into their own logging or telemetry. By default,
+/// errors and warnings are written to stderr and other levels to stdout.
+pub mod log {
+	use anyhow::Result;
+	pub use surrealism_types::log::LogLevel;
+	use surrealism_types::transfer::Transfer;
+
+	use crate::Controller;
+
+	// Declares external C functions for logging.
+	//
+	// # Safety
+	// Assumes valid pointers and correct external implementation.
+	unsafe extern "C" {
+		/// Logs a message using pointers to the level and the message.
+		unsafe fn __sr_log(level_ptr: u32, message_ptr: u32) -> i32;
+	}
+
+	/// Logs a message at the given level.
+	///
+	/// # Errors
+	/// - If transferring data fails.
+	/// - If the FFI call or result reception encounters an issue.
+	/// - If the host fails to handle the record.
+	pub fn log<M: Into<String>>(level: LogLevel, message: M) -> Result<()> {
+		let mut controller = Controller {};
+		let level = level.transfer(&mut controller)?;
+		let message = message.into().transfer(&mut controller)?;
+		let result = unsafe { __sr_log(*level, *message) };
+		Result::<()>::receive(result.try_into()?, &mut controller)?
+	}
+
+	/// Logs a message at the error level.
+	pub fn error<M: Into<String>>(message: M) -> Result<()> {
+		log(LogLevel::Error, message)
+	}
+
+	/// Logs a message at the warn level.
+	pub fn warn<M: Into<String>>(message: M) -> Result<()> {
+		log(LogLevel::Warn, message)
+	}
+
+	/// Logs a message at the info level.
+	pub fn info<M: Into<String>>(message: M) -> Result<()> {
+		log(LogLevel::Info, message)
+	}
+
+	/// Logs a message at the debug level.
+	pub fn debug<M: Into<String>>(message: M) -> Result<()> {
+		log(LogLevel::Debug, message)
+	}
+}
+
+/// Module containing filesystem access backed by the host.
+///
+/// WASI filesystem access is disabled by the sandbox, so files are read through the host
+/// instead, and only those allowed by the `allow_fs` capability of the package can be read.
+pub mod fs {
+	use anyhow::Result;
+	use surrealism_types::transfer::Transfer;
+
+	use crate::Controller;
+
+	// Declares external C functions for filesystem operations.
+	//
+	// # Safety
+	// Assumes valid pointers and correct external implementation.
+	unsafe extern "C" {
+		/// Reads a file using a pointer to its path.
+		unsafe fn __sr_fs_read(path_ptr: u32) -> i32;
+	}
+
+	/// Reads the whole file at `path` from the host.
+	///
+	/// # Errors
+	/// - If transferring data fails.
+	/// - If the FFI call or result reception encounters an issue.
+	/// - If the path is not allowed by the `allow_fs` capability of the package.
+	/// - If the file is larger than [`MAX_FS_READ_BYTES`](surrealism_types::MAX_FS_READ_BYTES).
+	/// - If the host fails to read the file.
+	pub fn read<P: Into<String>>(path: P) -> Result<bytes::Bytes> {
+		let mut controller = Controller {};
+		let path = path.into().transfer(&mut controller)?;
+		let result = unsafe { __sr_fs_read(*path) };
+		// The host sends the file as a `Vec<u8>`, which has the same wire format as `Bytes`
+		Result::<bytes::Bytes>::receive(result.try_into()?, &mut controller)?
+	}
+}
+
+/// Module containing request-scoped context provided by the host.
+///
+/// Hosts set context values, such as the current user or session id, on the controller
+/// before invoking a function, so that they need not be passed as function arguments.
+pub mod context {
+	use anyhow::Result;
+	use surrealdb_types::SurrealValue;
+	use surrealism_types::arg::SerializableArg;
+	use surrealism_types::transfer::Transfer;
+
+	use crate::Controller;
+
+	// Declares external C functions for context operations.
+	//
+	// # Safety
+	// Assumes valid pointers and correct external implementation.
+	unsafe extern "C" {
+		/// Retrieves a context value using a key pointer.
+		unsafe fn __sr_ctx_get(key_ptr: u32) -> i32;
+	}
+
+	/// Retrieves the context value set by the host for the specified key.
+	///
+	/// # Returns
+	/// A `Result` containing `Some(R)` if the host set the key, `None` if it didn't,
+	/// or an error if the operation fails.
+	///
+	/// # Errors
+	/// - If transferring data fails.
+	/// - If the FFI call or result reception encounters an issue.
+	/// - If deserializing the result into `R` fails.
+	pub fn get<K: Into<String>, R: SurrealValue>(key: K) -> Result<Option<R>> {
+		let mut controller = Controller {};
+		let key = key.into().transfer(&mut controller)?;
+		let result = unsafe { __sr_ctx_get(*key) };
+		Result::<Option<SerializableArg<R>>>::receive(result.try_into()?, &mut controller)?
+			.map(|x| x.map(|x| x.0))
+	}
 }
@@ -1,10 +1,25 @@
+pub mod abi;
+pub mod build_info;
 pub mod controller;
 pub mod err;
 pub mod imports;
+pub mod manifest;
 pub mod memory;
 pub mod registry;
+pub mod schema;
+pub mod stream;
+#[cfg(all(feature = "testing", not(target_arch = "wasm32")))]
+pub mod testing;
 pub use controller::Controller;
-pub use imports::{kv, run, sql};
+pub use imports::{
+	PackageMeta, RetryPolicy, context, fs, kv, log, meta, rng, run, run_with_retry, sql, sql_many,
+	sql_results, sql_results_with_vars, sql_typed, sql_typed_with_vars, sql_with_binds,
+	sql_with_vars, time,
+};
+#[doc(hidden)]
+pub use inventory;
 pub use registry::SurrealismFunction;
 pub use surrealism_macros::surrealism;
 pub use surrealism_types as types;
+pub use surrealism_types::Value;
+pub use surrealism_types::err::{SqlError, SqlErrorKind};
@@ -0,0 +1,78 @@
+//! The manifest listing every function a module exports.
+//!
+//! Each `#[surrealism]` function submits a [`ManifestEntry`] for every name it is exported
+//! under, and `__sr_manifest` returns all of them in one transfer, so the host can describe
+//! a module without calling the `args`, `returns` and other exports of each function.
+
+use surrealdb_types::{Kind, SurrealValue};
+use surrealism_types::args::Args;
+use surrealism_types::transfer::Transfer;
+
+use crate::Controller;
+
+/// A function as listed in the manifest. Not public API, used by generated code.
+#[doc(hidden)]
+pub struct ManifestEntry {
+	pub namespace: Option<&'static str>,
+	pub name: &'static str,
+	pub args: fn() -> Vec<Kind>,
+	pub arg_names: &'static [&'static str],
+	pub returns: fn() -> Kind,
+	pub doc: Option<&'static str>,
+	pub deprecated: Option<&'static str>,
+}
+
+impl ManifestEntry {
+	pub const fn new<A: Args, R: SurrealValue>(
+		namespace: Option<&'static str>,
+		name: &'static str,
+		arg_names: &'static [&'static str],
+		doc: Option<&'static str>,
+		deprecated: Option<&'static str>,
+	) -> Self {
+		Self {
+			namespace,
+			name,
+			args: A::kinds,
+			arg_names,
+			returns: R::kind_of,
+			doc,
+			deprecated,
+		}
+	}
+}
+
+inventory::collect!(ManifestEntry);
+
+/// A function as transferred to the host: its namespace, name, argument kinds, argument
+/// names, return kind, doc comment and deprecation note.
+type Signature =
+	(Option<String>, String, Vec<Kind>, Vec<String>, Kind, Option<String>, Option<String>);
+
+/// Exports the manifest to the host, with the functions sorted by namespace and name.
+#[unsafe(no_mangle)]
+pub extern "C" fn __sr_manifest() -> i32 {
+	let mut signatures: Vec<Signature> = inventory::iter::<ManifestEntry>
+		.into_iter()
+		.map(|entry| {
+			(
+				entry.namespace.map(str::to_string),
+				entry.name.to_string(),
+				(entry.args)(),
+				entry.arg_names.iter().map(|name| name.to_string()).collect(),
+				(entry.returns)(),
+				entry.doc.map(str::to_string),
+				entry.deprecated.map(str::to_string),
+			)
+		})
+		.collect();
+	signatures.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+	let mut controller = Controller {};
+	match signatures.transfer(&mut controller) {
+		Ok(ptr) => (*ptr).try_into().unwrap_or(-1),
+		Err(e) => {
+			eprintln!("Transfer error: {}", e);
+			-1
+		}
+	}
+}
@@ -1,3 +1,16 @@
+// Pointers are handed to the host as `u32` offsets into linear memory. On a 64-bit
+// target the `usize -> u32` casts below would silently truncate, so only allow
+// building the guest allocator for 32-bit WASM targets.
+#[cfg(target_family = "wasm")]
+const _: () = assert!(
+	std::mem::size_of::<usize>() == std::mem::size_of::<u32>(),
+	"Surrealism guest modules must be compiled for a 32-bit WASM target"
+);
+
+/// The pointer returned for zero-length allocations: non-null and aligned to 8 bytes, like
+/// every other allocation, but never backed by memory.
+const EMPTY_ALLOCATION: u32 = 8;
+
 /// Allocates a block of memory with the specified size and alignment.
 ///
 /// This function is exposed as a C-compatible export (via `extern "C"`) and is not mangled,
@@ -11,6 +24,11 @@
 /// A `u32` representing the starting offset (pointer) of the allocated memory.
 /// Returns `0` if allocation fails (e.g., out-of-memory condition).
 ///
+/// A zero-length allocation does not touch the allocator, as allocating zero bytes is
+/// undefined behaviour for the global allocator. It returns a non-null, aligned sentinel
+/// instead, which must not be read from or written to, and which `__sr_free` accepts with
+/// a length of `0`.
+///
 /// # Panics
 /// Panics if the provided size and alignment do not form a valid `Layout` (e.g., alignment
 /// is not a power of two or size overflows when padded).
@@ -21,6 +39,10 @@
 /// be valid for the WASM linear memory context if used in such environments.
 #[unsafe(no_mangle)]
 pub extern "C" fn __sr_alloc(len: u32) -> u32 {
+	if len == 0 {
+		return EMPTY_ALLOCATION;
+	}
+
 	let layout = match std::alloc::Layout::from_size_align(len as usize, 8) {
 		Ok(layout) => layout,
 		Err(_) => return 0, // invalid layout
@@ -31,6 +53,8 @@ pub extern "C" fn __sr_alloc(len: u32) -> u32 {
 	if ptr.is_null() {
 		0 // signal OOM or allocation failure
 	} else {
+		#[cfg(feature = "alloc-stats")]
+		stats::record_alloc(len);
 		ptr as usize as u32 // cast pointer to offset
 	}
 }
@@ -44,7 +68,8 @@ pub extern "C" fn __sr_alloc(len: u32) -> u32 {
 ///
 /// # Parameters
 /// - `ptr`: The starting offset (pointer) of the memory block to deallocate.
-/// - `len`: The size of the memory block being deallocated, in bytes.
+/// - `len`: The size of the memory block being deallocated, in bytes. Freeing a
+///   zero-length block is a no-op, matching `__sr_alloc`.
 ///
 /// # Safety
 /// This function is unsafe because it performs raw deallocation. The caller must ensure:
@@ -54,6 +79,10 @@ pub extern "C" fn __sr_alloc(len: u32) -> u32 {
 ///   undefined behavior, such as double-free or use-after-free.
 #[unsafe(no_mangle)]
 pub extern "C" fn __sr_free(ptr: u32, len: u32) -> u32 {
+	if len == 0 {
+		return 1; // nothing was allocated
+	}
+
 	let layout = match std::alloc::Layout::from_size_align(len as usize, 8) {
 		Ok(layout) => layout,
 		Err(_) => return 0, // invalid layout - return 0 to indicate failure
@@ -63,5 +92,56 @@ pub extern "C" fn __sr_free(ptr: u32, len: u32) -> u32 {
 	unsafe {
 		std::alloc::dealloc(ptr, layout);
 	}
+	#[cfg(feature = "alloc-stats")]
+	stats::record_free(len);
 	1 // success
 }
+
+/// Tracking of live transfer allocations, for detecting leaks across the FFI boundary.
+///
+/// Only blocks allocated through `__sr_alloc` and released through `__sr_free` are
+/// counted, which covers every value transferred between the guest and the host. Once a
+/// call has completed and its result has been received, the counts should be back to
+/// what they were before the call.
+#[cfg(feature = "alloc-stats")]
+pub mod stats {
+	use std::sync::atomic::{AtomicU64, Ordering};
+
+	use surrealism_types::transfer::Transfer;
+
+	use crate::Controller;
+
+	static LIVE_ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+	static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+
+	pub(crate) fn record_alloc(len: u32) {
+		LIVE_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		LIVE_BYTES.fetch_add(len as u64, Ordering::Relaxed);
+	}
+
+	pub(crate) fn record_free(len: u32) {
+		LIVE_ALLOCATIONS.fetch_sub(1, Ordering::Relaxed);
+		LIVE_BYTES.fetch_sub(len as u64, Ordering::Relaxed);
+	}
+
+	/// The number of live allocations and the number of bytes they hold.
+	pub fn live() -> (u64, u64) {
+		(LIVE_ALLOCATIONS.load(Ordering::Relaxed), LIVE_BYTES.load(Ordering::Relaxed))
+	}
+
+	/// Exports the live allocation counts to the host, as a `(allocations, bytes)` tuple.
+	///
+	/// The counts are read before the tuple itself is transferred, so the allocation used
+	/// to return them is not included.
+	#[unsafe(no_mangle)]
+	pub extern "C" fn __sr_alloc_stats() -> i32 {
+		let mut controller = Controller {};
+		match live().transfer(&mut controller) {
+			Ok(ptr) => (*ptr).try_into().unwrap_or(-1),
+			Err(e) => {
+				eprintln!("Transfer error: {}", e);
+				-1
+			}
+		}
+	}
+}
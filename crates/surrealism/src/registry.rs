@@ -64,17 +64,17 @@ where
 	/// This method uses the `Args` trait to determine the SQL kinds for each argument.
 	///
 	/// # Returns
-	/// A vector of `sql::Kind` representing the argument types.
+	/// A vector of `surrealdb_types::Kind` representing the argument types.
 	pub fn args(&self) -> Vec<surrealdb_types::Kind> {
 		A::kinds()
 	}
 
 	/// Retrieves the kind (type) of the function's return value.
 	///
-	/// This method uses the `KindOf` trait to determine the SQL kind for the return type.
+	/// This method uses the `SurrealValue` trait to determine the SQL kind for the return type.
 	///
 	/// # Returns
-	/// The `sql::Kind` of the return value.
+	/// The `surrealdb_types::Kind` of the return value.
 	pub fn returns(&self) -> surrealdb_types::Kind {
 		R::kind_of()
 	}
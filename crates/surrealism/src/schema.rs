@@ -0,0 +1,93 @@
+//! Idempotent schema definitions.
+//!
+//! Modules which store data usually need tables, fields and indexes to exist before they run
+//! any queries. [`define`] runs `DEFINE` statements so that calling it again, for example
+//! each time the module is initialised, is not an error: definitions which already exist are
+//! skipped, as with `IF NOT EXISTS`, and only the definitions which were created are reported.
+//!
+//! `DEFINE` is not one of the statements a module may run by default, so packages using
+//! [`define`] must allow it in their capabilities, either by listing `DEFINE` in
+//! `allow_statements` or with `allow_arbitrary_queries`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use surrealism::{schema, surrealism};
+//!
+//! #[surrealism(init)]
+//! fn init() -> Result<()> {
+//!     let created = schema::define([
+//!         "DEFINE TABLE user SCHEMAFULL",
+//!         "DEFINE FIELD name ON user TYPE string",
+//!     ])?;
+//!     for statement in created {
+//!         surrealism::log::info(format!("Created: {statement}"))?;
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use anyhow::Result;
+use surrealism_types::err::{PrefixError, SqlError, SqlErrorKind};
+use surrealism_types::statement;
+
+/// Runs `DEFINE` statements in order, skipping the definitions which already exist.
+///
+/// Each statement is run as its own query, so a definition which already exists does not
+/// prevent the following ones from being created. A statement whose query fails with
+/// [`SqlErrorKind::AlreadyExists`] is skipped. Statements must not use `IF NOT EXISTS` or
+/// `OVERWRITE` themselves, as whether they created anything could then not be reported.
+///
+/// # Returns
+/// The statements which created a definition, in the order they were run. This is empty
+/// when every definition already existed.
+///
+/// # Errors
+/// - If a statement is not a single `DEFINE` statement, or uses `IF NOT EXISTS` or
+///   `OVERWRITE`. No statements have been run.
+/// - If a statement fails for any reason other than the definition already existing, for
+///   example because the module is not allowed to run `DEFINE` statements. The statements
+///   before it have been run.
+pub fn define<I, S>(statements: I) -> Result<Vec<String>>
+where
+	I: IntoIterator<Item = S>,
+	S: Into<String>,
+{
+	let statements: Vec<String> = statements.into_iter().map(Into::into).collect();
+	for statement in &statements {
+		validate(statement)?;
+	}
+
+	let mut created = Vec::new();
+	for statement in statements {
+		match crate::sql::<_, surrealdb_types::Value>(statement.as_str()) {
+			Ok(_) => created.push(statement),
+			Err(e) => match e.downcast_ref::<SqlError>() {
+				Some(error) if error.kind() == SqlErrorKind::AlreadyExists => {}
+				_ => return Err(e).prefix_err(|| format!("Failed to run `{statement}`")),
+			},
+		}
+	}
+	Ok(created)
+}
+
+/// Check that a statement is a single definition which reports when it already exists.
+fn validate(statement: &str) -> Result<()> {
+	if statement::count(statement) != 1 {
+		anyhow::bail!("Expected a single DEFINE statement, found `{statement}`");
+	}
+	let words: Vec<String> =
+		statement.split_whitespace().take(5).map(str::to_ascii_uppercase).collect();
+	if words.first().map(String::as_str) != Some("DEFINE") {
+		anyhow::bail!("Expected a DEFINE statement, found `{statement}`");
+	}
+	let options = words.get(2..).unwrap_or_default();
+	if options.starts_with(&["IF".into(), "NOT".into(), "EXISTS".into()])
+		|| options.starts_with(&["OVERWRITE".into()])
+	{
+		anyhow::bail!(
+			"Schema statements are already skipped when they exist, remove IF NOT EXISTS or OVERWRITE from `{statement}`"
+		);
+	}
+	Ok(())
+}
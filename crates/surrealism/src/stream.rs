@@ -0,0 +1,119 @@
+//! Functions returning a stream of values, pulled by the host one at a time.
+//!
+//! A function marked `#[surrealism(stream)]` returns an iterator, which is kept in the module
+//! rather than collected into an array. The host receives a handle to it, and pulls each
+//! value through `__sr_next` until the iterator is exhausted, or releases it early through
+//! `__sr_close`. Values are produced lazily, so a function paginating an external API only
+//! fetches the pages the host asks for.
+//!
+//! ```rust,ignore
+//! #[surrealism(stream)]
+//! fn numbers(count: i64) -> impl Iterator<Item = i64> {
+//!     0..count
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use surrealdb_types::{Kind, SurrealValue, Value};
+use surrealism_types::transfer::Transfer;
+
+use crate::Controller;
+
+type ValueIter = Box<dyn Iterator<Item = Value>>;
+
+thread_local! {
+	/// The open streams, by handle, and the handle given to the next stream opened.
+	static STREAMS: RefCell<(u32, BTreeMap<u32, ValueIter>)> = RefCell::new((1, BTreeMap::new()));
+}
+
+/// An open stream of `T`, described as `array<T>`. Not public API, used by generated code.
+///
+/// The stream is transferred to the host as its handle, while the iterator stays in the
+/// module until it is exhausted or closed.
+#[doc(hidden)]
+pub struct Stream<T> {
+	handle: u32,
+	item: PhantomData<fn() -> T>,
+}
+
+impl<T: SurrealValue + 'static> Stream<T> {
+	/// Keep an iterator in the module, to be pulled by the host.
+	pub fn open<I>(iter: I) -> Self
+	where
+		I: IntoIterator<Item = T>,
+		I::IntoIter: 'static,
+	{
+		let iter: ValueIter = Box::new(iter.into_iter().map(T::into_value));
+		let handle = STREAMS.with_borrow_mut(|(next, streams)| {
+			let handle = *next;
+			*next = next.wrapping_add(1).max(1);
+			streams.insert(handle, iter);
+			handle
+		});
+		Self {
+			handle,
+			item: PhantomData,
+		}
+	}
+}
+
+impl<T> fmt::Debug for Stream<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Stream({})", self.handle)
+	}
+}
+
+impl<T: SurrealValue> SurrealValue for Stream<T> {
+	fn kind_of() -> Kind {
+		Kind::Array(Box::new(T::kind_of()), None)
+	}
+
+	fn is_value(_: &Value) -> bool {
+		false
+	}
+
+	fn into_value(self) -> Value {
+		i64::from(self.handle).into_value()
+	}
+
+	fn from_value(_: Value) -> anyhow::Result<Self> {
+		anyhow::bail!("Streams cannot be received by a module")
+	}
+}
+
+/// Pull the next value of a stream, transferred as `Option<Value>`, which is `None` once the
+/// stream is exhausted. An exhausted stream is closed.
+#[unsafe(no_mangle)]
+pub extern "C" fn __sr_next(handle: u32) -> i32 {
+	// The iterator is taken out of the table while it runs, as it may open streams of its own
+	let next = STREAMS.with_borrow_mut(|(_, streams)| streams.remove(&handle)).map(|mut iter| {
+		let next = iter.next();
+		if next.is_some() {
+			STREAMS.with_borrow_mut(|(_, streams)| streams.insert(handle, iter));
+		}
+		next
+	});
+	let mut controller = Controller {};
+	let result = match next {
+		Some(next) => Ok(next),
+		None => Err(format!("Stream {handle} is not open")),
+	};
+	match result.transfer(&mut controller) {
+		Ok(ptr) => (*ptr).try_into().unwrap_or(-1),
+		Err(e) => {
+			eprintln!("Transfer error: {}", e);
+			-1
+		}
+	}
+}
+
+/// Close a stream before it is exhausted, dropping its iterator. Returns `1` if the stream
+/// was open, and `0` otherwise.
+#[unsafe(no_mangle)]
+pub extern "C" fn __sr_close(handle: u32) -> i32 {
+	STREAMS.with_borrow_mut(|(_, streams)| streams.remove(&handle).is_some()) as i32
+}
@@ -0,0 +1,117 @@
+//! In-process test harness for module authors.
+//!
+//! Compiled for the host target behind the `testing` feature, this module builds the
+//! current crate to WASM, loads it into the Surrealism runtime, and lets tests call its
+//! `#[surrealism]` functions directly, without going through a packaged `.surli` and the CLI.
+//!
+//! ```rust,ignore
+//! #[tokio::test]
+//! async fn can_drive_as_adult() -> anyhow::Result<()> {
+//!     let runtime = surrealism::test_runtime!()?;
+//!     let result = runtime.call("can_drive", (20,)).await?;
+//!     assert_eq!(result, surrealdb_types::Value::Bool(true));
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Host calls made by the module are answered by a [`MockHost`]. Tests script its responses
+//! with `expect_sql` and `expect_run`, and assert on the recorded calls with `calls()`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::Result;
+use surrealism_runtime::config::SurrealismConfig;
+use surrealism_runtime::controller::{Controller, Runtime};
+pub use surrealism_runtime::mock::{Call, Expect, MockHost};
+use surrealism_runtime::package::SurrealismPackage;
+use surrealism_types::args::Args;
+use surrealism_types::err::PrefixError;
+
+/// Build the calling crate and load it into a [`TestRuntime`].
+#[macro_export]
+macro_rules! test_runtime {
+	() => {
+		$crate::testing::TestRuntime::build(env!("CARGO_MANIFEST_DIR"), env!("CARGO_CRATE_NAME"))
+	};
+}
+
+/// A module built from source and loaded into the runtime, ready to be called from tests.
+pub struct TestRuntime {
+	runtime: Runtime,
+	host: MockHost,
+}
+
+impl TestRuntime {
+	/// Build the crate at `manifest_dir` to WASM and load it.
+	///
+	/// The build uses a target directory under the system temp directory, kept between runs
+	/// so that repeated test runs only rebuild what changed. The package config is read from
+	/// `surrealism.toml` when present, and otherwise defaults to a placeholder package.
+	pub fn build(manifest_dir: impl AsRef<Path>, crate_name: &str) -> Result<Self> {
+		let manifest_dir = manifest_dir.as_ref();
+		let target_dir = std::env::temp_dir().join("surrealism-testing").join(crate_name);
+
+		let status = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+			.args(["build", "--target", "wasm32-wasip1", "--release", "--target-dir"])
+			.arg(&target_dir)
+			.current_dir(manifest_dir)
+			.status()
+			.prefix_err(|| "Failed to execute cargo build")?;
+		if !status.success() {
+			anyhow::bail!("Cargo build failed");
+		}
+
+		let wasm_path: PathBuf =
+			target_dir.join("wasm32-wasip1/release").join(format!("{crate_name}.wasm"));
+		let wasm = std::fs::read(&wasm_path)
+			.prefix_err(|| format!("Failed to read WASM file {}", wasm_path.display()))?;
+
+		let config_path = manifest_dir.join("surrealism.toml");
+		let config = if config_path.exists() {
+			SurrealismConfig::parse(
+				&std::fs::read_to_string(&config_path)
+					.prefix_err(|| "Failed to read surrealism.toml")?,
+			)?
+		} else {
+			SurrealismConfig::parse(&format!(
+				"[package]\norganisation = \"test\"\nname = \"{crate_name}\"\nversion = \"0.0.0\"\n"
+			))?
+		};
+
+		let runtime = Runtime::new(SurrealismPackage {
+			config,
+			wasm,
+		})?;
+
+		Ok(Self {
+			runtime,
+			host: MockHost::new(),
+		})
+	}
+
+	/// Use the given host for subsequent calls.
+	pub fn with_host(mut self, host: MockHost) -> Self {
+		self.host = host;
+		self
+	}
+
+	/// Call a function exported by the module. An empty name calls the default function.
+	///
+	/// Each call runs in a fresh instance of the module, so no guest state is kept between
+	/// calls. Use [`TestRuntime::controller`] to make several calls to the same instance.
+	pub async fn call<A: Args>(&self, name: &str, args: A) -> Result<surrealdb_types::Value> {
+		self.controller().await?.invoke(Some(name.to_string()), args).await
+	}
+
+	/// Create an initialised instance of the module, answered by the current host.
+	pub async fn controller(&self) -> Result<Controller> {
+		let mut controller = self
+			.runtime
+			.new_controller(Box::new(self.host.clone()))
+			.await
+			.prefix_err(|| "Failed to load WASM module")?;
+		controller.init().await?;
+		Ok(controller)
+	}
+}
@@ -0,0 +1,160 @@
+//! Drives the demo module through the in-process test harness.
+
+use surrealdb_types::{Array, Number, Object, Value};
+use surrealism::testing::{Call, MockHost, TestRuntime};
+
+fn demo() -> anyhow::Result<TestRuntime> {
+	TestRuntime::build(concat!(env!("CARGO_MANIFEST_DIR"), "/../demo"), "demo")
+}
+
+#[tokio::test]
+async fn can_drive_as_adult() -> anyhow::Result<()> {
+	let runtime = demo()?;
+	assert_eq!(runtime.call("can_drive", (20_i64,)).await?, Value::Bool(true));
+	assert_eq!(runtime.call("can_drive", (16_i64,)).await?, Value::Bool(false));
+	Ok(())
+}
+
+#[tokio::test]
+async fn transfers_10mb_of_bytes_through_the_host() -> anyhow::Result<()> {
+	let data: Vec<u8> = (0..10 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+	let runtime = demo()?;
+	let started = std::time::Instant::now();
+	let result = runtime.call("test_bytes_echo", (Value::Bytes(data.clone().into()),)).await?;
+	eprintln!("Transferred 10MB into the module and back in {:?}", started.elapsed());
+	assert_eq!(result, Value::Bytes(data.into()));
+	Ok(())
+}
+
+#[tokio::test]
+async fn answers_sql_from_mock_host() -> anyhow::Result<()> {
+	let query = "SELECT name, age, enabled FROM user";
+	let user = |name: &str, age: i64| {
+		Value::Object(Object::from_iter([
+			("name".to_string(), Value::String(name.to_string())),
+			("age".to_string(), Value::Number(Number::Int(age))),
+			("enabled".to_string(), Value::Bool(true)),
+		]))
+	};
+	let mock = MockHost::new();
+	mock.expect_sql(query)
+		.returns(Value::Array(Array::from(vec![user("Tobie", 36), user("Jaime", 34)])))
+		.times(1);
+
+	let runtime = demo()?.with_host(mock.clone());
+	let names = runtime.call("test_sql_rows", ()).await?;
+	assert_eq!(
+		names,
+		Value::Array(Array::from(vec![
+			Value::String("Tobie".to_string()),
+			Value::String("Jaime".to_string()),
+		]))
+	);
+	assert_eq!(
+		mock.calls(),
+		vec![Call::Sql {
+			query: query.to_string(),
+			vars: Object::new(),
+		}]
+	);
+	mock.verify()
+}
+
+#[tokio::test]
+async fn single_statement_script_has_one_result() -> anyhow::Result<()> {
+	let row = |id: i64| {
+		Value::Object(Object::from_iter([("id".to_string(), Value::Number(Number::Int(id)))]))
+	};
+	let mock = MockHost::new();
+	mock.expect_sql("SELECT * FROM user")
+		.returns(Value::Array(Array::from(vec![row(1), row(2)])))
+		.times(1);
+
+	let runtime = demo()?.with_host(mock.clone());
+	assert_eq!(runtime.call("test_sql_many_single", ()).await?, Value::Number(Number::Int(1)));
+	mock.verify()
+}
+
+#[tokio::test]
+async fn defining_schema_twice_creates_nothing_the_second_time() -> anyhow::Result<()> {
+	let table = "DEFINE TABLE user SCHEMAFULL";
+	let field = "DEFINE FIELD name ON user TYPE string";
+	let mock = MockHost::new();
+	mock.expect_sql(table).times(1);
+	mock.expect_sql(field).times(1);
+	mock.expect_sql(table).fails("The table 'user' already exists");
+	mock.expect_sql(field).fails("The field 'name' already exists");
+
+	let runtime = demo()?.with_host(mock.clone());
+	let created = Value::Array(Array::from(vec![
+		Value::String(table.to_string()),
+		Value::String(field.to_string()),
+	]));
+	assert_eq!(
+		runtime.call("test_define_schema", ()).await?,
+		Value::Array(Array::from(vec![created, Value::Array(Array::new())]))
+	);
+	mock.verify()
+}
+
+#[tokio::test]
+async fn reset_clears_guest_globals() -> anyhow::Result<()> {
+	let count = |n: i64| Value::Number(Number::Int(n));
+	let mut controller = demo()?.controller().await?;
+	assert_eq!(controller.invoke(Some("test_counter".into()), ()).await?, count(1));
+	assert_eq!(controller.invoke(Some("test_counter".into()), ()).await?, count(2));
+	controller.reset().await?;
+	controller.init().await?;
+	assert_eq!(controller.invoke(Some("test_counter".into()), ()).await?, count(1));
+	Ok(())
+}
+
+#[tokio::test]
+async fn streams_are_pulled_lazily_and_released() -> anyhow::Result<()> {
+	let int = |n: i64| Value::Number(Number::Int(n));
+	let stats = |produced: i64, released: i64| {
+		Value::Array(Array::from(vec![int(produced), int(released)]))
+	};
+	let mut controller = demo()?.controller().await?;
+
+	// Only the values pulled are produced, and closing the stream releases it
+	let mut stream = controller.invoke_stream(Some("test_counted".into()), (100_i64,)).await?;
+	for n in 1..=3 {
+		assert_eq!(stream.next().await.transpose()?, Some(int(n)));
+	}
+	stream.close().await?;
+	assert_eq!(controller.invoke(Some("test_counted_stats".into()), ()).await?, stats(3, 1));
+
+	// A stream dropped without being closed is released before the next call
+	let mut stream = controller.invoke_stream(Some("test_counted".into()), (100_i64,)).await?;
+	assert_eq!(stream.next().await.transpose()?, Some(int(1)));
+	drop(stream);
+	assert_eq!(controller.invoke(Some("test_counted_stats".into()), ()).await?, stats(4, 2));
+	Ok(())
+}
+
+#[tokio::test]
+async fn live_allocations_return_to_baseline() -> anyhow::Result<()> {
+	let untracked = || anyhow::anyhow!("The demo should be built with the alloc-stats feature");
+	let mut controller = demo()?.controller().await?;
+	let before = controller.alloc_stats().await?.ok_or_else(untracked)?;
+	for _ in 0..1000 {
+		controller.invoke(Some("test_initial".into()), ("tobie".to_string(),)).await?;
+	}
+	let after = controller.alloc_stats().await?.ok_or_else(untracked)?;
+	assert_eq!(after, before);
+	Ok(())
+}
+
+#[tokio::test]
+async fn duplicate_vars_bind_the_last_value() -> anyhow::Result<()> {
+	// Answer `RETURN $name` with the variable the host was given
+	let mock =
+		MockHost::new().with_sql(|_, vars| Ok(vars.get("name").cloned().unwrap_or(Value::None)));
+	let runtime = demo()?.with_host(mock);
+	assert_eq!(
+		runtime.call("test_sql_duplicate_vars", ()).await?,
+		Value::String("second".to_string())
+	);
+	Ok(())
+}
@@ -1,4 +1,5 @@
 sh build-cli.sh
 sh build-demo.sh
 
-sh test.sh
\ No newline at end of file
+sh test.sh
+sh test-harness.sh
\ No newline at end of file
@@ -0,0 +1 @@
+cargo test --package surrealism --features testing --test testing
\ No newline at end of file
@@ -1,6 +1,77 @@
 echo "Information for 'demo.surli':"
 ./surrealism info demo.surli
 
+echo ""
+echo "Checking that 'demo.surli' reports the surrealism version it was built against:"
+version=$(sed -n 's/^version = "\(.*\)"$/\1/p' ../crates/surrealism/Cargo.toml | head -n 1)
+./surrealism info demo.surli | grep "Surrealism version: $version"
+
+echo ""
+echo "Information for a package whose 'test_number' function cannot be described, and without a manifest:"
+mkdir -p broken
+tar --zstd -xf demo.surli -C broken
+# Renaming exports keeps their length, so the module stays valid with its integrity updated
+sed -i 's/__sr_manifest/__sr_manifesx/g; s/__sr_args__test_number/__sr_argx__test_number/g' broken/surrealism/mod.wasm
+sha=$(sha256sum broken/surrealism/mod.wasm | cut -d ' ' -f 1)
+sed -i "s/^sha256 = .*/sha256 = \"$sha\"/" broken/surrealism/surrealism.toml
+tar --zstd -cf broken.surli -C broken surrealism
+./surrealism info broken.surli | grep -A 1 -E "<mod>::test_(identity|number|optional_arg)\b"
+
+echo ""
+echo "Information for the same package with '--strict' (expected to fail):"
+./surrealism info --strict broken.surli
+rm -rf broken broken.surli
+
+echo ""
+echo "Checking that the manifest of 'demo.surli' lists every exported function:"
+mkdir -p nomanifest
+tar --zstd -xf demo.surli -C nomanifest
+# Without a manifest, functions are found from their exports instead
+sed -i 's/__sr_manifest/__sr_manifesx/g' nomanifest/surrealism/mod.wasm
+sha=$(sha256sum nomanifest/surrealism/mod.wasm | cut -d ' ' -f 1)
+sed -i "s/^sha256 = .*/sha256 = \"$sha\"/" nomanifest/surrealism/surrealism.toml
+tar --zstd -cf nomanifest.surli -C nomanifest surrealism
+./surrealism info demo.surli | grep "^- <mod>" | sort > manifest.txt
+./surrealism info nomanifest.surli | grep "^- <mod>" | sort > exports.txt
+test -s exports.txt && diff manifest.txt exports.txt && echo "The manifest lists all $(wc -l < exports.txt) exported functions"
+rm -rf nomanifest nomanifest.surli manifest.txt exports.txt
+
+echo ""
+echo "Schema for 'demo.surli':"
+./surrealism schema demo.surli
+
+echo ""
+echo "Verifying 'demo.surli':"
+./surrealism verify demo.surli
+
+echo ""
+echo "Verifying a package whose module does not export 'memory' (expected to fail):"
+mkdir -p nomemory/surrealism
+printf '\0asm\1\0\0\0' > nomemory/surrealism/mod.wasm
+printf '[package]\norganisation = "surrealdb"\nname = "nomemory"\nversion = "1.0.0"\n' > nomemory/surrealism/surrealism.toml
+tar --zstd -cf nomemory.surli -C nomemory surrealism
+./surrealism verify nomemory.surli
+rm -rf nomemory nomemory.surli
+
+echo ""
+echo "Verifying a package whose plain WASM module has no ABI marker (expected to fail with an incompatible module ABI):"
+mkdir -p noabi/surrealism
+printf '\0asm\1\0\0\0\5\3\1\0\1\7\12\1\6memory\2\0' > noabi/surrealism/mod.wasm
+printf '[package]\norganisation = "surrealdb"\nname = "noabi"\nversion = "1.0.0"\n' > noabi/surrealism/surrealism.toml
+tar --zstd -cf noabi.surli -C noabi surrealism
+./surrealism verify noabi.surli
+rm -rf noabi noabi.surli
+
+echo ""
+echo "Verifying a package whose module was modified after building (expected to fail):"
+mkdir -p tampered
+tar --zstd -xf demo.surli -C tampered
+byte=$(dd if=tampered/surrealism/mod.wasm bs=1 skip=64 count=1 2>/dev/null | od -An -tu1 | tr -d ' ')
+printf "\\$(printf '%03o' $((255 - byte)))" | dd of=tampered/surrealism/mod.wasm bs=1 seek=64 conv=notrunc 2>/dev/null
+tar --zstd -cf tampered.surli -C tampered surrealism
+./surrealism verify tampered.surli
+rm -rf tampered tampered.surli
+
 echo ""
 echo "Signature for 'can_drive' in 'demo.surli':"
 ./surrealism sig --fnc can_drive demo.surli
@@ -13,6 +84,10 @@ echo ""
 echo "Running 'can_drive' function with argument 18 in 'demo.surli':"
 ./surrealism run --fnc can_drive --arg 18 demo.surli
 
+echo ""
+echo "Running 'can_drive' function with argument 18, reading 'demo.surli' from stdin:"
+./surrealism run --fnc can_drive --arg 18 - < demo.surli
+
 echo ""
 echo "Running 'result' function with argument false in 'demo.surli':"
 ./surrealism run --fnc result --arg false demo.surli
@@ -25,10 +100,960 @@ echo ""
 echo "Running 'test_kv' function in 'demo.surli':"
 ./surrealism run --fnc test_kv demo.surli
 
+echo ""
+echo "Running 'test_kv_counter' function twice with a persistent KV store (expected 1, then 2):"
+rm -f counter.kv
+./surrealism run --fnc test_kv_counter --kv-path counter.kv demo.surli
+./surrealism run --fnc test_kv_counter --kv-path counter.kv demo.surli
+rm -f counter.kv
+
+echo ""
+echo "Running 'test_kv_counter' function in two modules sharing a namespaced KV store (expected 1, 1, 2, then 1 without namespacing):"
+rm -f shared.kv
+mkdir -p other
+tar --zstd -xf demo.surli -C other
+sed -i 's/^name = "demo"/name = "other"/' other/surrealism/surrealism.toml
+tar --zstd -cf other.surli -C other surrealism
+./surrealism run --fnc test_kv_counter --kv-path shared.kv --kv-namespaced demo.surli
+./surrealism run --fnc test_kv_counter --kv-path shared.kv --kv-namespaced other.surli
+./surrealism run --fnc test_kv_counter --kv-path shared.kv --kv-namespaced demo.surli
+./surrealism run --fnc test_kv_counter --kv-path shared.kv demo.surli
+rm -rf other other.surli shared.kv
+
+echo ""
+echo "Running 'test_kv_counter' function without a persistent KV store (expected 1):"
+./surrealism run --fnc test_kv_counter demo.surli
+
+echo ""
+echo "Running 'test_kv_cached' function with 1, then 2, with a persistent KV store (expected 1, then 1):"
+rm -f cached.kv
+./surrealism run --fnc test_kv_cached --arg 1 --kv-path cached.kv demo.surli
+./surrealism run --fnc test_kv_cached --arg 2 --kv-path cached.kv demo.surli
+rm -f cached.kv
+
+echo ""
+echo "Running 'test_kv_null' function, reading back null and none values and a missing key, in 'demo.surli':"
+./surrealism run --fnc test_kv_null demo.surli
+
+echo ""
+echo "Running 'test_visit' function as a dry run, listing its KV and SQL requests, in 'demo.surli':"
+./surrealism run --fnc test_visit --arg "'home'" --dry-run demo.surli < /dev/null
+
+echo ""
+echo "Signature for 'test_unit' in 'demo.surli':"
+./surrealism sig --fnc test_unit demo.surli
+
+echo ""
+echo "Running 'test_unit' function with argument false in 'demo.surli':"
+./surrealism run --fnc test_unit --arg false demo.surli
+
+echo ""
+echo "Running 'test_unit' function with argument true in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_unit --arg true demo.surli
+
+echo ""
+echo "Running 'test_unit_plain' function with argument 3 in 'demo.surli':"
+./surrealism run --fnc test_unit_plain --arg 3 demo.surli
+
 echo ""
 echo "Running 'test_io' function in 'demo.surli':"
 ./surrealism run --fnc test_io demo.surli
 
 echo ""
 echo "Running 'test_none_value' function in 'demo.surli':"
-./surrealism run --fnc test_none_value demo.surli
\ No newline at end of file
+./surrealism run --fnc test_none_value demo.surli
+echo ""
+echo "Running 'test_record_id' function with a nested record id in 'demo.surli':"
+./surrealism run --fnc test_record_id --arg "user:['tokyo', { year: 2024 }]" demo.surli
+
+echo ""
+echo "Running 'test_record_id' function with array and object record ids in 'demo.surli':"
+./surrealism run --fnc test_record_id --arg "user:['tokyo', 2024]" demo.surli
+./surrealism run --fnc test_record_id --arg "user:{ x: 1 }" demo.surli
+
+echo ""
+echo "Running 'test_record_key' function, which reads the key inside the module, in 'demo.surli':"
+./surrealism run --fnc test_record_key --arg "user:['tokyo', 2024]" demo.surli
+./surrealism run --fnc test_record_key --arg "user:{ x: 1, y: ['a', 2] }" demo.surli
+
+echo ""
+echo "Running 'test_datetime_add' function at the epoch in 'demo.surli':"
+./surrealism run --fnc test_datetime_add --arg "d'1970-01-01T00:00:00Z'" --arg "1d1ns" demo.surli
+
+echo ""
+echo "Running 'test_datetime_add' function in the far future in 'demo.surli':"
+./surrealism run --fnc test_datetime_add --arg "d'9999-12-30T23:59:59.999999999Z'" --arg "1d" demo.surli
+
+echo ""
+echo "Running 'test_env' function without exposing the host environment in 'demo.surli':"
+SURREALISM_SECRET=hidden ./surrealism run --fnc test_env demo.surli
+
+echo ""
+echo "Running 'test_env' function with an allowed environment variable in 'demo.surli':"
+SURREALISM_SECRET=visible ./surrealism run --env SURREALISM_SECRET --fnc test_env demo.surli
+
+echo ""
+echo "Running 'test_random' function twice with the same seed in 'demo.surli':"
+./surrealism run --seed 42 --fnc test_random demo.surli
+./surrealism run --seed 42 --fnc test_random demo.surli
+
+echo ""
+echo "Running 'test_random_fill' function, filling 1MB in several host calls, in 'demo.surli':"
+./surrealism run --fnc test_random_fill --arg 1048576 demo.surli
+
+echo ""
+echo "Running 'test_now' function in 'demo.surli':"
+./surrealism run --fnc test_now demo.surli
+
+echo ""
+echo "Running 'test_generate' function for 5 tokens without a deadline, then with a deadline already too close, in 'demo.surli':"
+./surrealism run --fnc test_generate --arg 5 demo.surli
+./surrealism run --fnc test_generate --arg 5 --deadline 5ms demo.surli
+
+echo ""
+echo "Running 'test_generate' function for 100 tokens of 10ms with a 200ms deadline in 'demo.surli' (expected to stop early):"
+./surrealism run --fnc test_generate --arg 100 --deadline 200ms demo.surli
+
+echo ""
+echo "Running 'test_context' function with context user=alice in 'demo.surli':"
+./surrealism run --fnc test_context --ctx "user='alice'" demo.surli
+
+echo ""
+echo "Running 'test_context' function without context in 'demo.surli':"
+./surrealism run --fnc test_context demo.surli
+
+echo ""
+echo "Running 'test_context_numbers' function with context numbers=[1, 2, 3] in 'demo.surli':"
+./surrealism run --fnc test_context_numbers --ctx "numbers=[1, 2, 3]" demo.surli
+
+echo ""
+echo "Running 'test_context_numbers' function with context numbers=[1, 'two'] in 'demo.surli' (expected to fail, naming none | array<int>):"
+./surrealism run --fnc test_context_numbers --ctx "numbers=[1, 'two']" demo.surli
+
+echo ""
+echo "Running 'test_datetime_max' function, returning the last nanosecond of year 9999, in 'demo.surli':"
+./surrealism run --fnc test_datetime_max demo.surli
+
+echo ""
+echo "Running 'test_datetime_shift' function with a pre-epoch datetime in 'demo.surli':"
+./surrealism run --fnc test_datetime_shift --arg "d'1969-07-20T20:17:40.5Z'" --arg=-365 demo.surli
+
+echo ""
+echo "Running 'test_datetime_shift' function by more days than a duration can hold in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_datetime_shift --arg "d'1969-07-20T20:17:40.5Z'" --arg 9223372036854775807 demo.surli
+
+echo ""
+echo "Running 'test_datetime_invalid' function, decoding an impossible timestamp, in 'demo.surli':"
+./surrealism run --fnc test_datetime_invalid demo.surli
+
+echo ""
+echo "Running 'test_log' function in 'demo.surli':"
+./surrealism run --fnc test_log --arg "''" demo.surli
+
+echo ""
+echo "Signatures of 'test_result_option' and 'test_option_result' in 'demo.surli' (both none | int):"
+./surrealism sig --fnc test_result_option demo.surli
+./surrealism sig --fnc test_option_result demo.surli
+
+echo ""
+echo "Running 'test_result_option' function with a value, NONE, and an error in 'demo.surli':"
+./surrealism run --fnc test_result_option --arg 1 demo.surli
+./surrealism run --fnc test_result_option --arg 0 demo.surli
+./surrealism run --fnc test_result_option --arg=-1 demo.surli
+
+echo ""
+echo "Signature of 'test_result_option_string' in 'demo.surli' (none | int):"
+./surrealism sig --fnc test_result_option_string demo.surli
+
+echo ""
+echo "Running 'test_result_option_string' function with a value, NONE, and an error in 'demo.surli':"
+./surrealism run --fnc test_result_option_string --arg 1 demo.surli
+./surrealism run --fnc test_result_option_string --arg 0 demo.surli
+./surrealism run --fnc test_result_option_string --arg=-1 demo.surli
+
+echo ""
+echo "Running 'test_option_result' function with a value, NONE, and an error in 'demo.surli':"
+./surrealism run --fnc test_option_result --arg 1 demo.surli
+./surrealism run --fnc test_option_result --arg 0 demo.surli
+./surrealism run --fnc test_option_result --arg=-1 demo.surli
+
+echo ""
+echo "Running 'test_bytes' function in 'demo.surli':"
+./surrealism run --fnc test_bytes demo.surli
+
+echo ""
+echo "Signature for 'test_vec_u8' and 'test_vec_u8_reverse' in 'demo.surli', where Vec<u8> is bytes:"
+./surrealism sig --fnc test_vec_u8 demo.surli
+./surrealism sig --fnc test_vec_u8_reverse demo.surli
+
+echo ""
+echo "Running 'test_vec_u8' function, returning vec![1u8, 2, 3] as bytes, in 'demo.surli':"
+./surrealism run --fnc test_vec_u8 demo.surli
+
+echo ""
+echo "Running 'test_vec_u8_reverse' function with argument b\"010203\" in 'demo.surli':"
+./surrealism run --fnc test_vec_u8_reverse --arg 'b"010203"' demo.surli
+
+echo ""
+echo "Running 'test_sha256' function with argument 'abc' in 'demo.surli':"
+./surrealism run --fnc test_sha256 --arg "'abc'" demo.surli
+
+echo ""
+echo "Running 'test_bytes_slice' function with argument b\"0102030405\" in 'demo.surli':"
+./surrealism run --fnc test_bytes_slice --arg 'b"0102030405"' --arg 1 --arg 3 demo.surli
+
+echo ""
+echo "Running 'test_bytes_slice' function past the end in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_bytes_slice --arg 'b"0102030405"' --arg 1 --arg 9 demo.surli
+
+echo ""
+echo "Running 'test_bytes_echo' function, round-tripping 10MB through the host, in 'demo.surli':"
+{ printf 'b"'; head -c 10485760 /dev/urandom | od -An -v -tx1 | tr -d ' \n'; printf '"'; } > bytes.txt
+started=$(date +%s%N)
+./surrealism run --fnc test_bytes_echo --arg-file bytes.txt demo.surli | wc -c
+echo "Took $(( ($(date +%s%N) - started) / 1000000 ))ms"
+rm bytes.txt
+
+echo ""
+echo "Signature for 'test_verify' in 'demo.surli':"
+./surrealism sig --fnc test_verify demo.surli
+
+echo ""
+echo "Running 'test_verify' function with the digest of 'surrealism' in 'demo.surli':"
+./surrealism run --fnc test_verify --arg 'b"188d63e6362153900603985d545744a5ce044a0cb50324d34ad558518ba7ed36"' demo.surli
+
+echo ""
+echo "Running 'test_verify' function with a 4 byte digest in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_verify --arg 'b"01020304"' demo.surli
+
+echo ""
+echo "Running 'test_initial' function with argument 'tobie' in 'demo.surli':"
+./surrealism run --fnc test_initial --arg "'tobie'" demo.surli
+
+echo ""
+echo "Running 'test_repeat_char' function with argument 'a' in 'demo.surli':"
+./surrealism run --fnc test_repeat_char --arg "'a'" --arg 3 demo.surli
+
+echo ""
+echo "Running 'test_repeat_char' function with argument 'ab' in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_repeat_char --arg "'ab'" --arg 3 demo.surli
+
+echo ""
+echo "Running 'test_repeat_char' function with 3 arguments in 'demo.surli' (expected to fail, as it takes 2):"
+./surrealism run --fnc test_repeat_char --arg "'a'" --arg 3 --arg 4 demo.surli
+
+echo ""
+echo "Signatures for 'test_option_vec', 'test_vec_option' and 'test_vec_option_char' in 'demo.surli':"
+./surrealism sig --fnc test_option_vec demo.surli
+./surrealism sig --fnc test_vec_option demo.surli
+./surrealism sig --fnc test_vec_option_char demo.surli
+
+echo ""
+echo "Running 'test_option_vec' function with an array, then NONE, in 'demo.surli':"
+./surrealism run --fnc test_option_vec --arg "[1, 2, 3]" demo.surli
+./surrealism run --fnc test_option_vec --arg NONE demo.surli
+
+echo ""
+echo "Running 'test_vec_option' function with missing elements in 'demo.surli':"
+./surrealism run --fnc test_vec_option --arg "[1, NONE, 3]" demo.surli
+
+echo ""
+echo "Running 'test_vec_option_char' function with all characters, then one missing, in 'demo.surli':"
+./surrealism run --fnc test_vec_option_char --arg "['a', 'b']" demo.surli
+./surrealism run --fnc test_vec_option_char --arg "['a', NONE]" demo.surli
+
+echo ""
+echo "Signature for 'test_greet' in 'demo.surli':"
+./surrealism sig --fnc test_greet demo.surli
+
+echo ""
+echo "Running 'test_greet' function, which returns a static string, in 'demo.surli':"
+./surrealism run --fnc test_greet demo.surli
+
+echo ""
+echo "Running 'test_trim' function, which takes and returns a Cow<str>, in 'demo.surli':"
+./surrealism run --fnc test_trim --arg "'  tobie  '" demo.surli
+
+echo ""
+echo "Signature for 'test_squares' stream function in 'demo.surli':"
+./surrealism sig --fnc test_squares demo.surli
+
+echo ""
+echo "Running 'test_squares' stream function, collecting 100 values, in 'demo.surli':"
+./surrealism run --fnc test_squares --arg 100 demo.surli
+
+echo ""
+echo "Running 'test_squares' stream function, printing values as they arrive, in 'demo.surli':"
+./surrealism run --fnc test_squares --arg 5 --stream demo.surli
+
+echo ""
+echo "Running 'test_squares' stream function, stopping after 3 of 100 values, in 'demo.surli':"
+./surrealism run --fnc test_squares --arg 100 --stream --take 3 demo.surli
+
+echo ""
+echo "Running 'test_pages' stream function in 'demo.surli':"
+./surrealism run --fnc test_pages --arg 3 --arg 2 demo.surli
+
+echo ""
+echo "Running 'test_pages' stream function with an empty page size in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_pages --arg 3 --arg 0 demo.surli
+
+echo ""
+echo "Signature for 'test_rgb' in 'demo.surli':"
+./surrealism sig --fnc test_rgb demo.surli
+
+echo ""
+echo "Running 'test_rgb' function with argument [255, 128, 0] in 'demo.surli':"
+./surrealism run --fnc test_rgb --arg "[255, 128, 0]" demo.surli
+
+echo ""
+echo "Running 'test_rgb' function with argument [255, 128] in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_rgb --arg "[255, 128]" demo.surli
+
+echo ""
+echo "Signature for 'test_u128_echo' in 'demo.surli':"
+./surrealism sig --fnc test_u128_echo demo.surli
+
+echo ""
+echo "Running 'test_u128_max' function in 'demo.surli':"
+./surrealism run --fnc test_u128_max demo.surli
+
+echo ""
+echo "Running 'test_u128_echo' function with u128::MAX in 'demo.surli':"
+./surrealism run --fnc test_u128_echo --arg "'340282366920938463463374607431768211455'" demo.surli
+
+echo ""
+echo "Running 'test_u128_echo' function with u128::MAX + 1 in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_u128_echo --arg "'340282366920938463463374607431768211456'" demo.surli
+
+echo ""
+echo "Running 'test_i128_negate' function with i128::MAX in 'demo.surli':"
+./surrealism run --fnc test_i128_negate --arg "'170141183460469231731687303715884105727'" demo.surli
+
+echo ""
+echo "Signature for 'test_set' in 'demo.surli':"
+./surrealism sig --fnc test_set demo.surli
+
+echo ""
+echo "Running 'test_set' function with an empty array in 'demo.surli':"
+./surrealism run --fnc test_set --arg "[]" demo.surli
+
+echo ""
+echo "Running 'test_set' function with duplicate elements in 'demo.surli':"
+./surrealism run --fnc test_set --arg "[3, 1, 2, 3, 1]" demo.surli
+
+echo ""
+echo "Signature for 'test_unique' in 'demo.surli':"
+./surrealism sig --fnc test_unique demo.surli
+
+echo ""
+echo "Running 'test_unique' function, returning a BTreeSet, with duplicate elements in 'demo.surli':"
+./surrealism run --fnc test_unique --arg "[3, 1, 2, 3, 1]" demo.surli
+
+echo ""
+echo "Signature for 'test_set_union' in 'demo.surli':"
+./surrealism sig --fnc test_set_union demo.surli
+
+echo ""
+echo "Running 'test_set_union' function, taking a HashSet and a BTreeSet, in 'demo.surli':"
+./surrealism run --fnc test_set_union --arg "['b', 'a', 'b']" --arg "{'c', 'a'}" demo.surli
+
+echo ""
+echo "Running 'test_set_union' function with two empty sets in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_set_union --arg "[]" --arg "[]" demo.surli
+
+echo ""
+echo "Signature for 'test_either' in 'demo.surli':"
+./surrealism sig --fnc test_either demo.surli
+
+echo ""
+echo "Running 'test_either' function with a number in 'demo.surli':"
+./surrealism run --fnc test_either --arg "42" demo.surli
+
+echo ""
+echo "Running 'test_either' function with a string in 'demo.surli':"
+./surrealism run --fnc test_either --arg "'hello'" demo.surli
+
+echo ""
+echo "Signature for 'tax' in 'demo.surli':"
+./surrealism sig --fnc tax demo.surli
+
+echo ""
+echo "Running 'tax' function with a decimal in 'demo.surli':"
+./surrealism run --fnc tax --arg "19.99dec" demo.surli
+
+echo ""
+echo "Running 'test_meta' function in 'demo.surli':"
+./surrealism run --fnc test_meta demo.surli
+
+echo ""
+echo "Running 'test_sql_many' function with a two-statement script in 'demo.surli':"
+printf "NONE\n[{ id: demo_module_data:1 }]\n" | ./surrealism run --fnc test_sql_many demo.surli
+
+echo ""
+echo "Running 'test_sql_many_single' function with one statement returning two rows in 'demo.surli':"
+echo "[{ id: user:1 }, { id: user:2 }]" | ./surrealism run --fnc test_sql_many_single demo.surli
+
+echo ""
+echo "Running 'test_sql_results' function with a two-statement script whose second statement fails in 'demo.surli':"
+printf "[{ id: user:tobie }]\n!The table 'missing' does not exist\n" | ./surrealism run --fnc test_sql_results demo.surli
+
+echo ""
+echo "Running 'test_sql_binds' function with a positional bind in 'demo.surli':"
+echo "[{ id: user:tobie, name: 'Tobie' }]" | ./surrealism run --fnc test_sql_binds --arg "'tobie'" demo.surli
+
+echo ""
+echo "Running 'test_sql_rows' function selecting two rows in 'demo.surli':"
+echo "[{ name: 'Tobie', age: 36, enabled: true }, { name: 'Jaime', age: 30, enabled: false }]" | ./surrealism run --fnc test_sql_rows demo.surli
+
+echo ""
+echo "Running 'test_sql_rows' function with a mismatched row in 'demo.surli' (expected to fail):"
+echo "[{ name: 'Tobie' }]" | ./surrealism run --fnc test_sql_rows demo.surli
+
+
+echo ""
+echo "Running 'test_sql_duplicate_vars' function, checking that the last duplicate variable is bound, in 'demo.surli':"
+echo "'second'" | ./surrealism run --fnc test_sql_duplicate_vars demo.surli | grep "^Vars: " | grep -v "'first'" | grep "'second'"
+
+echo ""
+echo "Running 'test_sql_error' function with a failing CREATE in 'demo.surli':"
+echo '!Database record `user:tobie` already exists' | ./surrealism run --fnc test_sql_error demo.surli
+
+echo ""
+echo "Running 'tax' function with an int coerced to a decimal in 'demo.surli':"
+./surrealism run --fnc tax --arg "20" demo.surli
+
+echo ""
+echo "Running 'test_half' function with an int which is exact as a float in 'demo.surli':"
+./surrealism run --fnc test_half --arg "9007199254740992" demo.surli
+
+echo ""
+echo "Running 'test_half' function with i64::MAX, which no float represents exactly, in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_half --arg "9223372036854775807" demo.surli
+
+echo ""
+echo "Running 'can_drive' function with a whole float coerced to an int in 'demo.surli':"
+./surrealism run --fnc can_drive --arg "18.0f" demo.surli
+
+echo ""
+echo "Running 'can_drive' function with a string in 'demo.surli' (expected to fail):"
+./surrealism run --fnc can_drive --arg "'18'" demo.surli
+
+echo ""
+echo "Running 'test_aliases' function through its 'is_adult' alias in 'demo.surli':"
+./surrealism run --fnc is_adult --arg "21" demo.surli
+
+echo ""
+echo "Running 'test_aliases' function through its 'of_age' alias in 'demo.surli':"
+./surrealism run --fnc of_age --arg "12" demo.surli
+
+echo ""
+echo "Signature for the deprecated 'test_deprecated' in 'demo.surli':"
+./surrealism sig --fnc test_deprecated demo.surli
+
+echo ""
+echo "Signature for 'test_record_table' in 'demo.surli':"
+./surrealism sig --fnc test_record_table demo.surli
+
+echo ""
+echo "Running 'test_record_table' function with a record in another table in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_record_table --arg "post:1" demo.surli
+
+echo ""
+echo "Signature for 'test_grid' in 'demo.surli':"
+./surrealism sig --fnc test_grid demo.surli
+
+echo ""
+echo "Running 'test_grid' function with a nested array in 'demo.surli':"
+./surrealism run --fnc test_grid --arg "[[1, 2, 3], [4, 5, 6]]" demo.surli
+
+echo ""
+echo "Signature for 'test_named_args' in 'demo.surli':"
+./surrealism sig --fnc test_named_args demo.surli
+
+echo ""
+echo "Running 'test_named_args' function with a destructured tuple in 'demo.surli':"
+./surrealism run --fnc test_named_args --arg "['Tobie', 'Morgan']" --arg false --arg 35 demo.surli
+
+echo ""
+echo "Signature for 'test_pair' in 'demo.surli':"
+./surrealism sig --fnc test_pair demo.surli
+
+echo ""
+echo "Running 'test_pair' function with argument 'tobie' in 'demo.surli':"
+./surrealism run --fnc test_pair --arg "'tobie'" demo.surli
+
+echo ""
+echo "Signature for 'test_single' in 'demo.surli':"
+./surrealism sig --fnc test_single demo.surli
+
+echo ""
+echo "Running 'test_single' function with argument [1] in 'demo.surli':"
+./surrealism run --fnc test_single --arg "[1]" demo.surli
+
+echo ""
+echo "Running 'test_single' function with argument 1 in 'demo.surli' (expected to fail, as it is not a one element array):"
+./surrealism run --fnc test_single --arg 1 demo.surli
+
+echo ""
+echo "Signature for 'test_sum' in 'demo.surli':"
+./surrealism sig --fnc test_sum demo.surli
+
+echo ""
+echo "Running 'test_sum' function with argument [1, 2, 3] in 'demo.surli':"
+./surrealism run --fnc test_sum --arg "[1, 2, 3]" demo.surli
+
+echo ""
+echo "Signature for 'test_shout' in 'demo.surli':"
+./surrealism sig --fnc test_shout demo.surli
+
+echo ""
+echo "Running 'test_shout' function in 'demo.surli':"
+./surrealism run --fnc test_shout --arg "'hello'" --arg "{ name: 'tobie', age: 36, enabled: true }" --arg "['b', 'a', 'b']" demo.surli
+
+echo ""
+echo "Precompiling 'demo.surli':"
+./surrealism precompile -o demo.cwasm demo.surli
+
+echo ""
+echo "Running 'test_sum' function with argument [1, 2, 3] from the precompiled module:"
+./surrealism run --fnc test_sum --arg "[1, 2, 3]" --precompiled demo.cwasm demo.surli
+
+echo ""
+echo "Running 'test_sum' function from the precompiled module with other engine settings (expected to fail):"
+./surrealism run --fnc test_sum --arg "[1, 2, 3]" --static-memory-maximum-size 1048576 --precompiled demo.cwasm demo.surli
+
+mkdir -p othermodule
+tar --zstd -xf demo.surli -C othermodule
+sed -i 's/__sr_manifest/__sr_manifesx/g' othermodule/surrealism/mod.wasm
+sha=$(sha256sum othermodule/surrealism/mod.wasm | cut -d ' ' -f 1)
+sed -i "s/^sha256 = .*/sha256 = \"$sha\"/" othermodule/surrealism/surrealism.toml
+tar --zstd -cf othermodule.surli -C othermodule surrealism
+
+echo ""
+echo "Running 'test_sum' function from the precompiled module with another package's module (expected to fail):"
+./surrealism run --fnc test_sum --arg "[1, 2, 3]" --precompiled demo.cwasm othermodule.surli
+rm -rf demo.cwasm demo.cwasm.sha256 othermodule othermodule.surli
+
+echo ""
+echo "Signature for 'test_destructured' in 'demo.surli':"
+./surrealism sig --fnc test_destructured demo.surli
+
+echo ""
+echo "Running 'test_destructured' function with argument ['tobie', 36] in 'demo.surli':"
+./surrealism run --fnc test_destructured --arg "['tobie', 36]" demo.surli
+
+echo ""
+echo "Signature for 'test_destructured_mixed' in 'demo.surli':"
+./surrealism sig --fnc test_destructured_mixed demo.surli
+
+echo ""
+echo "Running 'test_destructured_mixed' function in 'demo.surli':"
+./surrealism run --fnc test_destructured_mixed --arg "['tobie', 36]" --arg "'Hello'" --arg "{ name: 'tobie', age: 36, enabled: true }" --arg 0 demo.surli
+
+echo ""
+echo "Running 'test_object_fields' function, reading a string and an int field, in 'demo.surli':"
+./surrealism run --fnc test_object_fields --arg "{ name: 'tobie', age: 36, tags: ['admin'] }" demo.surli
+./surrealism run --fnc test_object_fields --arg "{ name: 'jaime' }" demo.surli
+
+echo ""
+echo "Running 'test_object_fields' function with a string age in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_object_fields --arg "{ name: 'tobie', age: '36' }" demo.surli
+
+echo ""
+echo "Signature for 'test_optional_arg' in 'demo.surli':"
+./surrealism sig --fnc test_optional_arg demo.surli
+
+echo ""
+echo "Running 'test_optional_arg' function with all 3 arguments, then without the optional one, in 'demo.surli' (expected 6, then 3):"
+./surrealism run --fnc test_optional_arg --arg 1 --arg 2 --arg 3 demo.surli
+./surrealism run --fnc test_optional_arg --arg 1 --arg 2 demo.surli
+
+echo ""
+echo "Running 'test_optional_arg' function without a required argument in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_optional_arg --arg 1 demo.surli
+
+echo ""
+echo "Running 'test_identity' function with the same argument as SurrealQL and as JSON in 'demo.surli':"
+sql=$(./surrealism run --fnc test_identity --arg "{ a: 1, b: [1.5, 'x', NULL, true], c: { d: -2 } }" demo.surli)
+json=$(./surrealism run --fnc test_identity --arg-json '{"a":1,"b":[1.5,"x",null,true],"c":{"d":-2}}' demo.surli)
+echo "$sql"
+echo "$json"
+if [ "$sql" = "$json" ]; then
+	echo "Both arguments were received as the same value"
+else
+	echo "The arguments were received as different values"
+fi
+
+echo ""
+echo "Running 'test_identity' and 'test_optional_arg' functions entirely through JSON in 'demo.surli':"
+./surrealism run --json --fnc test_identity --arg-json '{"a":1,"b":[1.5,"x",null,true],"c":{"d":-2}}' demo.surli
+./surrealism run --json --fnc test_optional_arg --arg-json 1 --arg-json 2 demo.surli
+
+echo ""
+echo "Running 'test_identity' function, which takes any value, without an argument in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_identity demo.surli
+
+echo ""
+echo "Running 'test_identity' function with invalid JSON in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_identity --arg-json '{a:1}' demo.surli
+
+echo ""
+echo "Signature for 'test_serde' in 'demo.surli':"
+./surrealism sig --fnc test_serde demo.surli
+
+echo ""
+echo "Running 'test_serde' function with a nested order in 'demo.surli' (expected a total of 7.5):"
+./surrealism run --fnc test_serde --arg "{ id: 'o1', customer: { name: 'Tobie', tags: ['vip'] }, lines: [{ sku: 'a', quantity: 2, price: 1.5 }, { sku: 'b', quantity: 1, price: 4.5 }], note: NONE }" demo.surli
+
+echo ""
+echo "Running 'test_serde' function with the same order as JSON, checking it round-trips in 'demo.surli':"
+order='{"id":"o1","customer":{"name":"Tobie","tags":["vip"]},"lines":[{"sku":"a","quantity":2,"price":1.5},{"sku":"b","quantity":1,"price":4.5}],"note":null}'
+result=$(./surrealism run --fnc test_serde --arg-json "$order" demo.surli)
+expected=$(./surrealism run --fnc test_identity --arg-json "{\"order\":$order,\"total\":7.5}" demo.surli)
+echo "$result"
+if [ "$result" = "$expected" ]; then
+	echo "The order round-tripped unchanged"
+else
+	echo "The order changed when round-tripped"
+fi
+
+echo ""
+echo "Running 'test_serde' function with a missing field in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_serde --arg "{ id: 'o1', lines: [] }" demo.surli
+
+echo ""
+echo "Running 'test_serde_unserializable' function, whose result has no JSON form, in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_serde_unserializable demo.surli
+
+echo ""
+echo "Signature for 'test_number' in 'demo.surli':"
+./surrealism sig --fnc test_number demo.surli
+
+echo ""
+echo "Running 'test_number' function with an int, a float and a decimal in 'demo.surli':"
+./surrealism run --fnc test_number --arg 5 demo.surli
+./surrealism run --fnc test_number --arg 5.0 demo.surli
+./surrealism run --fnc test_number --arg 5dec demo.surli
+
+echo ""
+echo "Signature for 'test_dynamic' in 'demo.surli':"
+./surrealism sig --fnc test_dynamic demo.surli
+
+echo ""
+echo "Running 'test_dynamic' function, which returns an int or a string, with 4 and 5 in 'demo.surli':"
+./surrealism run --fnc test_dynamic --arg 4 demo.surli
+./surrealism run --fnc test_dynamic --arg 5 demo.surli
+
+echo ""
+echo "Signature for 'test_geometry_point' in 'demo.surli':"
+./surrealism sig --fnc test_geometry_point demo.surli
+
+echo ""
+echo "Running 'test_geometry_point' function with a point in 'demo.surli':"
+./surrealism run --fnc test_geometry_point --arg "(1.5, 2)" demo.surli
+
+echo ""
+echo "Running 'test_geometry_point' function with a GeoJSON point in 'demo.surli':"
+./surrealism run --fnc test_geometry_point --arg "{ type: 'Point', coordinates: [1, 2] }" demo.surli
+
+echo ""
+echo "Running 'test_geometry_point' function with a GeoJSON polygon in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_geometry_point --arg "{ type: 'Polygon', coordinates: [[[0, 0], [1, 0], [1, 1], [0, 0]]] }" demo.surli
+
+echo ""
+echo "Signature for 'test_geometry_kind' in 'demo.surli':"
+./surrealism sig --fnc test_geometry_kind demo.surli
+
+echo ""
+echo "Running 'test_geometry_kind' function with a GeoJSON polygon in 'demo.surli':"
+./surrealism run --fnc test_geometry_kind --arg "{ type: 'Polygon', coordinates: [[[0, 0], [1, 0], [1, 1], [0, 0]]] }" demo.surli
+
+echo ""
+echo "Signature for 'test_range_len' in 'demo.surli':"
+./surrealism sig --fnc test_range_len demo.surli
+
+echo ""
+echo "Running 'test_range_len' function with the ranges 1..5 and 1..=5 in 'demo.surli':"
+./surrealism run --fnc test_range_len --arg "1..5" demo.surli
+./surrealism run --fnc test_range_len --arg "1..=5" demo.surli
+
+echo ""
+echo "Running 'test_range_len' function with argument 5 in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_range_len --arg 5 demo.surli
+
+echo ""
+echo "Signature for the pure 'can_drive' function in 'demo.surli':"
+./surrealism sig --fnc can_drive demo.surli
+
+echo ""
+echo "Signature for 'other' in the 'math' namespace in 'demo.surli':"
+./surrealism sig --namespace math --fnc other demo.surli
+
+echo ""
+echo "Running 'other' in the 'math' namespace with argument 21 in 'demo.surli':"
+./surrealism run --namespace math --fnc other --arg 21 demo.surli
+
+echo ""
+echo "Running 'test_recursion' function 10000 calls deep with a 64 KiB stack in 'demo.surli' (expected to fail with a trap):"
+# Every native frame takes at least 16 bytes, so 10000 of them never fit in 64 KiB
+./surrealism run --fnc test_recursion --arg 10000 --max-wasm-stack 65536 demo.surli
+
+echo ""
+echo "Running 'test_recursion' function 10000 calls deep with an 8 MiB stack in 'demo.surli':"
+./surrealism run --fnc test_recursion --arg 10000 --max-wasm-stack 8388608 demo.surli
+
+echo ""
+echo "Running 'test_panic' function, which panics, in 'demo.surli' (expected to fail with a trap):"
+./surrealism run --fnc test_panic --arg "'Something went wrong'" demo.surli
+
+echo ""
+echo "Running 'test_divide' function dividing 1 by 0 in 'demo.surli' (expected to fail with a trap naming the function):"
+./surrealism run --fnc test_divide --arg 1 --arg 0 demo.surli
+
+echo ""
+echo "Running 'test_wrong_return' function, which returns a string for an int, in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_wrong_return demo.surli
+
+echo ""
+echo "Running 'test_oversized_array' function, which the host should reject, in 'demo.surli':"
+./surrealism run --fnc test_oversized_array demo.surli
+
+echo ""
+echo "Running 'test_overflowing_length' function, which the host should reject, in 'demo.surli':"
+./surrealism run --fnc test_overflowing_length demo.surli
+
+echo ""
+echo "Running 'test_overflowing_range' function, whose bound lengths overflow, in 'demo.surli':"
+./surrealism run --fnc test_overflowing_range demo.surli
+
+echo ""
+echo "Running 'test_out_of_bounds_pointer' function, which the host should reject as out of bounds, in 'demo.surli':"
+./surrealism run --fnc test_out_of_bounds_pointer demo.surli
+
+echo ""
+echo "Running 'test_out_of_bounds_length' function, which the host should reject as out of bounds, in 'demo.surli':"
+./surrealism run --fnc test_out_of_bounds_length demo.surli
+
+echo ""
+echo "Running 'test_random_oversized' function, requesting 1GB of random bytes at once, in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_random_oversized --arg 1073741824 demo.surli
+
+echo ""
+echo "Running 'test_log' function with a 16 byte transfer limit in 'demo.surli' (expected to fail):"
+./surrealism run --max-transfer-bytes 16 --fnc test_log demo.surli
+
+echo ""
+echo "Running 'test_kv' function, whose get_batch sends 3 keys, with a 2 element array limit in 'demo.surli' (expected to fail):"
+./surrealism run --max-array-len 2 --fnc test_kv demo.surli
+
+echo ""
+echo "Running 'test_trim' function with an argument larger than the memory limit in 'demo.surli' (expected to fail, out of memory):"
+printf "'%s'" "$(head -c 33554432 /dev/zero | tr '\0' a)" > large.txt
+./surrealism run --fnc test_trim --arg-file large.txt --max-memory 16777216 demo.surli
+rm large.txt
+
+echo ""
+echo "Running the default function with --default in 'demo.surli':"
+./surrealism run --default --arg 21 demo.surli
+
+echo ""
+echo "Running the default function with an argument from stdin in 'demo.surli':"
+echo "17" | ./surrealism run --default --arg-stdin demo.surli
+
+echo ""
+echo "Running 'safe_divide' function with an argument from a file in 'demo.surli':"
+echo "4" > divisor.surql
+./surrealism run --fnc safe_divide --arg 12 --arg-file divisor.surql demo.surli
+rm divisor.surql
+
+echo ""
+echo "Running with both --fnc and --default in 'demo.surli' (expected to fail):"
+./surrealism run --fnc can_drive --default --arg 21 demo.surli
+
+echo ""
+echo "Running 'test_sql_query' function with a REMOVE TABLE, in a package not allowed arbitrary queries (expected to fail):"
+mkdir -p restricted
+tar --zstd -xf demo.surli -C restricted
+sed -i 's/allow_arbitrary_queries = true/allow_arbitrary_queries = false/' restricted/surrealism/surrealism.toml
+tar --zstd -cf restricted.surli -C restricted surrealism
+./surrealism run --fnc test_sql_query --arg "'REMOVE TABLE user'" restricted.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_query' function with a SELECT, in a package not allowed arbitrary queries:"
+echo "[{ id: user:tobie }]" | ./surrealism run --fnc test_sql_query --arg "'SELECT * FROM user'" restricted.surli
+rm -rf restricted restricted.surli
+
+echo ""
+echo "Information for a package which declares no capabilities:"
+mkdir -p nocaps
+tar --zstd -xf demo.surli -C nocaps
+awk '/^\[/ { skip = ($0 == "[capabilities]") } !skip' nocaps/surrealism/surrealism.toml > nocaps.toml
+mv nocaps.toml nocaps/surrealism/surrealism.toml
+tar --zstd -cf nocaps.surli -C nocaps surrealism
+./surrealism info nocaps.surli | sed -n '/^Capabilities:/,$p'
+
+echo ""
+echo "Running 'test_sql_query' function with a SELECT, in a package which declares no capabilities:"
+echo "[{ id: user:tobie }]" | ./surrealism run --fnc test_sql_query --arg "'SELECT * FROM user'" nocaps.surli
+
+echo ""
+echo "Running 'test_sql_query' function with a REMOVE TABLE, in a package which declares no capabilities (expected to fail):"
+./surrealism run --fnc test_sql_query --arg "'REMOVE TABLE user'" nocaps.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_query' function with a DELETE hidden after a regex, in a package which declares no capabilities (expected to fail):"
+./surrealism run --fnc test_sql_query --arg "\"SELECT * FROM user WHERE name = /'/; DELETE user; SELECT '/'\"" nocaps.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_query' function with a DELETE in nested blocks, in a package which declares no capabilities (expected to fail):"
+./surrealism run --fnc test_sql_query --arg "'IF true { IF true { DELETE user } }'" nocaps.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_query' function with a DELETE after quoted identifiers, in a package which declares no capabilities (expected to fail):"
+./surrealism run --fnc test_sql_query --arg "'SELECT \`a;b\` FROM ⟨x;y⟩; DELETE user'" nocaps.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_query' function with an unterminated identifier, in a package which declares no capabilities (expected to fail):"
+./surrealism run --fnc test_sql_query --arg "'SELECT * FROM ⟨user; DELETE user'" nocaps.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_query' function with a field named 'delete', which the scanner reports, in a package which declares no capabilities (expected to fail):"
+./surrealism run --fnc test_sql_query --arg "'SELECT id, delete FROM user'" nocaps.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_query' function with DELETE only inside a string, in a package which declares no capabilities:"
+echo "[]" | ./surrealism run --fnc test_sql_query --arg "\"SELECT * FROM user WHERE name = 'it\\\\'s; DELETE user'\"" nocaps.surli
+rm -rf nocaps nocaps.surli
+
+echo ""
+echo "Running 'test_fs_read' function with a file in the allowed 'fixtures' directory in 'demo.surli':"
+mkdir -p fixtures
+echo "Hello from a file" > fixtures/hello.txt
+echo "Not for modules" > secret.txt
+./surrealism run --fnc test_fs_read --arg "'fixtures/hello.txt'" demo.surli
+
+echo ""
+echo "Running 'test_fs_read' function with a file outside of 'fixtures' in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_fs_read --arg "'secret.txt'" demo.surli
+
+echo ""
+echo "Running 'test_fs_read' function with a path stepping out of 'fixtures' in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_fs_read --arg "'fixtures/../secret.txt'" demo.surli
+
+echo ""
+echo "Running 'test_fs_read' function with a link in 'fixtures' to a file outside of it in 'demo.surli' (expected to fail):"
+ln -s ../secret.txt fixtures/link.txt
+./surrealism run --fnc test_fs_read --arg "'fixtures/link.txt'" demo.surli
+
+echo ""
+echo "Running 'test_fs_read' function with a file larger than the read limit in 'demo.surli' (expected to fail):"
+head -c 17000000 /dev/zero > fixtures/large.txt
+./surrealism run --fnc test_fs_read --arg "'fixtures/large.txt'" demo.surli
+
+mkdir -p wholedir
+tar --zstd -xf demo.surli -C wholedir
+sed -i 's/^allow_fs = .*/allow_fs = ["."]/' wholedir/surrealism/surrealism.toml
+tar --zstd -cf wholedir.surli -C wholedir surrealism
+
+echo ""
+echo "Running 'test_fs_read' function in a package allowing '.' (expected to fail):"
+./surrealism run --fnc test_fs_read --arg "'fixtures/hello.txt'" wholedir.surli
+rm -rf fixtures secret.txt wholedir wholedir.surli
+
+echo ""
+echo "Building 'demo' with allow_net = [\"api.example.com\"] and reading its capabilities back:"
+# Build a copy of the demo within a copy of the workspace, leaving the tracked surrealism.toml as it is
+mkdir -p netdemo/crates
+cp ../Cargo.toml ../LICENSE netdemo
+cp -r ../crates/demo netdemo/crates/demo
+for crate in surrealism surrealism-cli surrealism-macros surrealism-runtime surrealism-types; do
+	ln -s "$(realpath ../crates/$crate)" netdemo/crates/$crate
+done
+sed -i 's/^allow_net = .*/allow_net = ["api.example.com"]/' netdemo/crates/demo/surrealism.toml
+CARGO_TARGET_DIR="$(realpath ../target)" ./surrealism build -o net.surli netdemo/crates/demo > /dev/null
+./surrealism info net.surli | sed -n '/^Capabilities:/,$p'
+rm -rf netdemo net.surli
+
+echo ""
+echo "Building 'demo' with SOURCE_DATE_EPOCH=0 and reading its build time back:"
+SOURCE_DATE_EPOCH=0 ./surrealism build -o epoch.surli ../crates/demo > /dev/null
+./surrealism info epoch.surli | grep "Built at"
+rm epoch.surli
+
+echo ""
+echo "Running 'test_sql_rows' function with scripted responses and no stdin in 'demo.surli':"
+cat > responses.json <<'JSON'
+{
+	"sql": [
+		{ "query": "SELECT name, age, enabled FROM user", "result": [{ "name": "Tobie", "age": 36, "enabled": true }] }
+	]
+}
+JSON
+./surrealism run --fnc test_sql_rows --responses responses.json demo.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_typed' function with a row missing its 'age' field in 'demo.surli' (expected to fail, naming row 1 and 'age'):"
+cat > responses.json <<'JSON'
+{
+	"sql": [
+		{
+			"query": "SELECT name, age, enabled FROM user",
+			"result": [{ "name": "Tobie", "age": 36, "enabled": true }, { "name": "Jaime", "enabled": true }]
+		}
+	]
+}
+JSON
+./surrealism run --fnc test_sql_typed --responses responses.json demo.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_error' function with a scripted error and no stdin in 'demo.surli':"
+cat > responses.json <<'JSON'
+{ "sql": [{ "query": "CREATE user:tobie", "error": "Database record `user:tobie` already exists" }] }
+JSON
+./surrealism run --fnc test_sql_error --responses responses.json demo.surli < /dev/null
+
+echo ""
+echo "Running 'test_define_schema' function, defining the schema twice, in 'demo.surli':"
+cat > responses.json <<'JSON'
+{
+	"sql": [
+		{ "query": "DEFINE TABLE user SCHEMAFULL", "result": null },
+		{ "query": "DEFINE FIELD name ON user TYPE string", "result": null },
+		{ "query": "DEFINE TABLE user SCHEMAFULL", "error": "The table 'user' already exists" },
+		{ "query": "DEFINE FIELD name ON user TYPE string", "error": "The field 'name' already exists" }
+	]
+}
+JSON
+./surrealism run --fnc test_define_schema --responses responses.json demo.surli < /dev/null
+
+echo ""
+echo "Running 'test_define_schema_overwrite' function, using OVERWRITE, in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_define_schema_overwrite demo.surli < /dev/null
+
+echo ""
+echo "Running 'test_define_schema_script' function, with two statements in one, in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_define_schema_script demo.surli < /dev/null
+
+echo ""
+echo "Running 'test_run_retry' function with two scripted failures before a success in 'demo.surli':"
+cat > responses.json <<'JSON'
+{
+	"run": [
+		{ "fnc": "fn::flaky", "error": "Connection reset" },
+		{ "fnc": "fn::flaky", "error": "Connection reset" },
+		{ "fnc": "fn::flaky", "result": "Hello" }
+	]
+}
+JSON
+./surrealism run --fnc test_run_retry --arg 3 --responses responses.json demo.surli < /dev/null
+
+echo ""
+echo "Running 'test_run_retry' function with fewer attempts than failures in 'demo.surli' (expected to fail):"
+./surrealism run --fnc test_run_retry --arg 2 --responses responses.json demo.surli < /dev/null
+
+echo ""
+echo "Running 'test_sql_many' function with no scripted responses left in 'demo.surli' (expected to fail):"
+echo "{}" > responses.json
+./surrealism run --fnc test_sql_many --responses responses.json demo.surli < /dev/null
+rm responses.json